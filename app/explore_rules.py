@@ -0,0 +1,58 @@
+import argparse
+
+from py_ltl_engine import PyConfig, PyNeighbourhood
+
+
+def parse_args() -> argparse.Namespace:
+    parser = argparse.ArgumentParser(
+        description="Sweep the rule space and rank rules by how interesting their soups are."
+    )
+    parser.add_argument("--count", type=int, default=20, help="number of random rules")
+    parser.add_argument("--seed", type=int, default=0, help="seed of the random rules")
+    parser.add_argument(
+        "--rr", type=int, nargs="+", help="ranges of a grid sweep instead of random rules"
+    )
+    parser.add_argument("--cc", type=int, default=0)
+    parser.add_argument("--mm", type=int, nargs="+", choices=[0, 1])
+    parser.add_argument("--nn", nargs="+", choices=["NM", "NN"])
+    parser.add_argument("--steps", type=int, default=4, help="grid steps of each interval")
+    parser.add_argument("--soups", type=int, default=4, help="soups run for each rule")
+    parser.add_argument("--board-size", type=int, default=64)
+    parser.add_argument("--soup-size", type=int, default=16)
+    parser.add_argument("--max-generations", type=int, default=500)
+    parser.add_argument("--max-period", type=int, default=32)
+    parser.add_argument("--no-census", action="store_true")
+    parser.add_argument("--threads", type=int, default=None)
+    parser.add_argument("--format", choices=["json", "text"], default="text")
+    parser.add_argument("--output", help="file to write the catalogue to")
+    return parser.parse_args()
+
+
+def main():
+    args = parse_args()
+    catalogue = PyConfig.sweep(
+        count=args.count,
+        seed=args.seed,
+        rr=args.rr,
+        cc=args.cc,
+        mm=args.mm,
+        nn=None if args.nn is None else [PyNeighbourhood(nn) for nn in args.nn],
+        steps=args.steps,
+        soups=args.soups,
+        board_size=args.board_size,
+        soup_size=args.soup_size,
+        max_generations=args.max_generations,
+        max_period=args.max_period,
+        census=not args.no_census,
+        threads=args.threads,
+        format=args.format,
+    )
+    if args.output is None:
+        print(catalogue, end="")
+    else:
+        with open(args.output, "w") as f:
+            f.write(catalogue)
+
+
+if __name__ == "__main__":
+    main()
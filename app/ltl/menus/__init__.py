@@ -12,3 +12,4 @@ from .create_manual_config import create_manual_config
 from .choose_configuration import choose_configuration
 from .existing_configurations import existing_configurations
 from .choose_existing_configurations import choose_existing_configurations
+from .choose_preset import choose_preset
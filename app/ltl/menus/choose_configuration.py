@@ -5,6 +5,7 @@ from .board import board
 from .static_menu import static_menu
 from .create_manual_config import create_manual_config
 from .choose_existing_configurations import choose_existing_configurations
+from .choose_preset import choose_preset
 
 from ..state import state
 from ..handlers import handle_submit
@@ -17,4 +18,5 @@ def choose_configuration(menu: Base):
     )
     menu.add.button("Create manual", create_manual_config())
     menu.add.button("Choose existing", choose_existing_configurations())
+    menu.add.button("Choose preset", choose_preset())
     menu.add.button("Back", pygame_menu.events.BACK)
@@ -0,0 +1,19 @@
+import pygame_menu
+
+from py_ltl_engine import PyConfig
+
+from .base import Base
+from .board import board
+from .static_menu import static_menu
+
+from ..state import state
+from ..handlers import handle_submit
+
+
+@static_menu()
+def choose_preset(menu: Base):
+    for preset in PyConfig.presets():
+        menu.add.button(preset["name"], board()).add_update_callback(
+            handle_submit(lambda w: state.set_config(PyConfig.preset(w.get_title())))
+        )
+    menu.add.button("Back", pygame_menu.events.BACK)
@@ -1,4 +1,5 @@
 from enum import Enum
+from typing import Any, BinaryIO, Optional, Union
 
 class PyNeighbourhood(Enum):
     def __init__(self, neighbourhood: str) -> PyNeighbourhood: ...
@@ -15,6 +16,77 @@ class PyConfig(object):
         nn: PyNeighbourhood,
     ) -> None: ...
     def randomize(self, seed: int = ...) -> PyConfig: ...
+    @classmethod
+    def preset(cls, name: str) -> PyConfig: ...
+    @classmethod
+    def presets(cls) -> list[dict[str, Any]]: ...
+    def soup_search(
+        self,
+        soups: int = 100,
+        first_seed: int = 0,
+        board_size: int = 64,
+        soup_size: int = 16,
+        max_generations: int = 1000,
+        max_period: int = 32,
+        threads: Optional[int] = None,
+        examples: int = 3,
+        format: str = "json",
+    ) -> str: ...
+    def classify_behaviour(
+        self,
+        soups: int = 4,
+        first_seed: int = 0,
+        board_size: int = 64,
+        soup_size: int = 16,
+        max_generations: int = 500,
+        max_period: int = 32,
+        census: bool = True,
+    ) -> dict[str, Any]: ...
+    def phase_diagram(
+        self,
+        steps: int = 8,
+        ss: Optional[list[tuple[int, int]]] = None,
+        bb: Optional[list[tuple[int, int]]] = None,
+        seeds: int = 4,
+        first_seed: int = 0,
+        board_size: int = 64,
+        soup_size: int = 64,
+        max_generations: int = 500,
+        max_period: int = 32,
+        threads: Optional[int] = None,
+        csv: Optional[str] = None,
+        heatmap: Optional[str] = None,
+        metric: str = "density",
+        scale: int = 8,
+    ) -> dict[str, Any]: ...
+    def search_oscillators(
+        self,
+        width: int = 8,
+        height: int = 8,
+        period: int = 1,
+        max_solutions: int = 1,
+        max_conflicts: Optional[int] = None,
+        padding: Optional[int] = None,
+    ) -> dict[str, Any]: ...
+    @classmethod
+    def sweep(
+        cls,
+        count: int = 20,
+        seed: int = 0,
+        rr: Optional[list[int]] = None,
+        cc: int = 0,
+        mm: Optional[list[int]] = None,
+        nn: Optional[list[PyNeighbourhood]] = None,
+        steps: int = 4,
+        soups: int = 4,
+        board_size: int = 64,
+        soup_size: int = 16,
+        max_generations: int = 500,
+        max_period: int = 32,
+        census: bool = True,
+        threads: Optional[int] = None,
+        format: str = "json",
+    ) -> str: ...
     @property
     def rr(self) -> int: ...
     @property
@@ -30,8 +102,20 @@ class PyConfig(object):
 
 class PyBoard(object):
     def __init__(self, size: int, config: PyConfig): ...
+    @staticmethod
+    def from_image(
+        source: Union[str, bytes],
+        size: int,
+        config: PyConfig,
+        fit: str = "scale",
+        threshold: int = 128,
+        palette: Optional[list[tuple[int, ...]]] = None,
+    ) -> PyBoard: ...
     @property
     def board(self) -> list[list[int]]: ...
+    @property
+    def __array_interface__(self) -> dict[str, Any]: ...
+    def set_cells(self, cells: Any) -> None: ...
     def reset(self): ...
     def get_cell(self, x: int, y: int) -> int: ...
     def set_cell(self, x: int, y: int, value: int) -> None: ...
@@ -39,3 +123,159 @@ class PyBoard(object):
     def cell_up(self, x: int, y: int) -> None: ...
     def cell_down(self, x: int, y: int) -> None: ...
     def update(self) -> None: ...
+    @property
+    def generation(self) -> int: ...
+    @property
+    def stats(self) -> dict[str, Any]: ...
+    def refresh_stats(self) -> None: ...
+    def record_stats(self, capacity: int) -> None: ...
+    def stats_history(
+        self, format: Optional[str] = None
+    ) -> Union[list[dict[str, Any]], str]: ...
+    def save_stats(self, path: str) -> None: ...
+    def explain_cell(self, x: int, y: int) -> dict[str, Any]: ...
+    def neighbour_counts(self) -> list[list[int]]: ...
+    def record_activity(self, enabled: bool = True) -> None: ...
+    def reset_activity(self) -> None: ...
+    def activity(self, layer: str = "changes") -> list[list[Optional[int]]]: ...
+    def activity_summary(self) -> dict[str, Any]: ...
+    def track_ages(self, enabled: bool = True) -> None: ...
+    def reset_ages(self) -> None: ...
+    def ages(self) -> list[list[int]]: ...
+    def get_age(self, x: int, y: int) -> Optional[int]: ...
+    def activity_heatmap(
+        self, layer: str = "changes", scale: int = 1, format: Optional[str] = None
+    ) -> bytes: ...
+    @property
+    def hash(self) -> int: ...
+    def set_hash_history(self, capacity: int) -> None: ...
+    def detect_cycle(self, max_period: int = 64) -> Optional[tuple[int, int]]: ...
+    def detect_translation(self, max_period: int = 64) -> Optional[dict[str, Any]]: ...
+    def census(
+        self,
+        radius: Optional[int] = None,
+        max_period: int = 32,
+        max_generations: int = 64,
+        padding: Optional[int] = None,
+        summary: bool = False,
+    ) -> list[dict[str, Any]]: ...
+    def pattern_code(
+        self, region: Optional[tuple[int, int, int, int]] = None
+    ) -> str: ...
+    def place_code(self, code: str, x: int, y: int) -> None: ...
+    def run_until_periodic(
+        self, max_generations: int, max_period: int = 64
+    ) -> Optional[tuple[int, int]]: ...
+    def compare(self, other: PyBoard) -> dict[str, Any]: ...
+    def twin_run(
+        self,
+        generations: int,
+        cells: Optional[list[tuple[int, int]]] = None,
+        count: int = 1,
+        radius: int = 0,
+        seed: int = 0,
+    ) -> dict[str, Any]: ...
+    def find_pattern(
+        self,
+        pattern: Union[str, list[list[Optional[int]]]],
+        symmetries: Optional[list[str]] = None,
+    ) -> list[dict[str, Any]]: ...
+    def find_predecessor(
+        self,
+        region: Optional[tuple[int, int, int, int]] = None,
+        margin: Optional[int] = None,
+        exact: bool = False,
+        max_conflicts: Optional[int] = None,
+    ) -> dict[str, Any]: ...
+    def render_image(
+        self,
+        scale: int = 1,
+        palette: Optional[list[tuple[int, ...]]] = None,
+        format: Optional[str] = None,
+    ) -> bytes: ...
+    def save_image(
+        self,
+        path: str,
+        scale: int = 1,
+        palette: Optional[list[tuple[int, ...]]] = None,
+    ) -> None: ...
+    def record(
+        self,
+        generations: int,
+        format: str = "gif",
+        frame_skip: int = 1,
+        scale: int = 1,
+        delay_ms: int = 100,
+        region: Optional[tuple[int, int, int, int]] = None,
+        max_bytes: Optional[int] = None,
+        palette: Optional[list[tuple[int, ...]]] = None,
+    ) -> bytes: ...
+    def save_recording(
+        self,
+        path: str,
+        generations: int,
+        frame_skip: int = 1,
+        scale: int = 1,
+        delay_ms: int = 100,
+        region: Optional[tuple[int, int, int, int]] = None,
+        max_bytes: Optional[int] = None,
+        palette: Optional[list[tuple[int, ...]]] = None,
+    ) -> int: ...
+    def stream_video(
+        self,
+        target: Union[str, BinaryIO],
+        generations: int,
+        format: Optional[str] = None,
+        fps: int = 30,
+        scale: int = 1,
+        region: Optional[tuple[int, int, int, int]] = None,
+        palette: Optional[list[tuple[int, ...]]] = None,
+    ) -> int: ...
+
+class PyRule(object):
+    @classmethod
+    def parse(cls, text: str) -> PyRule: ...
+    @classmethod
+    def table(
+        cls,
+        rr: int,
+        mm: int,
+        nn: PyNeighbourhood,
+        states: int,
+        transitions: dict[tuple[int, int], int],
+    ) -> PyRule: ...
+    @classmethod
+    def from_config(cls, config: PyConfig, isotropic: bool = False) -> PyRule: ...
+    @classmethod
+    def load(cls, path: str) -> PyRule: ...
+    @property
+    def states(self) -> int: ...
+    @property
+    def palette(self) -> list[tuple[int, ...]]: ...
+    def next_state(self, state: int, neighbours: list[int]) -> int: ...
+
+class PyRuleBoard(object):
+    def __init__(self, size: int, rule: PyRule): ...
+    @property
+    def rule(self) -> PyRule: ...
+    @property
+    def board(self) -> list[list[int]]: ...
+    def set_cells(self, cells: list[int]) -> None: ...
+    def reset(self): ...
+    def get_cell(self, x: int, y: int) -> int: ...
+    def set_cell(self, x: int, y: int, value: int) -> None: ...
+    def randomize(self, seed: int = ...): ...
+    def update(self) -> None: ...
+    @property
+    def generation(self) -> int: ...
+    @property
+    def stats(self) -> dict[str, Any]: ...
+    @property
+    def hash(self) -> int: ...
+    def detect_cycle(self, max_period: int = 64) -> Optional[tuple[int, int]]: ...
+    def render_image(
+        self,
+        scale: int = 1,
+        palette: Optional[list[tuple[int, ...]]] = None,
+        format: Optional[str] = None,
+    ) -> bytes: ...
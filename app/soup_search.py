@@ -0,0 +1,57 @@
+import argparse
+import sys
+
+from py_ltl_engine import PyConfig
+
+from ltl.config_parser import ConfigParser
+
+
+def parse_args() -> argparse.Namespace:
+    parser = argparse.ArgumentParser(
+        description="Run random soups of a rule and report the objects they leave."
+    )
+    rule = parser.add_mutually_exclusive_group(required=True)
+    rule.add_argument("--config", help="name of a configuration in ./configs")
+    rule.add_argument("--preset", help="name of a built-in preset")
+    parser.add_argument("--soups", type=int, default=100)
+    parser.add_argument("--seed", type=int, default=0, help="seed of the first soup")
+    parser.add_argument("--board-size", type=int, default=64)
+    parser.add_argument("--soup-size", type=int, default=16)
+    parser.add_argument("--max-generations", type=int, default=1000)
+    parser.add_argument("--max-period", type=int, default=32)
+    parser.add_argument("--threads", type=int, default=None)
+    parser.add_argument("--examples", type=int, default=3)
+    parser.add_argument("--format", choices=["json", "text"], default="text")
+    parser.add_argument("--output", help="file to write the report to")
+    return parser.parse_args()
+
+
+def main():
+    args = parse_args()
+    if args.preset is not None:
+        config = PyConfig.preset(args.preset)
+    else:
+        config = ConfigParser.parse_config(args.config)
+        if config is None:
+            sys.exit(f"Invalid configuration {args.config}")
+
+    report = config.soup_search(
+        soups=args.soups,
+        first_seed=args.seed,
+        board_size=args.board_size,
+        soup_size=args.soup_size,
+        max_generations=args.max_generations,
+        max_period=args.max_period,
+        threads=args.threads,
+        examples=args.examples,
+        format=args.format,
+    )
+    if args.output is None:
+        print(report, end="")
+    else:
+        with open(args.output, "w") as f:
+            f.write(report)
+
+
+if __name__ == "__main__":
+    main()
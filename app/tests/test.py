@@ -1,6 +1,11 @@
+import io
+import json
+import os
+import tempfile
+
 import pytest
 
-from py_ltl_engine import PyBoard, PyConfig, PyNeighbourhood
+from py_ltl_engine import PyBoard, PyConfig, PyNeighbourhood, PyRule, PyRuleBoard
 from ltl.config_parser import ConfigParser
 
 
@@ -55,3 +60,482 @@ def test_board_update():
     assert board.get_cell(2, 1) == 1
     assert board.get_cell(1, 0) == 0
     assert board.get_cell(1, 2) == 0
+
+
+def test_board_render_image():
+    config = PyConfig(1, 0, 0, (2, 3), (3, 3), PyNeighbourhood("NM"))
+    board = PyBoard(3, config)
+    board.set_cell(1, 0, 1)
+    pixels = board.render_image(scale=2, palette=[(255, 255, 255), (0, 0, 0)])
+    assert len(pixels) == 6 * 6 * 4
+    assert pixels[8:12] == bytes([0, 0, 0, 255])
+    assert board.render_image(format="png").startswith(b"\x89PNG")
+    assert board.render_image(format="ppm").startswith(b"P6\n3 3\n255\n")
+
+
+def test_board_record():
+    config = PyConfig(1, 0, 0, (2, 3), (3, 3), PyNeighbourhood("NM"))
+    board = PyBoard(5, config)
+    for x in range(1, 4):
+        board.set_cell(x, 2, 1)
+    assert board.record(2, region=(1, 1, 3, 3)).startswith(b"GIF89a")
+    assert board.record(2, format="apng", scale=2).startswith(b"\x89PNG")
+    with pytest.raises(ValueError):
+        board.record(2, format="mp4")
+
+
+def test_board_stream_video():
+    config = PyConfig(1, 0, 0, (2, 3), (3, 3), PyNeighbourhood("NM"))
+    board = PyBoard(4, config)
+    stream = io.BytesIO()
+    assert board.stream_video(stream, 2, fps=10) == 3
+    header = b"YUV4MPEG2 W4 H4 F10:1 Ip A1:1 C444\n"
+    assert stream.getvalue().startswith(header)
+    assert len(stream.getvalue()) == len(header) + 3 * (6 + 3 * 4 * 4)
+
+
+def test_board_from_image():
+    config = PyConfig(1, 0, 0, (2, 3), (3, 3), PyNeighbourhood("NM"))
+    board = PyBoard.from_image(b"P2 2 2 255 0 255 255 0", 2, config)
+    assert board.board == [[1, 0], [0, 1]]
+    palette = [(255, 255, 255), (0, 0, 0), (128, 128, 128)]
+    with pytest.raises(ValueError):
+        PyBoard.from_image(b"P2 1 1 255 128", 1, config, palette=palette)
+
+
+def test_board_buffer_view():
+    config = PyConfig(1, 0, 0, (2, 3), (3, 3), PyNeighbourhood("NM"))
+    board = PyBoard(3, config)
+    view = memoryview(board)
+    assert view.shape == (3, 3)
+    assert view.readonly
+    with pytest.raises(TypeError):
+        view[1, 0] = 2
+    board.set_cells([[0, 0, 0], [1, 1, 1], [0, 0, 0]])
+    assert view[1, 2] == 1
+    board.update()
+    assert view.tolist() == [[0, 1, 0], [0, 1, 0], [0, 1, 0]]
+
+
+def test_board_set_cells():
+    config = PyConfig(1, 0, 0, (2, 3), (3, 3), PyNeighbourhood("NM"))
+    board = PyBoard(2, config)
+    board.set_cells([[0, 1], [1, 0]])
+    assert board.board == [[0, 1], [1, 0]]
+    with pytest.raises(ValueError):
+        board.set_cells([[0, 1]])
+    with pytest.raises(ValueError):
+        board.set_cells([[0, 2], [0, 0]])
+
+
+def test_config_presets():
+    names = [preset["name"] for preset in PyConfig.presets()]
+    assert "Bosco's Rule" in names
+    config = PyConfig.preset("Bosco's Rule")
+    assert (config.rr, config.ss, config.bb) == (5, (34, 58), (34, 45))
+    with pytest.raises(ValueError):
+        PyConfig.preset("unknown")
+
+
+def test_board_stats():
+    config = PyConfig(1, 0, 0, (2, 3), (3, 3), PyNeighbourhood("NM"))
+    board = PyBoard(5, config)
+    for x in range(1, 4):
+        board.set_cell(x, 2, 1)
+    board.record_stats(10)
+    board.update()
+    stats = board.stats
+    assert board.generation == 1
+    assert stats["population"] == 3
+    assert (stats["births"], stats["deaths"], stats["survivors"]) == (2, 2, 1)
+    assert stats["bounding_box"] == (2, 1, 1, 3)
+    assert [s["generation"] for s in board.stats_history()] == [0, 1]
+    assert board.stats_history("csv").startswith("generation,population")
+    with pytest.raises(ValueError):
+        board.stats_history("xml")
+
+
+def test_board_detect_cycle():
+    config = PyConfig(1, 0, 0, (2, 3), (3, 3), PyNeighbourhood("NM"))
+    board = PyBoard(5, config)
+    for x in range(1, 4):
+        board.set_cell(x, 2, 1)
+    assert board.detect_cycle() is None
+    assert board.run_until_periodic(10) == (0, 2)
+    assert board.generation == 2
+    board.reset()
+    assert board.hash == 0
+    assert board.run_until_periodic(10) == (0, 1)
+
+
+def test_board_detect_translation():
+    config = PyConfig(1, 0, 0, (2, 3), (3, 3), PyNeighbourhood("NM"))
+    board = PyBoard(16, config)
+    for x, y in [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)]:
+        board.set_cell(x + 3, y + 3, 1)
+    for _ in range(4):
+        board.update()
+    translation = board.detect_translation()
+    assert (translation["period"], translation["dx"], translation["dy"]) == (4, 1, 1)
+    assert translation["speed"] == 0.25
+
+
+def test_board_census():
+    config = PyConfig(1, 0, 0, (2, 3), (3, 3), PyNeighbourhood("NM"))
+    board = PyBoard(20, config)
+    for x, y in [(2, 2), (2, 3), (3, 2), (3, 3), (10, 10), (11, 10), (12, 10)]:
+        board.set_cell(x, y, 1)
+    objects = board.census()
+    assert [o["kind"] for o in objects] == ["still_life", "oscillator"]
+    assert objects[0]["bounding_box"] == (2, 2, 2, 2)
+    assert objects[1]["period"] == 2
+    assert [o["code"] for o in objects] == ["xs4_33", "xp2_7"]
+    summary = board.census(summary=True)
+    assert sorted(entry["count"] for entry in summary) == [1, 1]
+
+
+def test_board_pattern_code():
+    config = PyConfig(1, 3, 0, (2, 3), (3, 3), PyNeighbourhood("NM"))
+    board = PyBoard(10, config)
+    board.set_cell(4, 4, 1)
+    board.set_cell(4, 5, 2)
+    code = board.pattern_code()
+    assert code == board.pattern_code((3, 3, 4, 4))
+    other = PyBoard(10, config)
+    other.place_code(code, 6, 1)
+    assert other.pattern_code() == code
+    assert sum(other.board[6][1:3]) == 3
+    with pytest.raises(ValueError):
+        other.place_code("33", 0, 0)
+    with pytest.raises(ValueError):
+        PyBoard(10, PyConfig(1, 0, 0, (2, 3), (3, 3), PyNeighbourhood("NM"))).place_code(
+            code, 0, 0
+        )
+
+
+def test_config_soup_search():
+    config = PyConfig(1, 0, 0, (2, 3), (3, 3), PyNeighbourhood("NM"))
+    report = json.loads(
+        config.soup_search(soups=4, board_size=32, soup_size=8, max_generations=300)
+    )
+    assert report["rule"] == "R1,C0,M0,S2..3,B3..3,NM"
+    assert report["soups"] == 4
+    assert all(entry["count"] > 0 for entry in report["objects"])
+    text = config.soup_search(soups=1, board_size=16, soup_size=4, format="text")
+    assert text.startswith("Soup search of R1")
+
+
+def test_config_classify_behaviour():
+    config = PyConfig(1, 0, 0, (2, 3), (3, 3), PyNeighbourhood("NM"))
+    report = config.classify_behaviour(
+        soups=2, board_size=32, soup_size=8, max_generations=300
+    )
+    assert report["rule"] == "R1,C0,M0,S2..3,B3..3,NM"
+    assert len(report["soups"]) == 2
+    assert report["score"] >= 0
+
+
+def test_config_sweep():
+    catalogue = json.loads(
+        PyConfig.sweep(
+            rr=[1],
+            mm=[0],
+            nn=[PyNeighbourhood("NM")],
+            steps=2,
+            soups=1,
+            board_size=16,
+            soup_size=4,
+            max_generations=50,
+        )
+    )
+    scores = [rule["score"] for rule in catalogue["rules"]]
+    assert len(scores) > 0
+    assert scores == sorted(scores, reverse=True)
+
+
+def test_config_phase_diagram(tmp_path):
+    config = PyConfig(1, 0, 0, (2, 3), (3, 3), PyNeighbourhood("NM"))
+    diagram = config.phase_diagram(
+        ss=[(2, 3), (9, 9)],
+        bb=[(3, 3)],
+        seeds=2,
+        board_size=24,
+        soup_size=8,
+        max_generations=100,
+        csv=str(tmp_path / "phase.csv"),
+        heatmap=str(tmp_path / "phase.png"),
+        metric="extinction",
+    )
+    assert diagram["rule"] == "R1,C0,M0,S2..3,B3..3,NM"
+    assert [cell["ss"] for cell in diagram["cells"]] == [[2, 3], [9, 9]]
+    assert diagram["cells"][1]["extinct"] == 2
+    lines = (tmp_path / "phase.csv").read_text().splitlines()
+    assert lines[0].startswith("ss_min,ss_max,bb_min,bb_max")
+    assert len(lines) == 3
+    assert (tmp_path / "phase.png").read_bytes().startswith(b"\x89PNG")
+    with pytest.raises(ValueError):
+        config.phase_diagram(metric="colour")
+
+
+def test_board_damage():
+    config = PyConfig(1, 0, 0, (0, 8), (1, 8), PyNeighbourhood("NM"))
+    board = PyBoard(16, config)
+    other = PyBoard(16, config)
+    other.set_cell(3, 4, 1)
+    diff = board.compare(other)
+    assert diff["distance"] == 1
+    assert diff["bounding_box"] == (3, 4, 1, 1)
+    with pytest.raises(ValueError):
+        board.compare(PyBoard(8, config))
+
+    curve = board.twin_run(4, cells=[(8, 8)])
+    assert curve["perturbed"] == [(8, 8)]
+    assert curve["healed_at"] is None
+    assert [point["distance"] for point in curve["points"]] == [1, 9, 25, 49, 81]
+    assert curve["velocity"] == pytest.approx(1.0)
+    assert board.generation == 0
+
+
+def test_config_search_oscillators():
+    config = PyConfig(1, 0, 0, (2, 3), (3, 3), PyNeighbourhood("NM"))
+    report = config.search_oscillators(width=3, height=3, period=2, max_solutions=3)
+    assert report["exhausted"]
+    [blinker] = report["oscillators"]
+    assert blinker["code"] == "xp2_7"
+    assert blinker["rle"].startswith("x = ")
+    board = blinker["board"]
+    assert board.run_until_periodic(10) == (0, 2)
+    with pytest.raises(ValueError):
+        config.search_oscillators(period=0)
+
+
+def test_board_find_predecessor():
+    config = PyConfig(1, 0, 0, (2, 3), (3, 3), PyNeighbourhood("NM"))
+    board = PyBoard(8, config)
+    for y in range(2, 5):
+        board.set_cell(3, y, 1)
+    result = board.find_predecessor(region=(2, 2, 3, 3), exact=True)
+    assert result["status"] == "found"
+    assert not result["garden_of_eden"]
+    predecessor = result["board"]
+    predecessor.update()
+    assert predecessor.pattern_code() == board.pattern_code()
+
+    config = PyConfig(1, 3, 0, (0, 8), (3, 3), PyNeighbourhood("NM"))
+    board = PyBoard(4, config)
+    board.set_cell(1, 1, 2)
+    result = board.find_predecessor(region=(1, 1, 1, 1))
+    assert result["status"] == "none"
+    assert result["garden_of_eden"]
+    assert result["board"] is None
+    with pytest.raises(ValueError):
+        board.find_predecessor(region=(0, 0, 0, 0))
+
+
+def test_board_find_pattern():
+    config = PyConfig(1, 0, 0, (2, 3), (3, 3), PyNeighbourhood("NM"))
+    board = PyBoard(16, config)
+    glider = [[0, 1, 0], [0, 0, 1], [1, 1, 1]]
+    for y, row in enumerate(glider):
+        for x, state in enumerate(row):
+            board.set_cell(2 + x, 2 + y, state)
+    [found] = board.find_pattern(glider)
+    assert (found["x"], found["y"], found["symmetry"]) == (2, 2, "identity")
+    code = board.pattern_code()
+    assert len(board.find_pattern(code, symmetries=["identity", "rotate90", "flip_x", "flip_y"])) == 1
+    assert board.find_pattern([[None, 1, None], [None, None, 1]])[0]["width"] == 3
+    for _ in range(4):
+        board.update()
+    [found] = board.find_pattern(glider)
+    assert (found["x"], found["y"]) == (3, 3)
+    with pytest.raises(ValueError):
+        board.find_pattern(glider, symmetries=["sideways"])
+
+
+def test_board_activity():
+    config = PyConfig(1, 0, 0, (2, 3), (3, 3), PyNeighbourhood("NM"))
+    board = PyBoard(5, config)
+    with pytest.raises(ValueError):
+        board.activity()
+    for y in range(1, 4):
+        board.set_cell(2, y, 1)
+    board.record_activity()
+    for _ in range(4):
+        board.update()
+    changes = board.activity()
+    assert changes[2][2] == 0
+    assert changes[1][2] == 4
+    assert board.activity("alive")[2][2] == 5
+    last_active = board.activity("last_active")
+    assert last_active[2][1] == 4
+    assert last_active[0][0] is None
+    summary = board.activity_summary()
+    assert summary["generations"] == 4
+    assert summary["reach"] == (1, 1, 3, 3)
+    assert len(board.activity_heatmap("reached", scale=2)) == 10 * 10 * 4
+    assert board.activity_heatmap(format="png").startswith(b"\x89PNG")
+    with pytest.raises(ValueError):
+        board.activity("age")
+    board.reset_activity()
+    assert board.activity_summary()["generations"] == 0
+    board.record_activity(False)
+    with pytest.raises(ValueError):
+        board.activity_summary()
+
+
+def test_board_ages():
+    config = PyConfig(1, 0, 0, (2, 3), (3, 3), PyNeighbourhood("NM"))
+    board = PyBoard(5, config)
+    for x in range(1, 4):
+        board.set_cell(x, 2, 1)
+    assert board.get_age(2, 2) is None
+    with pytest.raises(ValueError):
+        board.ages()
+    board.track_ages()
+    for _ in range(3):
+        board.update()
+    ages = board.ages()
+    assert ages[2][2] == 4
+    assert ages[2][1] == 1
+    assert board.get_age(1, 2) == 0
+    assert board.get_age(0, 4) == 0
+    with pytest.raises(ValueError):
+        board.get_age(0, 5)
+    board.reset_ages()
+    assert board.get_age(2, 2) == 1
+    board.track_ages(False)
+    assert board.get_age(2, 2) is None
+
+
+def test_board_explain_cell():
+    config = PyConfig(1, 0, 0, (2, 3), (3, 3), PyNeighbourhood("NM"))
+    board = PyBoard(5, config)
+    for x in range(1, 4):
+        board.set_cell(x, 2, 1)
+    explanation = board.explain_cell(2, 1)
+    assert explanation["state"] == 0
+    assert explanation["condition"] == "birth"
+    assert explanation["count"] == 3
+    assert explanation["range"] == (3, 3)
+    assert explanation["satisfied"]
+    assert explanation["next_state"] == 1
+    assert len(explanation["neighbours"]) == 8
+    assert (1, 2, 1) in explanation["neighbours"]
+    assert board.explain_cell(1, 2)["condition"] == "survival"
+    counts = board.neighbour_counts()
+    assert counts[2][1] == 3
+    assert counts[0][0] == 0
+    with pytest.raises(ValueError):
+        board.explain_cell(5, 0)
+
+
+def test_table_rule_matches_config():
+    config = PyConfig(1, 0, 0, (2, 3), (3, 3), PyNeighbourhood("NM"))
+    rule = PyRule.parse("R1,C2,M0,NM,T000100000/001100000")
+    assert str(PyRule.from_config(config)) == str(rule)
+    transitions = {(0, 3): 1, (1, 2): 1, (1, 3): 1}
+    assert str(PyRule.table(1, 0, PyNeighbourhood("NM"), 2, transitions)) == str(rule)
+    assert rule.next_state(0, [1, 1, 1, 0, 0, 0, 0, 0]) == 1
+    with pytest.raises(ValueError):
+        rule.next_state(0, [1] * 20)
+    with pytest.raises(ValueError):
+        rule.next_state(0, [2, 0, 0, 0, 0, 0, 0, 0])
+    board = PyBoard(16, config)
+    table_board = PyRuleBoard(16, rule)
+    board.randomize(7)
+    table_board.set_cells([s for column in board.board for s in column])
+    for _ in range(10):
+        board.update()
+        table_board.update()
+    assert table_board.board == board.board
+    assert table_board.stats["population"] == board.stats["population"]
+    assert table_board.generation == 10
+    with pytest.raises(ValueError):
+        PyRule.parse("R1,C2,M0,NM,T0001/0011")
+    with pytest.raises(ValueError):
+        PyRule.table(1, 0, PyNeighbourhood("NM"), 2, {(0, 3): 2})
+    with pytest.raises(ValueError):
+        table_board.set_cell(0, 0, 2)
+
+
+def test_isotropic_rule():
+    config = PyConfig(1, 0, 0, (2, 3), (3, 3), PyNeighbourhood("NM"))
+    assert str(PyRule.from_config(config, isotropic=True)) == "B3/S23"
+    rule = PyRule.parse("b2ceikn/s21")
+    assert str(rule) == "B2-a/S12"
+    assert rule.states == 2
+    # Adjacent edge and corner neighbours are the 2a arrangement.
+    assert rule.next_state(0, [0, 0, 0, 1, 0, 1, 0, 0]) == 0
+    assert rule.next_state(0, [0, 1, 0, 0, 0, 0, 1, 0]) == 1
+    with pytest.raises(ValueError):
+        rule.next_state(0, [1] * 9)
+    board = PyRuleBoard(8, rule)
+    board.set_cell(3, 3, 1)
+    board.set_cell(3, 5, 1)
+    board.update()
+    assert board.get_cell(3, 4) == 1
+    with pytest.raises(ValueError):
+        PyRule.parse("B2x/S23")
+    with pytest.raises(ValueError):
+        PyRule.from_config(PyConfig(2, 0, 0, (2, 3), (3, 3), PyNeighbourhood("NM")), isotropic=True)
+
+
+WIREWORLD = """@RULE WireWorld
+@TABLE
+n_states:4
+neighborhood:Moore
+symmetries:permute
+var a={0,1,2,3}
+var b={a}
+var c={a}
+var d={a}
+var e={a}
+var f={a}
+var g={a}
+var h={a}
+var o={0,2,3}
+var p={o}
+var q={o}
+var r={o}
+var s={o}
+var t={o}
+var u={o}
+1,a,b,c,d,e,f,g,h,2
+2,a,b,c,d,e,f,g,h,3
+3,1,o,p,q,r,s,t,u,1
+3,1,1,o,p,q,r,s,t,1
+@COLORS
+0 48 48 48
+1 0 128 255
+2 255 255 255
+3 255 128 0
+"""
+
+
+def test_golly_rule():
+    rule = PyRule.parse(WIREWORLD)
+    assert str(rule) == "WireWorld"
+    assert rule.states == 4
+    assert tuple(rule.palette[3]) == (255, 128, 0, 255)
+    with tempfile.TemporaryDirectory() as directory:
+        path = os.path.join(directory, "WireWorld.rule")
+        with open(path, "w") as f:
+            f.write(WIREWORLD)
+        assert str(PyRule.load(path)) == "WireWorld"
+        with pytest.raises(OSError):
+            PyRule.load(os.path.join(directory, "Missing.rule"))
+    with pytest.raises(ValueError):
+        rule.next_state(3, [1, 1])
+    with pytest.raises(ValueError):
+        rule.next_state(3, [4, 0, 0, 0, 0, 0, 0, 0])
+    board = PyRuleBoard(8, rule)
+    for x in range(8):
+        board.set_cell(x, 3, 3)
+    board.set_cell(1, 3, 2)
+    board.set_cell(2, 3, 1)
+    board.update()
+    assert [board.get_cell(x, 3) for x in range(5)] == [3, 3, 2, 1, 3]
+    pixels = board.render_image()
+    assert tuple(pixels[:4]) == (48, 48, 48, 255)
+    with pytest.raises(ValueError):
+        PyRule.parse("@RULE Empty\n@TABLE\nn_states:2\nneighborhood:hexagonal")
@@ -1,7 +1,9 @@
 mod ltl_engine;
 mod py_board;
 mod py_config;
+mod py_json;
 mod py_neighbourhood;
+mod py_rule;
 
 use pyo3::prelude::*;
 
@@ -10,5 +12,7 @@ fn py_ltl_engine(_py: Python, m: &PyModule) -> PyResult<()> {
     m.add_class::<py_neighbourhood::PyNeighbourhood>()?;
     m.add_class::<py_config::PyConfig>()?;
     m.add_class::<py_board::PyBoard>()?;
+    m.add_class::<py_rule::PyRule>()?;
+    m.add_class::<py_rule::PyRuleBoard>()?;
     Ok(())
 }
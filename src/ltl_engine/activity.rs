@@ -0,0 +1,274 @@
+use crate::ltl_engine::cells::{Cell, Cells, Region};
+use crate::ltl_engine::image::Image;
+use crate::ltl_engine::phase::{heat, HEATMAP};
+use std::str::FromStr;
+
+const CHANGES: &str = "changes";
+const ALIVE: &str = "alive";
+const LAST_ACTIVE: &str = "last_active";
+const REACHED: &str = "reached";
+
+/// A layer of the activity of a board, rendered by `Activity::heatmap`.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ActivityLayer {
+    /// Number of updates changing the state of a cell.
+    Changes,
+    /// Number of generations a cell spent in a non-zero state.
+    Alive,
+    /// The last generation a cell changed state, more recent generations rendering hotter.
+    LastActive,
+    /// Whether a cell ever changed state, the light cone of the initial pattern.
+    Reached,
+}
+
+impl FromStr for ActivityLayer {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            CHANGES => Ok(ActivityLayer::Changes),
+            ALIVE => Ok(ActivityLayer::Alive),
+            LAST_ACTIVE => Ok(ActivityLayer::LastActive),
+            REACHED => Ok(ActivityLayer::Reached),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Per cell activity of a board accumulated over generations. Values are stored column by
+/// column, like the cells of a board.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct Activity {
+    size: usize,
+    since: u64,
+    generations: u64,
+    changes: Vec<u64>,
+    alive: Vec<u64>,
+    last_active: Vec<Option<u64>>,
+}
+
+impl Activity {
+    /// Starts accumulating the activity of a board from its current generation, which counts
+    /// towards the time cells spend alive.
+    ///
+    /// # Arguments
+    ///
+    /// * `cells` - The cells of the current generation.
+    /// * `generation` - The current generation.
+    ///
+    pub fn new(cells: &Cells, generation: u64) -> Self {
+        Activity {
+            size: cells.len(),
+            since: generation,
+            generations: 0,
+            changes: vec![0; cells.len() * cells.len()],
+            alive: cells.as_slice().iter().map(|&s| (s != 0) as u64).collect(),
+            last_active: vec![None; cells.len() * cells.len()],
+        }
+    }
+
+    /// Accumulates the activity of an update.
+    ///
+    /// # Arguments
+    ///
+    /// * `before` - The cells before the update.
+    /// * `after` - The cells after the update.
+    /// * `generation` - The generation produced by the update.
+    ///
+    pub fn record(&mut self, before: &[Cell], after: &[Cell], generation: u64) {
+        self.generations += 1;
+        for (i, (&old, &new)) in before.iter().zip(after).enumerate() {
+            if old != new {
+                self.changes[i] += 1;
+                self.last_active[i] = Some(generation);
+            }
+            if new != 0 {
+                self.alive[i] += 1;
+            }
+        }
+    }
+
+    /// Returns the size of the board the activity was recorded on.
+    ///
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Returns the generation the recording started at.
+    ///
+    pub fn since(&self) -> u64 {
+        self.since
+    }
+
+    /// Returns the number of recorded updates.
+    ///
+    pub fn generations(&self) -> u64 {
+        self.generations
+    }
+
+    /// Returns the number of changes of every cell, column by column.
+    ///
+    pub fn changes(&self) -> &[u64] {
+        &self.changes
+    }
+
+    /// Returns the number of generations every cell was alive, column by column.
+    ///
+    pub fn alive(&self) -> &[u64] {
+        &self.alive
+    }
+
+    /// Returns the last generation every cell changed, column by column, `None` for cells
+    /// that never changed.
+    ///
+    pub fn last_active(&self) -> &[Option<u64>] {
+        &self.last_active
+    }
+
+    /// Returns the smallest region holding every cell that changed, `None` when nothing
+    /// changed.
+    ///
+    pub fn reach(&self) -> Option<Region> {
+        let mut bounds: Option<(usize, usize, usize, usize)> = None;
+        for (i, _) in self.changes.iter().enumerate().filter(|(_, &c)| c > 0) {
+            let (x, y) = (i / self.size, i % self.size);
+            let (x0, y0, x1, y1) = bounds.unwrap_or((x, y, x, y));
+            bounds = Some((x0.min(x), y0.min(y), x1.max(x), y1.max(y)));
+        }
+        bounds.map(|(x0, y0, x1, y1)| Region {
+            x: x0,
+            y: y0,
+            width: x1 - x0 + 1,
+            height: y1 - y0 + 1,
+        })
+    }
+
+    /// Returns the values of a layer, column by column. Last activity is counted in updates
+    /// since the recording started, cells that never changed holding 0.
+    ///
+    pub fn values(&self, layer: ActivityLayer) -> Vec<u64> {
+        match layer {
+            ActivityLayer::Changes => self.changes.clone(),
+            ActivityLayer::Alive => self.alive.clone(),
+            ActivityLayer::LastActive => self
+                .last_active
+                .iter()
+                .map(|last| last.map_or(0, |generation| generation - self.since))
+                .collect(),
+            ActivityLayer::Reached => self.changes.iter().map(|&c| (c > 0) as u64).collect(),
+        }
+    }
+
+    /// Renders a layer as a heatmap, the cell at `(x, y)` being drawn at pixel column `x` and
+    /// row `y`. Values are scaled from 0 to the largest value of the layer.
+    ///
+    /// # Arguments
+    ///
+    /// * `layer` - The rendered layer.
+    /// * `scale` - The side of the square of pixels drawn for every cell.
+    ///
+    pub fn heatmap(&self, layer: ActivityLayer, scale: usize) -> Image {
+        let scale = scale.max(1);
+        let values = self.values(layer);
+        let max = values.iter().copied().max().unwrap_or(0).max(1) as f64;
+        let side = self.size * scale;
+        let mut image = Image::new(side, side, HEATMAP[0]);
+        for (i, &value) in values.iter().enumerate() {
+            if value == 0 {
+                continue;
+            }
+            let color = heat(value as f64 / max);
+            let (x, y) = (i / self.size, i % self.size);
+            for py in y * scale..(y + 1) * scale {
+                for px in x * scale..(x + 1) * scale {
+                    image.set_pixel(px, py, color);
+                }
+            }
+        }
+        image
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ltl_engine::board::Board;
+    use crate::ltl_engine::config::Config;
+    use crate::ltl_engine::neighbourhood::Neighbourhood;
+
+    fn blinker_board() -> Board {
+        let config = Config::new(1, 0, 0, (2, 3), (3, 3), Neighbourhood::Moore);
+        let mut board = Board::new(5, config);
+        for y in 1..4 {
+            board.set_cell(2, y, 1);
+        }
+        board
+    }
+
+    #[test]
+    fn blinker_activity() {
+        let mut board = blinker_board();
+        board.update();
+        board.record_activity(true);
+        for _ in 0..4 {
+            board.update();
+        }
+        let activity = board.activity().unwrap();
+        assert_eq!((activity.since(), activity.generations()), (1, 4));
+        let at = |x: usize, y: usize| x * 5 + y;
+        // The centre never changes, the tips of the blinker flip every generation.
+        assert_eq!(activity.changes()[at(2, 2)], 0);
+        assert_eq!(activity.alive()[at(2, 2)], 5);
+        assert_eq!(activity.changes()[at(1, 2)], 4);
+        assert_eq!(activity.alive()[at(1, 2)], 3);
+        assert_eq!(activity.last_active()[at(2, 1)], Some(5));
+        assert_eq!(activity.last_active()[at(0, 0)], None);
+        assert_eq!(
+            activity.values(ActivityLayer::LastActive)[at(2, 1)],
+            activity.generations()
+        );
+        assert_eq!(
+            activity.reach(),
+            Some(Region {
+                x: 1,
+                y: 1,
+                width: 3,
+                height: 3
+            })
+        );
+        assert_eq!(
+            activity.values(ActivityLayer::Reached).iter().sum::<u64>(),
+            4
+        );
+
+        let image = activity.heatmap(ActivityLayer::Changes, 2);
+        assert_eq!(image.width, 10);
+        assert_eq!(image.get_pixel(0, 0), HEATMAP[0]);
+        assert_eq!(image.get_pixel(2, 4), HEATMAP[4]);
+    }
+
+    #[test]
+    fn reset_and_disable() {
+        let mut board = blinker_board();
+        board.record_activity(true);
+        board.update();
+        board.reset_activity();
+        assert_eq!(board.activity().unwrap().generations(), 0);
+        assert_eq!(board.activity().unwrap().since(), 1);
+        board.update();
+        board.reset();
+        assert_eq!(board.activity().unwrap().generations(), 0);
+        assert!(board.activity().unwrap().reach().is_none());
+        board.record_activity(false);
+        board.update();
+        assert!(board.activity().is_none());
+    }
+
+    #[test]
+    fn parse_layers() {
+        assert_eq!("Last_Active".parse(), Ok(ActivityLayer::LastActive));
+        assert_eq!("age".parse::<ActivityLayer>(), Err(()));
+    }
+}
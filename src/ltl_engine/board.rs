@@ -1,25 +1,46 @@
+use crate::ltl_engine::activity::Activity;
+use crate::ltl_engine::cells::{Cell, Cells, CellsError};
 use crate::ltl_engine::config::Config;
-use crate::ltl_engine::neighbourhood::Neighbourhood;
+use crate::ltl_engine::hash::{
+    self, Cycle, HashHistory, Snapshot, StateHasher, Translation, HASH_HISTORY,
+};
+use crate::ltl_engine::image::{Fit, Image, ImageError, Palette, Quantize};
+use crate::ltl_engine::Rule;
+use crate::ltl_engine::stats::{Stats, StatsHistory, Tally};
 use rand::{Rng, RngCore, SeedableRng};
 use rand_pcg::Pcg32;
-use std::cmp::{max, min};
-use std::ops::{Range, Sub};
+use std::cmp::max;
+use std::ops::Range;
 use std::thread;
 
-type Cell = u8;
-type Cells = Vec<Vec<Cell>>;
-type DoGetNeighbourhood = dyn Fn(&Board, usize, usize, usize, usize) -> Option<u8>;
-
+/// Number of consecutive generations a cell has spent in state 1, saturating at the maximum.
+pub type Age = u16;
 
 /// A struct representing a game board of cells. Each cell can be in one of several states.
 ///
+/// The cells are updated in place, so the buffer returned by `Cells::as_slice` stays valid
+/// for the lifetime of the board.
+///
+/// Statistics and the hash of the current generation are kept up to date by the board's own
+/// methods. Call `refresh_stats` after writing to `cells` directly.
+///
+/// Cells are updated by a `Rule`, the Larger than Life rule of a `Config` by default.
+///
 #[derive(Debug, Clone, PartialEq)]
-pub struct Board {
-    config: Config,
+pub struct Board<R = Config> {
+    config: R,
     pub cells: Cells,
+    stats: Stats,
+    history: Option<StatsHistory>,
+    activity: Option<Activity>,
+    ages: Option<Vec<Age>>,
+    hasher: StateHasher,
+    hashes: HashHistory,
+    /// Number of threads updates run on, `None` for one per core.
+    threads: Option<usize>,
 }
 
-impl Board {
+impl<R: Rule> Board<R> {
     /// Create a new board of cells with the given size and configuration.
     ///
     /// # Arguments
@@ -27,11 +48,327 @@ impl Board {
     /// * `size` - The size of the board. The board will be `size` by `size`.
     /// * `config` - The configuration of the board.
     ///
-    pub fn new(size: u64, config: Config) -> Self {
+    pub fn new(size: u64, config: R) -> Self {
+        Board::with_cells(Cells::new(size as usize), config)
+    }
+
+    /// Create a new board from the given cells.
+    ///
+    /// # Arguments
+    ///
+    /// * `cells` - Columns of cells, `cells[x][y]` being the cell at `(x, y)`.
+    /// * `config` - The configuration of the board.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the columns don't form a square.
+    ///
+    pub fn from_cells(cells: Vec<Vec<Cell>>, config: R) -> Self {
+        Board::with_cells(Cells::from(cells), config)
+    }
+
+    fn with_cells(cells: Cells, config: R) -> Self {
+        let hasher = StateHasher::new(cells.len());
+        let stats = Stats::of(&cells, config.states(), 0);
+        let snapshot =
+            hasher.snapshot(hasher.hash(&cells), stats.bounding_box, config.radius());
         Board {
+            stats,
             config,
-            cells: vec![vec![0; size as usize]; size as usize],
+            cells,
+            history: None,
+            activity: None,
+            ages: None,
+            hasher,
+            hashes: HashHistory::new(HASH_HISTORY, 0, snapshot),
+            threads: None,
+        }
+    }
+
+    /// Create a new board seeded from an image.
+    ///
+    /// # Arguments
+    ///
+    /// * `image` - The image to read the cells from.
+    /// * `size` - The size of the board. The board will be `size` by `size`.
+    /// * `config` - The configuration of the board.
+    /// * `fit` - How the image is fitted to the board.
+    /// * `quantize` - How pixel colours are mapped to cell states.
+    ///
+    /// # Returns
+    ///
+    /// The seeded board, or an error when the image requires more states than the
+    /// configuration allows.
+    ///
+    pub fn from_image(
+        image: &Image,
+        size: u64,
+        config: R,
+        fit: Fit,
+        quantize: &Quantize,
+    ) -> Result<Self, ImageError> {
+        let mut board = Board::new(size, config);
+        let size = size as usize;
+        let mut required = 0;
+        for x in 0..size {
+            for y in 0..size {
+                let pixel = match fit {
+                    Fit::Scale if image.width > 0 && image.height > 0 => {
+                        Some((x * image.width / size, y * image.height / size))
+                    }
+                    Fit::Scale => None,
+                    Fit::Crop => {
+                        let px = (x + image.width / 2).checked_sub(size / 2);
+                        let py = (y + image.height / 2).checked_sub(size / 2);
+                        px.zip(py)
+                            .filter(|&(px, py)| px < image.width && py < image.height)
+                    }
+                };
+                if let Some((px, py)) = pixel {
+                    let state = image.quantize(px, py, quantize);
+                    required = max(required, state as usize + 1);
+                    board.cells[x][y] = state;
+                }
+            }
+        }
+        let available = board.config.states() as usize;
+        if required > available {
+            return Err(ImageError::TooManyStates {
+                required,
+                available,
+            });
         }
+        board.refresh_stats();
+        Ok(board)
+    }
+
+    /// Returns the configuration of the board.
+    ///
+    pub fn config(&self) -> &R {
+        &self.config
+    }
+
+    /// Returns the number of updates since the board was created, reset or randomized.
+    ///
+    pub fn generation(&self) -> u64 {
+        self.stats.generation
+    }
+
+    /// Returns the statistics of the current generation.
+    ///
+    pub fn stats(&self) -> &Stats {
+        &self.stats
+    }
+
+    /// Recounts the statistics and the hash of the current generation, e.g. after writing to
+    /// `cells` directly. Transitions of the last update are lost and cycle detection starts
+    /// over from the current generation.
+    ///
+    pub fn refresh_stats(&mut self) {
+        self.stats = Stats::of(&self.cells, self.config.states(), self.stats.generation);
+        self.hashes
+            .restart(self.stats.generation, self.snapshot(self.hasher.hash(&self.cells)));
+        self.refresh_ages();
+    }
+
+    /// Keep the statistics of the latest generations. The current generation is recorded
+    /// right away.
+    ///
+    /// # Arguments
+    ///
+    /// * `capacity` - The number of generations kept, `0` stops recording.
+    ///
+    pub fn record_stats(&mut self, capacity: usize) {
+        self.history = if capacity == 0 {
+            None
+        } else {
+            let mut history = StatsHistory::new(capacity);
+            history.push(self.stats.clone());
+            Some(history)
+        };
+    }
+
+    /// Returns the recorded statistics, if recording is enabled.
+    ///
+    pub fn stats_history(&self) -> Option<&StatsHistory> {
+        self.history.as_ref()
+    }
+
+    /// Accumulate the activity of every cell over the next updates, starting from the current
+    /// generation. Enabling it again starts over.
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - Whether the activity is recorded, `false` drops it.
+    ///
+    pub fn record_activity(&mut self, enabled: bool) {
+        self.activity = enabled.then(|| Activity::new(&self.cells, self.stats.generation));
+    }
+
+    /// Starts accumulating the activity over from the current generation, if recording is
+    /// enabled.
+    ///
+    pub fn reset_activity(&mut self) {
+        if self.activity.is_some() {
+            self.record_activity(true);
+        }
+    }
+
+    /// Returns the accumulated activity, if recording is enabled.
+    ///
+    pub fn activity(&self) -> Option<&Activity> {
+        self.activity.as_ref()
+    }
+
+    /// Count for every cell the consecutive generations it spends in state 1, apart from the
+    /// decay states of `cc > 2`. Cells in state 1 start at age 1, newborn cells being 1 too.
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - Whether ages are counted, `false` drops them.
+    ///
+    pub fn track_ages(&mut self, enabled: bool) {
+        self.ages = enabled.then(|| {
+            self.cells
+                .as_slice()
+                .iter()
+                .map(|&state| (state == 1) as Age)
+                .collect()
+        });
+    }
+
+    /// Starts counting ages over from the current generation, if ages are counted.
+    ///
+    pub fn reset_ages(&mut self) {
+        if self.ages.is_some() {
+            self.track_ages(true);
+        }
+    }
+
+    /// Returns the ages of the cells column by column, if ages are counted.
+    ///
+    pub fn ages(&self) -> Option<&[Age]> {
+        self.ages.as_deref()
+    }
+
+    /// Returns the age of the cell at the given coordinates, if ages are counted.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the coordinates are outside the board.
+    ///
+    pub fn get_age(&self, x: usize, y: usize) -> Option<Age> {
+        let size = self.cells.len();
+        assert!(x < size && y < size, "Cell is outside the board");
+        self.ages.as_ref().map(|ages| ages[x * size + y])
+    }
+
+    /// Brings the ages in line with cells written directly: cells out of state 1 lose their
+    /// age and cells put in state 1 start at 1.
+    ///
+    fn refresh_ages(&mut self) {
+        if let Some(ages) = &mut self.ages {
+            for (age, &state) in ages.iter_mut().zip(self.cells.as_slice()) {
+                *age = if state == 1 { (*age).max(1) } else { 0 };
+            }
+        }
+    }
+
+    /// Returns the hash of the current generation. Equal boards have equal hashes.
+    ///
+    pub fn hash(&self) -> u64 {
+        self.hashes.latest().hash
+    }
+
+    /// Returns the hash of the live cells relative to their bounding box. Boards equal up to
+    /// a translation have equal shapes.
+    ///
+    pub fn shape(&self) -> u64 {
+        self.hashes.latest().shape
+    }
+
+    /// Set the number of generations whose hashes are kept for cycle detection.
+    ///
+    pub fn set_hash_history(&mut self, capacity: usize) {
+        self.hashes.set_capacity(capacity);
+    }
+
+    /// Check whether the current generation repeats an earlier one.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_period` - The longest period searched for. Only the generations since the last
+    ///   edit of the board and within the hash history are considered.
+    ///
+    /// # Returns
+    ///
+    /// The shortest cycle of the current generation and the earliest generation it started at,
+    /// `None` when no earlier generation is equal.
+    ///
+    pub fn detect_cycle(&self, max_period: usize) -> Option<Cycle> {
+        self.hashes.detect_cycle(max_period)
+    }
+
+    /// Check whether the current generation repeats an earlier one up to a translation, as
+    /// spaceships do. Generations whose live cells come closer to the edges of the board than
+    /// the radius of the neighbourhood are ignored, because the edges change their evolution.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_period` - The longest period searched for. Only the generations since the last
+    ///   edit of the board and within the hash history are considered.
+    ///
+    /// # Returns
+    ///
+    /// The shortest period of the current generation with the displacement of the live cells,
+    /// `None` when no earlier generation has the same shape.
+    ///
+    pub fn detect_translation(&self, max_period: usize) -> Option<Translation> {
+        self.hashes.detect_translation(max_period)
+    }
+
+    /// Returns the hashes of the current generation for the given hash.
+    ///
+    fn snapshot(&self, hash: u64) -> Snapshot {
+        self.hasher
+            .snapshot(hash, self.stats.bounding_box, self.config.radius())
+    }
+
+    /// Updates the board until it becomes periodic.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_generations` - The maximal number of updates.
+    /// * `max_period` - The longest period searched for, the hash history is grown to fit it.
+    ///
+    /// # Returns
+    ///
+    /// The detected cycle, `None` when none was found within `max_generations` updates.
+    ///
+    pub fn run_until_periodic(&mut self, max_generations: usize, max_period: usize) -> Option<Cycle> {
+        if self.hashes.capacity() <= max_period {
+            self.hashes.set_capacity(max_period + 1);
+        }
+        for _ in 0..max_generations {
+            if let Some(cycle) = self.detect_cycle(max_period) {
+                return Some(cycle);
+            }
+            self.update();
+        }
+        self.detect_cycle(max_period)
+    }
+
+    /// Starts counting generations from zero with freshly counted statistics.
+    ///
+    fn restart(&mut self) {
+        self.stats = Stats::of(&self.cells, self.config.states(), 0);
+        self.hashes.restart(0, self.snapshot(self.hasher.hash(&self.cells)));
+        if let Some(history) = &mut self.history {
+            history.clear();
+            history.push(self.stats.clone());
+        }
+        self.reset_activity();
+        self.reset_ages();
     }
 
     /// Reset all cells in the board to their initial state (0).
@@ -40,6 +377,7 @@ impl Board {
         for i in self.cells.iter_mut().flat_map(|i| {i}) {
             *i = 0;
         }
+        self.restart();
     }
 
     /// Get the value of the cell at the given coordinates.
@@ -71,10 +409,53 @@ impl Board {
     /// number of cell states specified in the board's configuration.
     ///
     pub fn set_cell(&mut self, x: usize, y: usize, value: Cell) -> () {
-        if value >= max(self.config.cc, 2) {
+        if value >= self.config.states() {
             panic!("Config doesnt support provided value");
         }
+        let before = self.cells[x][y];
         self.cells[x][y] = value;
+        if before != value {
+            let size = self.cells.len();
+            if let Some(ages) = &mut self.ages {
+                ages[x * size + y] = (value == 1) as Age;
+            }
+        }
+        self.stats.replace(&self.cells, x, y, before);
+        let hash = self.hasher.replace(self.hash(), x, y, before, value);
+        self.hashes.restart(self.stats.generation, self.snapshot(hash));
+    }
+
+    /// Set the values of all cells in the board.
+    ///
+    /// # Arguments
+    ///
+    /// * `cells` - The values of the cells, column by column, `size * size` in total.
+    ///
+    /// # Returns
+    ///
+    /// An error when the number of cells doesn't match the board or a value isn't supported
+    /// by the board's configuration. The board is left unchanged in that case.
+    ///
+    pub fn set_cells(&mut self, cells: &[Cell]) -> Result<(), CellsError> {
+        let size = self.cells.len();
+        if cells.len() != size * size {
+            return Err(CellsError::Shape {
+                expected: size * size,
+                actual: cells.len(),
+            });
+        }
+        let states = self.config.states();
+        if let Some(i) = cells.iter().position(|&value| value >= states) {
+            return Err(CellsError::State {
+                x: i / size,
+                y: i % size,
+                value: cells[i],
+                states,
+            });
+        }
+        self.cells.as_mut_slice().copy_from_slice(cells);
+        self.refresh_stats();
+        Ok(())
     }
 
     /// Randomize the values of all cells in the board.
@@ -94,6 +475,7 @@ impl Board {
                 self._randomize(&mut rng)
             }
         }
+        self.restart();
     }
 
     /// Randomize the values of all cells in the board.
@@ -104,7 +486,7 @@ impl Board {
     ///
     fn _randomize<T: RngCore>(&mut self, rng: &mut T) -> () {
         for i in self.cells.iter_mut().flat_map(|i| {i}) {
-            *i = rng.gen_range(0..max(self.config.cc, 2));
+            *i = rng.gen_range(0..self.config.states());
         }
     }
 
@@ -116,7 +498,7 @@ impl Board {
     /// * `y` - The y coordinate of the cell.
     ///
     pub fn cell_up(&mut self, x: usize, y: usize) -> () {
-        let value = (self.get_cell(x, y) + 1) % max(self.config.cc, 2);
+        let value = (self.get_cell(x, y) + 1) % self.config.states();
         self.set_cell(x, y, value);
     }
 
@@ -131,43 +513,134 @@ impl Board {
         if self.get_cell(x, y) == 0 {
             return;
         }
-        let value = (self.get_cell(x, y) - 1) % max(self.config.cc, 2);
+        let value = (self.get_cell(x, y) - 1) % self.config.states();
         self.set_cell(x, y, value);
     }
 
+    /// Render the board as an RGBA image. The cell at `(x, y)` is drawn at pixel column `x`
+    /// and row `y`.
+    ///
+    /// # Arguments
+    ///
+    /// * `palette` - The colours of the cell states.
+    /// * `scale` - The side of the square of pixels drawn for every cell.
+    ///
+    /// # Returns
+    ///
+    /// Rendered image of `size * scale` by `size * scale` pixels.
+    ///
+    pub fn render_image(&self, palette: &Palette, scale: usize) -> Image {
+        let scale = max(scale, 1);
+        let side = self.cells.len() * scale;
+        let mut image = Image::new(side, side, palette.color(0));
+        for (x, column) in self.cells.iter().enumerate() {
+            for (y, &state) in column.iter().enumerate() {
+                if state == 0 {
+                    continue;
+                }
+                let color = palette.color(state);
+                for py in y * scale..(y + 1) * scale {
+                    for px in x * scale..(x + 1) * scale {
+                        image.set_pixel(px, py, color);
+                    }
+                }
+            }
+        }
+        image
+    }
+
     /// Updates the state of all cells on the board according to the rules of the game.
     ///
     pub fn update(&mut self) -> () {
-        self.cells = self._update();
+        let (cells, stats, hash, ages) = self._update();
+        if let Some(ages) = ages {
+            self.ages = Some(ages);
+        }
+        if let Some(activity) = &mut self.activity {
+            activity.record(self.cells.as_slice(), cells.as_slice(), stats.generation);
+        }
+        self.cells.as_mut_slice().copy_from_slice(cells.as_slice());
+        self.stats = stats;
+        self.hashes.push(self.snapshot(hash));
+        if let Some(history) = &mut self.history {
+            history.push(self.stats.clone());
+        }
     }
 
     /// Updates the state of all cells on the board according to the rules of the game.
     ///
     /// # Returns
     ///
-    /// Updated board.
-    ///
-    pub fn _update(&self) -> Cells {
-        let cores = thread::available_parallelism().unwrap().get();
+    /// Updated board with the statistics and the hash of the next generation, and the ages of
+    /// the cells when they are counted.
+    ///
+    pub fn _update(&self) -> (Cells, Stats, u64, Option<Vec<Age>>) {
+        let chunks = self.get_chunks();
+        let parts: Vec<_> = if chunks.len() == 1 {
+            chunks.into_iter().map(|c| self.update_chunk(c)).collect()
+        } else {
+            thread::scope(|scope| {
+                let handlers: Vec<_> = chunks
+                    .into_iter()
+                    .map(|c| scope.spawn(move || self.update_chunk(c)))
+                    .collect();
+                handlers.into_iter().map(|h| h.join().unwrap()).collect()
+            })
+        };
+        let mut total = Tally::new(self.config.states());
+        let mut total_hash = 0;
         let mut results = Vec::new();
-        thread::scope(|scope| {
-            let mut handlers = Vec::with_capacity(cores);
-            for c in self.get_chunks() {
-                handlers.push(scope.spawn(move || {
-                    let mut res = vec![vec![0; self.cells.len()]; c.len()];
-                    for (i, x) in c.enumerate() {
-                        for (j, y) in (0..self.cells.len()).enumerate() {
-                            res[i][j] = self.update_cell(x, y);
-                        }
-                    }
-                    res
-                }));
-            }
-            for handler in handlers {
-                results.push(handler.join().unwrap());
+        let mut ages = Vec::new();
+        for (res, chunk_ages, tally, hash) in parts {
+            total.merge(&tally);
+            total_hash = hash::add(total_hash, hash);
+            results.push(res);
+            ages.push(chunk_ages);
+        }
+        let size = self.cells.len();
+        (
+            Cells::from_data(size, results.concat()),
+            total.into_stats(self.stats.generation + 1, size),
+            total_hash,
+            self.ages.as_ref().map(|_| ages.concat()),
+        )
+    }
+
+    /// Computes the next states of a range of columns.
+    ///
+    /// # Returns
+    ///
+    /// The next states and ages of the columns, the tally of their transitions and their hash.
+    ///
+    fn update_chunk(&self, c: Range<usize>) -> (Vec<Cell>, Vec<Age>, Tally, u64) {
+        let size = self.cells.len();
+        let mut res = Vec::with_capacity(c.len() * size);
+        let mut ages = Vec::new();
+        let mut tally = Tally::new(self.config.states());
+        let mut hash = 0;
+        for x in c {
+            let column = res.len();
+            for y in 0..size {
+                let state = self.update_cell(x, y);
+                tally.transition(x, y, self.cells[x][y], state);
+                res.push(state);
+                if let Some(old) = &self.ages {
+                    ages.push(match state {
+                        1 => old[x * size + y].saturating_add(1),
+                        _ => 0,
+                    });
+                }
             }
-            results.concat()
-        })
+            hash = hash::add(hash, self.hasher.column(x, &res[column..]));
+        }
+        (res, ages, tally, hash)
+    }
+
+    /// Sets the number of threads updates run on, `None` for one per core. Boards updated
+    /// from worker threads, like soups of a search, use a single thread.
+    ///
+    pub fn set_threads(&mut self, threads: Option<usize>) {
+        self.threads = threads.map(|t| t.max(1));
     }
 
     /// Creates chunks of board optimized for parallel computing
@@ -177,7 +650,9 @@ impl Board {
     /// Vector of chunks.
     ///
     pub fn get_chunks(&self) -> Vec<Range<usize>> {
-        let cores = thread::available_parallelism().unwrap().get();
+        let cores = self
+            .threads
+            .unwrap_or_else(|| thread::available_parallelism().unwrap().get());
         let board_length = self.cells.len();
         let chunk_size = f32::ceil( board_length as f32 / cores as f32) as usize;
         let mut chunks = Vec::new();
@@ -203,52 +678,9 @@ impl Board {
     ///
     /// Updated state of cell at the given coordinates.
     ///
-    fn update_cell(&self, x: usize, y: usize) -> Cell {
-        let neighbourhood_count = self.get_neighbourhood_count(x, y);
-        let state = self.cells[x as usize][y as usize];
-        match state {
-            0 => self.check_birth(neighbourhood_count) as u8,
-            1 => {
-                if self.check_survival(neighbourhood_count) {
-                    1
-                } else if self.config.cc > 2 {
-                    2
-                } else {
-                    0
-                }
-            }
-            _ => (state + 1) % self.config.cc,
-        }
-    }
-
-    /// Check if cell satisfies birth condition
-    ///
-    /// # Arguments
-    ///
-    /// * `neighbourhood_count` - represents the number of live neighbours.
-    ///
-    /// # Returns
-    ///
-    /// A boolean indicating whether the neighbourhood_count is within the configured birth range.
-    ///
-    #[inline]
-    fn check_birth(&self, neighbourhood_count: u16) -> bool {
-        self.config.bb.0 <= neighbourhood_count && neighbourhood_count <= self.config.bb.1
-    }
-
-    /// Check if cell satisfies survival condition
-    ///
-    /// # Arguments
-    ///
-    /// * `neighbourhood_count` - represents the number of live neighbours.
-    ///
-    /// # Returns
-    ///
-    /// A boolean indicating whether the neighbourhood_count is within the configured survival range.
-    ///
-    #[inline]
-    fn check_survival(&self, neighbourhood_count: u16) -> bool {
-        self.config.ss.0 <= neighbourhood_count && neighbourhood_count <= self.config.ss.1
+    pub(crate) fn update_cell(&self, x: usize, y: usize) -> Cell {
+        let neighbourhood = self.get_neighbourhood(x, y);
+        self.config.next_state(self.cells[x][y], &neighbourhood)
     }
 
     /// Returns the number of live cells in the neighbourhood of the cell at the given coordinates.
@@ -262,107 +694,60 @@ impl Board {
     ///
     /// The number of live cells in the neighbourhood of the cell
     ///
-    fn get_neighbourhood_count(&self, x: usize, y: usize) -> u16 {
-        let neighbourhood = match self.config.nn {
-            Neighbourhood::Neumann => self.get_neighbourhood_neumann(x, y),
-            Neighbourhood::Moore => self.get_neighbourhood_moore(x, y),
-        };
-        neighbourhood
+    pub(crate) fn get_neighbourhood_count(&self, x: usize, y: usize) -> u16 {
+        self.get_neighbourhood(x, y)
             .into_iter()
             .filter(|&s| s > 0)
-            .collect::<Vec<Cell>>()
-            .len() as u16
+            .count() as u16
     }
 
-    /// Returns the neighbourhood of the cell at the given coordinates.
+    /// Returns the neighbourhood of the cell at the given coordinates, as defined by the rule.
     ///
     /// # Arguments
     ///
     /// * `x` - The x coordinate of the cell.
     /// * `y` - The y coordinate of the cell.
-    /// * `callback` - Callback function counting neighbourhood.
     ///
     /// # Returns
     ///
     /// The neighbourhood of the cell at the given coordinates
     ///
-    fn get_neighbourhood(&self, x: usize, y: usize, callback: &DoGetNeighbourhood) -> Vec<u8> {
-        let min_bound = 0;
-        let r = self.config.rr as usize;
-        let max_bound = self.cells.len() - 1;
-
-        let x_min = if r > x { min_bound } else { x.sub(r) };
-        let y_min = if r > y { min_bound } else { y.sub(r) };
-
-        let x_max = min(x + r, max_bound);
-        let y_max = min(y + r, max_bound);
+    fn get_neighbourhood(&self, x: usize, y: usize) -> Vec<Cell> {
+        let r = self.config.radius() as isize;
+        let size = self.cells.len() as isize;
+        let positional = self.config.positional();
 
         let mut neighbourhood: Vec<Cell> = Vec::new();
-        for x_i in x_min..=x_max {
-            for y_i in y_min..=y_max {
-                let cell = callback(&self, x, y, x_i, y_i);
-                if let Some(s) = cell {
-                    neighbourhood.push(s)
+        for dx in -r..=r {
+            for dy in -r..=r {
+                if !self.config.contains(dx, dy) {
+                    continue;
+                }
+                let (x_i, y_i) = (x as isize + dx, y as isize + dy);
+                if (0..size).contains(&x_i) && (0..size).contains(&y_i) {
+                    neighbourhood.push(self.cells[x_i as usize][y_i as usize]);
+                } else if positional {
+                    neighbourhood.push(0);
                 }
             }
         }
         neighbourhood
     }
-
-    /// Returns the neighbourhood of the cell at the given coordinates, using the von Neumann neighbourhood.
-    ///
-    /// # Arguments
-    ///
-    /// * `x` - The x coordinate of the cell.
-    /// * `y` - The y coordinate of the cell.
-    ///
-    /// # Returns
-    ///
-    /// The neighbourhood of the cell at the given coordinates
-    ///
-    fn get_neighbourhood_neumann(&self, x: usize, y: usize) -> Vec<u8> {
-        self.get_neighbourhood(x, y, &|board, x, y, x_i, y_i| {
-            if board.config.mm == 0 && x_i == x && y_i == y {
-                return None;
-            }
-            if x_i.abs_diff(x) + y_i.abs_diff(y) <= board.config.rr as usize {
-                return Option::from(board.cells[x_i][y_i]);
-            }
-            None
-        })
-    }
-
-    /// Returns the neighbourhood of the cell at the given coordinates, using the Moore neighbourhood.
-    ///
-    /// # Arguments
-    ///
-    /// * `x` - The x coordinate of the cell.
-    /// * `y` - The y coordinate of the cell.
-    ///
-    /// # Returns
-    ///
-    /// The neighbourhood of the cell at the given coordinates
-    ///
-    fn get_neighbourhood_moore(&self, x: usize, y: usize) -> Vec<u8> {
-        self.get_neighbourhood(x, y, &|board, x, y, x_i, y_i| {
-            if board.config.mm == 0 && x_i == x && y_i == y {
-                return None;
-            }
-            Option::from(board.cells[x_i][y_i])
-        })
-    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::ltl_engine::cells::Region;
+    use crate::ltl_engine::image::{BLACK, LIGHT_GREY, WHITE};
+    use crate::ltl_engine::neighbourhood::Neighbourhood;
     use rand::rngs::mock::StepRng;
 
-    fn test_cells() -> Cells {
+    fn test_cells() -> Vec<Vec<Cell>> {
         vec![vec![0, 1, 2], vec![3, 4, 5], vec![6, 7, 8]]
     }
 
-    fn test_cells_default(size: usize) -> Cells {
+    fn test_cells_default(size: usize) -> Vec<Vec<Cell>> {
         vec![vec![0; size]; size]
     }
 
@@ -405,28 +790,22 @@ mod tests {
         let cells = test_cells_default(3);
         let config = test_config_conways();
 
-        assert_eq!(*board.cells, cells);
+        assert_eq!(board.cells, cells);
         assert_eq!(board.config, config);
     }
 
     #[test]
     fn reset() {
-        let mut board = Board {
-            cells: test_cells(),
-            config: test_config_conways(),
-        };
+        let mut board = Board::from_cells(test_cells(), test_config_conways());
         let cells = test_cells_default(3);
         board.reset();
 
-        assert_eq!(*board.cells, cells);
+        assert_eq!(board.cells, cells);
     }
 
     #[test]
     fn get_cell() {
-        let board = Board {
-            cells: test_cells(),
-            config: test_config_conways(),
-        };
+        let board = Board::from_cells(test_cells(), test_config_conways());
         assert_eq!(board.get_cell(0, 0), 0);
         assert_eq!(board.get_cell(1, 1), 4);
         assert_eq!(board.get_cell(1, 2), 5);
@@ -435,10 +814,7 @@ mod tests {
     #[test]
     #[should_panic]
     fn get_cell_out_of_range() {
-        let board = Board {
-            cells: test_cells(),
-            config: test_config_conways(),
-        };
+        let board = Board::from_cells(test_cells(), test_config_conways());
         board.get_cell(5, 5);
     }
 
@@ -470,14 +846,14 @@ mod tests {
         let mut board = Board::new(3, test_config_conways());
         let mut rnd = StepRng::new(u64::MAX, 0);
         board._randomize(&mut rnd);
-        assert_eq!(*board.cells, vec![vec![1; 3]; 3]);
+        assert_eq!(board.cells, vec![vec![1; 3]; 3]);
     }
 
     #[test]
     fn _randomize_multistate() {
-        let mut board = Board {
-            cells: test_cells_default(3),
-            config: Config {
+        let mut board = Board::from_cells(
+            test_cells_default(3),
+            Config {
                 rr: 1,
                 cc: 5,
                 mm: 0,
@@ -485,11 +861,11 @@ mod tests {
                 bb: (3, 3),
                 nn: Neighbourhood::Moore,
             },
-        };
+        );
         let mut rnd = StepRng::new(u64::MAX, 1);
         board._randomize(&mut rnd);
         let right = vec![vec![4, 0, 0], vec![0, 0, 0], vec![0, 0, 0]];
-        assert_eq!(*board.cells, right);
+        assert_eq!(board.cells, right);
     }
 
     #[test]
@@ -503,9 +879,9 @@ mod tests {
 
     #[test]
     fn cell_up_multistate() {
-        let mut board = Board {
-            cells: test_cells_default(3),
-            config: Config {
+        let mut board = Board::from_cells(
+            test_cells_default(3),
+            Config {
                 rr: 1,
                 cc: 3,
                 mm: 0,
@@ -513,7 +889,7 @@ mod tests {
                 bb: (3, 3),
                 nn: Neighbourhood::Moore,
             },
-        };
+        );
         board.cell_up(0, 0);
         assert_eq!(board.get_cell(0, 0), 1);
         board.cell_up(0, 0);
@@ -539,9 +915,9 @@ mod tests {
 
     #[test]
     fn cell_down_multistate() {
-        let mut board = Board {
-            cells: test_cells_default(3),
-            config: Config {
+        let mut board = Board::from_cells(
+            test_cells_default(3),
+            Config {
                 rr: 1,
                 cc: 3,
                 mm: 0,
@@ -549,7 +925,7 @@ mod tests {
                 bb: (3, 3),
                 nn: Neighbourhood::Moore,
             },
-        };
+        );
         board.set_cell(0, 0, 2);
 
         board.cell_down(0, 0);
@@ -569,8 +945,8 @@ mod tests {
             nn: Neighbourhood::Moore,
         };
         let cells = test_cells();
-        let board: Board = Board { config, cells };
-        let mut neighbourhood = board.get_neighbourhood_moore(1, 1);
+        let board: Board = Board::from_cells(cells, config);
+        let mut neighbourhood = board.get_neighbourhood(1, 1);
         assert_eq!(neighbourhood.len(), 8);
 
         let right: Vec<u8> = vec![0, 1, 2, 3, 5, 6, 7, 8];
@@ -589,8 +965,8 @@ mod tests {
             nn: Neighbourhood::Moore,
         };
         let cells = test_cells();
-        let board: Board = Board { config, cells };
-        let mut neighbourhood = board.get_neighbourhood_moore(1, 1);
+        let board: Board = Board::from_cells(cells, config);
+        let mut neighbourhood = board.get_neighbourhood(1, 1);
         assert_eq!(neighbourhood.len(), 9);
 
         let mut right: Vec<u8> = vec![0, 1, 2, 3, 4, 5, 6, 7, 8];
@@ -601,11 +977,8 @@ mod tests {
 
     #[test]
     fn get_neighbourhood_moore_left_top_corner() {
-        let board: Board = Board {
-            config: test_config_moore_included(),
-            cells: test_cells(),
-        };
-        let mut neighbourhood = board.get_neighbourhood_moore(0, 0);
+        let board: Board = Board::from_cells(test_cells(), test_config_moore_included());
+        let mut neighbourhood = board.get_neighbourhood(0, 0);
         assert_eq!(neighbourhood.len(), 4);
 
         let mut right: Vec<u8> = vec![0, 1, 3, 4];
@@ -616,11 +989,8 @@ mod tests {
 
     #[test]
     fn get_neighbourhood_moore_right_top_corner() {
-        let board: Board = Board {
-            config: test_config_moore_included(),
-            cells: test_cells(),
-        };
-        let mut neighbourhood = board.get_neighbourhood_moore(2, 0);
+        let board: Board = Board::from_cells(test_cells(), test_config_moore_included());
+        let mut neighbourhood = board.get_neighbourhood(2, 0);
         assert_eq!(neighbourhood.len(), 4);
 
         let mut right: Vec<u8> = vec![3, 6, 4, 7];
@@ -631,11 +1001,8 @@ mod tests {
 
     #[test]
     fn get_neighbourhood_moore_left_bottom_corner() {
-        let board: Board = Board {
-            config: test_config_moore_included(),
-            cells: test_cells(),
-        };
-        let mut neighbourhood = board.get_neighbourhood_moore(0, 2);
+        let board: Board = Board::from_cells(test_cells(), test_config_moore_included());
+        let mut neighbourhood = board.get_neighbourhood(0, 2);
         assert_eq!(neighbourhood.len(), 4);
 
         let mut right: Vec<u8> = vec![1, 4, 2, 5];
@@ -646,11 +1013,8 @@ mod tests {
 
     #[test]
     fn get_neighbourhood_moore_right_bottom_corner() {
-        let board: Board = Board {
-            config: test_config_moore_included(),
-            cells: test_cells(),
-        };
-        let mut neighbourhood = board.get_neighbourhood_moore(2, 2);
+        let board: Board = Board::from_cells(test_cells(), test_config_moore_included());
+        let mut neighbourhood = board.get_neighbourhood(2, 2);
         assert_eq!(neighbourhood.len(), 4);
 
         let mut right: Vec<u8> = vec![4, 7, 5, 8];
@@ -677,8 +1041,8 @@ mod tests {
                 i += 1;
             }
         }
-        let board: Board = Board { cells, config };
-        let mut neighbourhood = board.get_neighbourhood_moore(3, 3);
+        let board: Board = Board::from_cells(cells, config);
+        let mut neighbourhood = board.get_neighbourhood(3, 3);
         assert_eq!(neighbourhood.len(), 25);
 
         let mut right: Vec<u8> = vec![
@@ -700,11 +1064,8 @@ mod tests {
             bb: (3, 3),
             nn: Neighbourhood::Neumann,
         };
-        let board: Board = Board {
-            config,
-            cells: test_cells(),
-        };
-        let mut neighbourhood = board.get_neighbourhood_neumann(1, 1);
+        let board: Board = Board::from_cells(test_cells(), config);
+        let mut neighbourhood = board.get_neighbourhood(1, 1);
         assert_eq!(neighbourhood.len(), 5);
 
         let mut right: Vec<u8> = vec![1, 3, 4, 5, 7];
@@ -723,11 +1084,8 @@ mod tests {
             bb: (3, 3),
             nn: Neighbourhood::Neumann,
         };
-        let board: Board = Board {
-            config,
-            cells: test_cells(),
-        };
-        let mut neighbourhood = board.get_neighbourhood_neumann(1, 1);
+        let board: Board = Board::from_cells(test_cells(), config);
+        let mut neighbourhood = board.get_neighbourhood(1, 1);
         assert_eq!(neighbourhood.len(), 4);
 
         let mut right: Vec<u8> = vec![1, 3, 5, 7];
@@ -738,11 +1096,8 @@ mod tests {
 
     #[test]
     fn get_neighbourhood_neumann_left_top_corner() {
-        let board: Board = Board {
-            config: test_config_neumann_included(),
-            cells: test_cells(),
-        };
-        let mut neighbourhood = board.get_neighbourhood_neumann(0, 0);
+        let board: Board = Board::from_cells(test_cells(), test_config_neumann_included());
+        let mut neighbourhood = board.get_neighbourhood(0, 0);
         assert_eq!(neighbourhood.len(), 3);
 
         let mut right: Vec<u8> = vec![0, 1, 3];
@@ -753,11 +1108,8 @@ mod tests {
 
     #[test]
     fn get_neighbourhood_neumann_right_top_corner() {
-        let board: Board = Board {
-            config: test_config_neumann_included(),
-            cells: test_cells(),
-        };
-        let mut neighbourhood = board.get_neighbourhood_neumann(2, 0);
+        let board: Board = Board::from_cells(test_cells(), test_config_neumann_included());
+        let mut neighbourhood = board.get_neighbourhood(2, 0);
         assert_eq!(neighbourhood.len(), 3);
 
         let mut right: Vec<u8> = vec![3, 6, 7];
@@ -768,11 +1120,8 @@ mod tests {
 
     #[test]
     fn get_neighbourhood_neumann_left_bottom_corner() {
-        let board: Board = Board {
-            config: test_config_neumann_included(),
-            cells: test_cells(),
-        };
-        let mut neighbourhood = board.get_neighbourhood_neumann(0, 2);
+        let board: Board = Board::from_cells(test_cells(), test_config_neumann_included());
+        let mut neighbourhood = board.get_neighbourhood(0, 2);
         assert_eq!(neighbourhood.len(), 3);
 
         let mut right: Vec<u8> = vec![1, 2, 5];
@@ -783,11 +1132,8 @@ mod tests {
 
     #[test]
     fn get_neighbourhood_neumann_right_bottom_corner() {
-        let board: Board = Board {
-            config: test_config_neumann_included(),
-            cells: test_cells(),
-        };
-        let mut neighbourhood = board.get_neighbourhood_neumann(2, 2);
+        let board: Board = Board::from_cells(test_cells(), test_config_neumann_included());
+        let mut neighbourhood = board.get_neighbourhood(2, 2);
         assert_eq!(neighbourhood.len(), 3);
 
         let mut right: Vec<u8> = vec![5, 7, 8];
@@ -814,8 +1160,8 @@ mod tests {
                 i += 1;
             }
         }
-        let board: Board = Board { cells, config };
-        let mut neighbourhood = board.get_neighbourhood_neumann(3, 3);
+        let board: Board = Board::from_cells(cells, config);
+        let mut neighbourhood = board.get_neighbourhood(3, 3);
         assert_eq!(neighbourhood.len(), 13);
 
         let mut right: Vec<u8> = vec![10, 16, 17, 18, 22, 23, 24, 25, 26, 30, 31, 32, 38];
@@ -834,10 +1180,10 @@ mod tests {
             bb: (3, 3),
             nn: Neighbourhood::Moore,
         };
-        let board = Board {
+        let board = Board::from_cells(
+            vec![vec![0, 0, 0], vec![1, 2, 3], vec![4, 0, 0]],
             config,
-            cells: vec![vec![0, 0, 0], vec![1, 2, 3], vec![4, 0, 0]],
-        };
+        );
         let neighbourhood_count = board.get_neighbourhood_count(1, 1);
         assert_eq!(neighbourhood_count, 4);
     }
@@ -852,10 +1198,10 @@ mod tests {
             bb: (3, 3),
             nn: Neighbourhood::Moore,
         };
-        let board = Board {
+        let board = Board::from_cells(
+            vec![vec![0, 0, 0], vec![1, 2, 3], vec![4, 0, 0]],
             config,
-            cells: vec![vec![0, 0, 0], vec![1, 2, 3], vec![4, 0, 0]],
-        };
+        );
         let neighbourhood_count = board.get_neighbourhood_count(1, 1);
         assert_eq!(neighbourhood_count, 3);
     }
@@ -870,10 +1216,7 @@ mod tests {
             bb: (3, 3),
             nn: Neighbourhood::Moore,
         };
-        let board = Board {
-            config,
-            cells: test_cells_default(3),
-        };
+        let board = Board::from_cells(test_cells_default(3), config);
         let neighbourhood_count = board.get_neighbourhood_count(1, 1);
         assert_eq!(neighbourhood_count, 0);
     }
@@ -888,10 +1231,10 @@ mod tests {
             bb: (3, 3),
             nn: Neighbourhood::Moore,
         };
-        let board = Board {
+        let board = Board::from_cells(
+            vec![vec![1, 2, 3], vec![4, 5, 6], vec![7, 8, 9]],
             config,
-            cells: vec![vec![1, 2, 3], vec![4, 5, 6], vec![7, 8, 9]],
-        };
+        );
         let neighbourhood_count = board.get_neighbourhood_count(1, 1);
         assert_eq!(neighbourhood_count, 9);
     }
@@ -906,10 +1249,10 @@ mod tests {
             bb: (3, 3),
             nn: Neighbourhood::Neumann,
         };
-        let board = Board {
+        let board = Board::from_cells(
+            vec![vec![0, 5, 0], vec![1, 2, 3], vec![4, 0, 0]],
             config,
-            cells: vec![vec![0, 5, 0], vec![1, 2, 3], vec![4, 0, 0]],
-        };
+        );
         let neighbourhood_count = board.get_neighbourhood_count(1, 1);
         assert_eq!(neighbourhood_count, 4);
     }
@@ -924,10 +1267,10 @@ mod tests {
             bb: (3, 3),
             nn: Neighbourhood::Neumann,
         };
-        let board = Board {
+        let board = Board::from_cells(
+            vec![vec![0, 5, 0], vec![1, 2, 3], vec![4, 0, 0]],
             config,
-            cells: vec![vec![0, 5, 0], vec![1, 2, 3], vec![4, 0, 0]],
-        };
+        );
         let neighbourhood_count = board.get_neighbourhood_count(1, 1);
         assert_eq!(neighbourhood_count, 3);
     }
@@ -942,10 +1285,7 @@ mod tests {
             bb: (3, 3),
             nn: Neighbourhood::Neumann,
         };
-        let board = Board {
-            config,
-            cells: test_cells_default(3),
-        };
+        let board = Board::from_cells(test_cells_default(3), config);
         let neighbourhood_count = board.get_neighbourhood_count(1, 1);
         assert_eq!(neighbourhood_count, 0);
     }
@@ -960,14 +1300,173 @@ mod tests {
             bb: (3, 3),
             nn: Neighbourhood::Neumann,
         };
-        let board = Board {
+        let board = Board::from_cells(
+            vec![vec![1, 2, 3], vec![4, 5, 6], vec![7, 8, 9]],
             config,
-            cells: vec![vec![1, 2, 3], vec![4, 5, 6], vec![7, 8, 9]],
-        };
+        );
         let neighbourhood_count = board.get_neighbourhood_count(1, 1);
         assert_eq!(neighbourhood_count, 5);
     }
 
+    #[test]
+    fn set_cells() {
+        let mut board = Board::new(2, test_config_conways());
+        board.set_cells(&[0, 1, 1, 0]).unwrap();
+        assert_eq!(board.cells, vec![vec![0, 1], vec![1, 0]]);
+    }
+
+    #[test]
+    fn set_cells_wrong_shape() {
+        let mut board = Board::new(2, test_config_conways());
+        assert_eq!(
+            board.set_cells(&[0, 1, 1]),
+            Err(CellsError::Shape {
+                expected: 4,
+                actual: 3
+            })
+        );
+    }
+
+    #[test]
+    fn set_cells_out_of_config_range() {
+        let mut board = Board::new(2, test_config_conways());
+        assert_eq!(
+            board.set_cells(&[0, 0, 2, 0]),
+            Err(CellsError::State {
+                x: 1,
+                y: 0,
+                value: 2,
+                states: 2
+            })
+        );
+        assert_eq!(board.cells, test_cells_default(2));
+    }
+
+    #[test]
+    fn update_keeps_buffer() {
+        let mut board = Board::new(3, test_config_conways());
+        let ptr = board.cells.as_slice().as_ptr();
+        board.update();
+        assert_eq!(board.cells.as_slice().as_ptr(), ptr);
+    }
+
+    #[test]
+    fn render_image() {
+        let board = Board::from_cells(vec![vec![0, 1], vec![2, 0]], test_config_conways());
+        let palette = Palette::new(vec![WHITE, BLACK, LIGHT_GREY]);
+        let image = board.render_image(&palette, 2);
+
+        assert_eq!((image.width, image.height), (4, 4));
+        assert_eq!(image.get_pixel(0, 0), WHITE);
+        assert_eq!(image.get_pixel(1, 3), BLACK);
+        assert_eq!(image.get_pixel(3, 1), LIGHT_GREY);
+        assert_eq!(image.get_pixel(3, 3), WHITE);
+    }
+
+    #[test]
+    fn from_image_threshold_scale() {
+        let mut image = Image::new(2, 2, WHITE);
+        image.set_pixel(1, 0, BLACK);
+        let board = Board::from_image(
+            &image,
+            4,
+            test_config_conways(),
+            Fit::Scale,
+            &Quantize::Threshold(128),
+        )
+        .unwrap();
+        let right = vec![
+            vec![0, 0, 0, 0],
+            vec![0, 0, 0, 0],
+            vec![1, 1, 0, 0],
+            vec![1, 1, 0, 0],
+        ];
+        assert_eq!(board.cells, right);
+    }
+
+    #[test]
+    fn from_image_crop() {
+        let mut image = Image::new(4, 4, WHITE);
+        image.set_pixel(1, 1, BLACK);
+        image.set_pixel(2, 2, BLACK);
+        let board = Board::from_image(
+            &image,
+            2,
+            test_config_conways(),
+            Fit::Crop,
+            &Quantize::Threshold(128),
+        )
+        .unwrap();
+        assert_eq!(board.cells, vec![vec![1, 0], vec![0, 1]]);
+
+        let padded = Board::from_image(
+            &image,
+            6,
+            test_config_conways(),
+            Fit::Crop,
+            &Quantize::Threshold(128),
+        )
+        .unwrap();
+        assert_eq!(padded.get_cell(2, 2), 1);
+        assert_eq!(padded.get_cell(3, 3), 1);
+        assert_eq!(padded.cells.iter().flatten().filter(|&&s| s > 0).count(), 2);
+    }
+
+    #[test]
+    fn from_image_nearest_palette() {
+        let mut image = Image::new(2, 1, [10, 10, 10, 255]);
+        image.set_pixel(1, 0, [190, 190, 210, 255]);
+        let palette = Palette::new(vec![WHITE, BLACK, LIGHT_GREY]);
+        let config = Config {
+            cc: 3,
+            ..test_config_conways()
+        };
+        let board =
+            Board::from_image(&image, 2, config, Fit::Crop, &Quantize::Nearest(palette)).unwrap();
+        assert_eq!(board.cells, vec![vec![0, 1], vec![0, 2]]);
+    }
+
+    #[test]
+    fn from_image_too_many_states() {
+        let image = Image::new(1, 1, LIGHT_GREY);
+        let palette = Palette::new(vec![WHITE, BLACK, LIGHT_GREY]);
+        let result = Board::from_image(
+            &image,
+            1,
+            test_config_conways(),
+            Fit::Scale,
+            &Quantize::Nearest(palette),
+        );
+        assert!(matches!(
+            result,
+            Err(ImageError::TooManyStates {
+                required: 3,
+                available: 2
+            })
+        ));
+    }
+
+    #[test]
+    fn from_image_last_palette_state() {
+        let image = Image::new(1, 1, BLACK);
+        let mut colors = vec![WHITE; 255];
+        colors.push(BLACK);
+        let result = Board::from_image(
+            &image,
+            1,
+            test_config_conways(),
+            Fit::Scale,
+            &Quantize::Nearest(Palette::new(colors)),
+        );
+        assert!(matches!(
+            result,
+            Err(ImageError::TooManyStates {
+                required: 256,
+                available: 2
+            })
+        ));
+    }
+
     #[test]
     fn update_all_alive() {
         let config = Config {
@@ -979,11 +1478,11 @@ mod tests {
             nn: Neighbourhood::Moore,
         };
         let mut board: Board = Board::new(10, config);
-        board.cells = test_cells_default(10);
+        board.cells = test_cells_default(10).into();
         let right = vec![vec![1; 10]; 10];
         board.update();
 
-        assert_eq!(*board.cells, right);
+        assert_eq!(board.cells, right);
     }
 
     #[test]
@@ -997,18 +1496,18 @@ mod tests {
             nn: Neighbourhood::Moore,
         };
         let mut board: Board = Board::new(10, config);
-        board.cells = vec![vec![2; 10]; 10];
+        board.cells = vec![vec![2; 10]; 10].into();
         let right = vec![vec![0; 10]; 10];
         board.update();
 
-        assert_eq!(*board.cells, right);
+        assert_eq!(board.cells, right);
     }
 
     #[test]
     fn update_aging() {
-        let mut board = Board {
-            cells: vec![vec![1; 3]; 3],
-            config: Config {
+        let mut board = Board::from_cells(
+            vec![vec![1; 3]; 3],
+            Config {
                 rr: 1,
                 cc: 3,
                 mm: 0,
@@ -1016,10 +1515,10 @@ mod tests {
                 bb: (3, 3),
                 nn: Neighbourhood::Moore,
             },
-        };
+        );
         board.update();
         let right = vec![vec![1, 2, 1], vec![2, 2, 2], vec![1, 2, 1]];
-        assert_eq!(*board.cells, right);
+        assert_eq!(board.cells, right);
     }
 
     #[test]
@@ -1029,12 +1528,219 @@ mod tests {
         let stick_vertical = vec![vec![0, 0, 0], vec![1, 1, 1], vec![0, 0, 0]];
         let stick_horizontal = vec![vec![0, 1, 0], vec![0, 1, 0], vec![0, 1, 0]];
 
-        board.cells = stick_vertical.clone();
+        board.cells = stick_vertical.clone().into();
+        board.update();
+        assert_eq!(board.cells, stick_horizontal);
+        board.update();
+        assert_eq!(board.cells, stick_vertical);
+        board.update();
+        assert_eq!(board.cells, stick_horizontal);
+    }
+
+    #[test]
+    fn update_single_thread() {
+        let mut parallel = Board::new(40, test_config_conways());
+        parallel.set_threads(Some(8));
+        parallel.randomize(Some(7));
+        let mut serial = Board::from_cells(parallel.cells.to_vec(), test_config_conways());
+        serial.set_threads(Some(1));
+        assert_eq!(serial.get_chunks(), vec![0..40]);
+        for _ in 0..5 {
+            parallel.update();
+            serial.update();
+        }
+        assert_eq!(serial.cells, parallel.cells);
+        assert_eq!(serial.stats(), parallel.stats());
+        assert_eq!(serial.hash(), parallel.hash());
+    }
+
+    #[test]
+    fn update_stats() {
+        let mut board = Board::from_cells(
+            vec![vec![0, 0, 0], vec![1, 1, 1], vec![0, 0, 0]],
+            test_config_conways(),
+        );
+        board.update();
+        let stats = board.stats();
+        assert_eq!(board.generation(), 1);
+        assert_eq!(stats.counts, vec![6, 3]);
+        assert_eq!(stats.population, 3);
+        assert_eq!((stats.births, stats.deaths, stats.survivors), (2, 2, 1));
+        assert_eq!(
+            stats.bounding_box,
+            Some(Region {
+                x: 0,
+                y: 1,
+                width: 3,
+                height: 1
+            })
+        );
+    }
+
+    #[test]
+    fn set_cell_updates_stats() {
+        let mut board = Board::new(4, test_config_conways());
+        board.set_cell(1, 2, 1);
+        board.set_cell(3, 3, 1);
+        board.set_cell(3, 3, 0);
+        assert_eq!(board.stats(), &Stats::of(&board.cells, 2, 0));
+        assert_eq!(board.stats().population, 1);
+    }
+
+    #[test]
+    fn stats_history() {
+        let mut board = Board::new(5, test_config_conways());
+        for x in 1..4 {
+            board.set_cell(x, 2, 1);
+        }
+        board.record_stats(3);
+        for _ in 0..4 {
+            board.update();
+        }
+        let history = board.stats_history().unwrap();
+        let generations: Vec<u64> = history.iter().map(|s| s.generation).collect();
+        assert_eq!(generations, vec![2, 3, 4]);
+
+        board.reset();
+        assert_eq!(board.generation(), 0);
+        assert_eq!(board.stats_history().unwrap().len(), 1);
+        board.record_stats(0);
+        assert_eq!(board.stats_history(), None);
+    }
+
+    #[test]
+    fn cell_ages() {
+        let mut board = Board::new(5, test_config_conways());
+        for x in 1..4 {
+            board.set_cell(x, 2, 1);
+        }
+        assert_eq!(board.get_age(2, 2), None);
+        board.track_ages(true);
+        for _ in 0..3 {
+            board.update();
+        }
+        // The centre of the blinker never dies, its tips are reborn every other generation.
+        assert_eq!(board.get_age(2, 2), Some(4));
+        assert_eq!(board.get_age(2, 1), Some(1));
+        assert_eq!(board.get_age(1, 2), Some(0));
+        board.set_cell(0, 0, 1);
+        board.set_cell(2, 2, 1);
+        assert_eq!(board.get_age(0, 0), Some(1));
+        assert_eq!(board.get_age(2, 2), Some(4));
+        board.reset_ages();
+        assert_eq!(board.get_age(2, 2), Some(1));
+
+        let mut ages = vec![0; 25];
+        ages[2 * 5 + 2] = Age::MAX;
+        board.ages = Some(ages);
         board.update();
-        assert_eq!(*board.cells.clone(), stick_horizontal.clone());
+        assert_eq!(board.get_age(2, 2), Some(Age::MAX));
+        board.reset();
+        assert!(board.ages().unwrap().iter().all(|&age| age == 0));
+        board.track_ages(false);
+        assert_eq!(board.ages(), None);
+    }
+
+    #[test]
+    #[should_panic]
+    fn get_age_out_of_range() {
+        let mut board = Board::new(5, test_config_conways());
+        board.track_ages(true);
+        board.get_age(0, 5);
+    }
+
+    #[test]
+    fn decaying_cells_have_no_age() {
+        let mut config = test_config_conways();
+        config.cc = 3;
+        let mut board = Board::new(5, config);
+        board.set_cell(2, 2, 1);
+        board.track_ages(true);
         board.update();
-        assert_eq!(*board.cells, stick_vertical);
+        assert_eq!(board.get_cell(2, 2), 2);
+        assert_eq!(board.get_age(2, 2), Some(0));
+    }
+
+    #[test]
+    fn update_keeps_hash() {
+        let mut board = Board::new(6, test_config_conways());
+        board.randomize(Some(3));
         board.update();
-        assert_eq!(*board.cells, stick_horizontal);
+        assert_eq!(board.hash(), StateHasher::new(6).hash(&board.cells));
+        board.set_cell(2, 2, 1);
+        assert_eq!(board.hash(), StateHasher::new(6).hash(&board.cells));
+    }
+
+    #[test]
+    fn detect_blinker_cycle() {
+        let mut board = Board::new(5, test_config_conways());
+        for x in 1..4 {
+            board.set_cell(x, 2, 1);
+        }
+        board.update();
+        assert_eq!(board.detect_cycle(8), None);
+        board.update();
+        assert_eq!(
+            board.detect_cycle(8),
+            Some(Cycle {
+                start: 0,
+                period: 2
+            })
+        );
+        assert_eq!(board.detect_cycle(1), None);
+    }
+
+    #[test]
+    fn run_until_still_life() {
+        let mut board = Board::new(6, test_config_conways());
+        for (x, y) in [(1, 1), (1, 2), (2, 1)] {
+            board.set_cell(x, y, 1);
+        }
+        let cycle = board.run_until_periodic(10, 4);
+        assert_eq!(
+            cycle,
+            Some(Cycle {
+                start: 1,
+                period: 1
+            })
+        );
+        assert_eq!(board.generation(), 2);
+    }
+
+    #[test]
+    fn detect_glider_translation() {
+        let mut board = Board::new(16, test_config_conways());
+        for (x, y) in [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)] {
+            board.set_cell(x + 3, y + 3, 1);
+        }
+        for _ in 0..3 {
+            board.update();
+            assert_eq!(board.detect_translation(8), None);
+        }
+        board.update();
+        let translation = board.detect_translation(8).unwrap();
+        assert_eq!(
+            translation,
+            Translation {
+                start: 0,
+                period: 4,
+                dx: 1,
+                dy: 1
+            }
+        );
+        assert_eq!(translation.speed(), 0.25);
+        assert_eq!(board.detect_cycle(8), None);
+    }
+
+    #[test]
+    fn translation_near_edge_is_ignored() {
+        let mut board = Board::new(5, test_config_conways());
+        for (x, y) in [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)] {
+            board.set_cell(x + 1, y + 1, 1);
+        }
+        for _ in 0..8 {
+            board.update();
+        }
+        assert_eq!(board.detect_translation(8), None);
     }
 }
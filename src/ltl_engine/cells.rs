@@ -0,0 +1,309 @@
+use serde::Serialize;
+use std::error::Error;
+use std::fmt;
+use std::ops::{Index, IndexMut};
+use std::slice::{ChunksExact, ChunksExactMut};
+
+pub type Cell = u8;
+
+/// Square grid of cells stored in one contiguous buffer. Column `x` holds the cells
+/// `(x, 0)..(x, size)`, so `cells[x][y]` addresses the cell at the given coordinates.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cells {
+    size: usize,
+    data: Vec<Cell>,
+}
+
+impl Cells {
+    /// Create a new grid of dead cells.
+    ///
+    /// # Arguments
+    ///
+    /// * `size` - The size of the grid. The grid will be `size` by `size`.
+    ///
+    pub fn new(size: usize) -> Self {
+        Cells {
+            size,
+            data: vec![0; size * size],
+        }
+    }
+
+    /// Create a grid from a buffer of cells laid out column by column.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `data` does not hold `size * size` cells.
+    ///
+    pub fn from_data(size: usize, data: Vec<Cell>) -> Self {
+        if data.len() != size * size {
+            panic!("Cells buffer doesnt match the grid size");
+        }
+        Cells { size, data }
+    }
+
+    /// Returns the size of the grid.
+    ///
+    pub fn len(&self) -> usize {
+        self.size
+    }
+
+    /// Returns true if the grid has no cells.
+    ///
+    pub fn is_empty(&self) -> bool {
+        self.size == 0
+    }
+
+    /// Returns all cells, column by column.
+    ///
+    pub fn as_slice(&self) -> &[Cell] {
+        &self.data
+    }
+
+    /// Returns all cells, column by column.
+    ///
+    pub fn as_mut_slice(&mut self) -> &mut [Cell] {
+        &mut self.data
+    }
+
+    /// Iterates over the columns of the grid.
+    ///
+    pub fn iter(&self) -> ChunksExact<'_, Cell> {
+        self.data.chunks_exact(self.size.max(1))
+    }
+
+    /// Iterates mutably over the columns of the grid.
+    ///
+    pub fn iter_mut(&mut self) -> ChunksExactMut<'_, Cell> {
+        self.data.chunks_exact_mut(self.size.max(1))
+    }
+
+    /// Copies the cells as nested vectors, column by column.
+    ///
+    pub fn to_vec(&self) -> Vec<Vec<Cell>> {
+        self.iter().map(|column| column.to_vec()).collect()
+    }
+}
+
+impl Index<usize> for Cells {
+    type Output = [Cell];
+
+    fn index(&self, x: usize) -> &[Cell] {
+        &self.data[x * self.size..(x + 1) * self.size]
+    }
+}
+
+impl IndexMut<usize> for Cells {
+    fn index_mut(&mut self, x: usize) -> &mut [Cell] {
+        &mut self.data[x * self.size..(x + 1) * self.size]
+    }
+}
+
+impl From<Vec<Vec<Cell>>> for Cells {
+    /// Converts nested columns of cells to a grid.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the columns don't form a square.
+    ///
+    fn from(columns: Vec<Vec<Cell>>) -> Self {
+        let size = columns.len();
+        if columns.iter().any(|column| column.len() != size) {
+            panic!("Cells must form a square grid");
+        }
+        Cells {
+            size,
+            data: columns.concat(),
+        }
+    }
+}
+
+impl PartialEq<Vec<Vec<Cell>>> for Cells {
+    fn eq(&self, other: &Vec<Vec<Cell>>) -> bool {
+        other.len() == self.size && self.iter().zip(other).all(|(a, b)| a == &b[..])
+    }
+}
+
+/// A rectangular region of the board, in cells.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Region {
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Region {
+    /// Creates a region covering a whole board of the given size.
+    ///
+    pub fn full(size: usize) -> Region {
+        Region {
+            x: 0,
+            y: 0,
+            width: size,
+            height: size,
+        }
+    }
+
+    /// Clamps the region to a board of the given size.
+    ///
+    pub(crate) fn clamp(&self, size: usize) -> Region {
+        let x = self.x.min(size);
+        let y = self.y.min(size);
+        Region {
+            x,
+            y,
+            width: self.width.min(size - x),
+            height: self.height.min(size - y),
+        }
+    }
+
+    /// Returns the smallest region covering both regions.
+    ///
+    pub fn union(&self, other: &Region) -> Region {
+        let x = self.x.min(other.x);
+        let y = self.y.min(other.y);
+        Region {
+            x,
+            y,
+            width: (self.x + self.width).max(other.x + other.width) - x,
+            height: (self.y + self.height).max(other.y + other.height) - y,
+        }
+    }
+
+    /// Returns true if the cell lies on the border of the region.
+    ///
+    pub fn on_edge(&self, x: usize, y: usize) -> bool {
+        x == self.x || y == self.y || x + 1 == self.x + self.width || y + 1 == self.y + self.height
+    }
+}
+
+/// Errors raised by bulk assignment of cells.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub enum CellsError {
+    Shape {
+        expected: usize,
+        actual: usize,
+    },
+    State {
+        x: usize,
+        y: usize,
+        value: Cell,
+        states: u8,
+    },
+}
+
+impl fmt::Display for CellsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CellsError::Shape { expected, actual } => {
+                write!(f, "Expected {} cells, got {}", expected, actual)
+            }
+            CellsError::State {
+                x,
+                y,
+                value,
+                states,
+            } => write!(
+                f,
+                "Cell ({}, {}) has state {}, config supports {} states",
+                x, y, value, states
+            ),
+        }
+    }
+}
+
+impl Error for CellsError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_is_dead() {
+        let cells = Cells::new(3);
+        assert_eq!(cells.len(), 3);
+        assert_eq!(cells.as_slice(), &[0; 9]);
+    }
+
+    #[test]
+    fn from_columns() {
+        let cells = Cells::from(vec![vec![0, 1], vec![2, 3]]);
+        assert_eq!(cells[0][1], 1);
+        assert_eq!(cells[1][0], 2);
+        assert_eq!(cells.as_slice(), &[0, 1, 2, 3]);
+        assert_eq!(cells.to_vec(), vec![vec![0, 1], vec![2, 3]]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn from_columns_not_square() {
+        let _ = Cells::from(vec![vec![0, 1], vec![2]]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn from_data_wrong_size() {
+        Cells::from_data(2, vec![0; 3]);
+    }
+
+    #[test]
+    fn index_mut() {
+        let mut cells = Cells::new(2);
+        cells[1][0] = 4;
+        assert_eq!(cells, vec![vec![0, 0], vec![4, 0]]);
+    }
+
+    #[test]
+    fn iter_columns() {
+        let cells = Cells::from_data(2, vec![1, 2, 3, 4]);
+        let columns: Vec<&[Cell]> = cells.iter().collect();
+        assert_eq!(columns, vec![&[1, 2][..], &[3, 4][..]]);
+    }
+
+    #[test]
+    fn region_clamp() {
+        let region = Region {
+            x: 3,
+            y: 1,
+            width: 10,
+            height: 2,
+        };
+        assert_eq!(
+            region.clamp(5),
+            Region {
+                x: 3,
+                y: 1,
+                width: 2,
+                height: 2
+            }
+        );
+    }
+
+    #[test]
+    fn region_union() {
+        let a = Region {
+            x: 1,
+            y: 4,
+            width: 2,
+            height: 1,
+        };
+        let b = Region {
+            x: 3,
+            y: 0,
+            width: 1,
+            height: 2,
+        };
+        assert_eq!(
+            a.union(&b),
+            Region {
+                x: 1,
+                y: 0,
+                width: 3,
+                height: 5
+            }
+        );
+    }
+}
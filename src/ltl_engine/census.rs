@@ -0,0 +1,470 @@
+use crate::ltl_engine::board::Board;
+use crate::ltl_engine::cells::{Cells, Region};
+use crate::ltl_engine::config::Config;
+use crate::ltl_engine::pattern::{Pattern, CODE_PREFIX};
+use serde::Serialize;
+use std::cmp::{max, min, Ordering};
+use std::collections::HashMap;
+use std::fmt;
+
+/// Behaviour of an object evolving alone on an empty plane.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ObjectKind {
+    StillLife,
+    Oscillator {
+        period: u64,
+    },
+    Spaceship {
+        period: u64,
+        dx: i64,
+        dy: i64,
+    },
+    /// The object disappears.
+    Dying,
+    /// No repetition was found within the searched generations.
+    Unclassified,
+}
+
+impl ObjectKind {
+    /// Returns the name of the kind, without its period.
+    ///
+    pub fn name(&self) -> &'static str {
+        match self {
+            ObjectKind::StillLife => "still_life",
+            ObjectKind::Oscillator { .. } => "oscillator",
+            ObjectKind::Spaceship { .. } => "spaceship",
+            ObjectKind::Dying => "dying",
+            ObjectKind::Unclassified => "unclassified",
+        }
+    }
+
+    /// Returns the period of a periodic object.
+    ///
+    pub fn period(&self) -> Option<u64> {
+        match *self {
+            ObjectKind::StillLife => Some(1),
+            ObjectKind::Oscillator { period } | ObjectKind::Spaceship { period, .. } => {
+                Some(period)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the prefix of the apgcode-like code of an object of this kind: `xs` followed
+    /// by the population for still lifes, `xp` and `xq` followed by the period for
+    /// oscillators and spaceships.
+    ///
+    pub fn code_prefix(&self, population: u64) -> String {
+        match *self {
+            ObjectKind::StillLife => format!("xs{}", population),
+            ObjectKind::Oscillator { period } => format!("xp{}", period),
+            ObjectKind::Spaceship { period, .. } => format!("xq{}", period),
+            _ => format!("{}{}", CODE_PREFIX, population),
+        }
+    }
+
+    /// Returns the displacement of the object during one period.
+    ///
+    pub fn displacement(&self) -> (i64, i64) {
+        match *self {
+            ObjectKind::Spaceship { dx, dy, .. } => (dx, dy),
+            _ => (0, 0),
+        }
+    }
+}
+
+impl fmt::Display for ObjectKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ObjectKind::StillLife => write!(f, "still life"),
+            ObjectKind::Oscillator { period } => write!(f, "p{} oscillator", period),
+            ObjectKind::Spaceship { period, dx, dy } => {
+                write!(f, "({},{})c/{} spaceship", dx, dy, period)
+            }
+            ObjectKind::Dying => write!(f, "dying"),
+            ObjectKind::Unclassified => write!(f, "unclassified"),
+        }
+    }
+}
+
+/// An object found on a board.
+///
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CensusObject {
+    pub bounding_box: Region,
+    /// Number of cells of the object in a non-zero state.
+    pub population: u64,
+    /// Number of cells of the object in every state, dead cells of the bounding box included.
+    pub counts: Vec<u64>,
+    /// Hash of the object independent of its position and phase. Equal objects found on
+    /// different boards have equal hashes.
+    pub hash: u64,
+    /// Code of the object independent of its position, phase and orientation, see
+    /// `Pattern::code`.
+    pub code: String,
+    #[serde(flatten)]
+    pub kind: ObjectKind,
+}
+
+/// The number of equal objects found by a census.
+///
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CensusEntry {
+    #[serde(flatten)]
+    pub kind: ObjectKind,
+    pub hash: u64,
+    pub code: String,
+    pub count: u64,
+    /// Population of the first object found.
+    pub population: u64,
+}
+
+/// Objects found on a board.
+///
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Census {
+    pub objects: Vec<CensusObject>,
+}
+
+impl Census {
+    /// Counts equal objects.
+    ///
+    /// # Returns
+    ///
+    /// Entries of distinct objects, the most common first.
+    ///
+    pub fn summary(&self) -> Vec<CensusEntry> {
+        let mut entries: Vec<CensusEntry> = Vec::new();
+        let mut index: HashMap<(ObjectKind, u64), usize> = HashMap::new();
+        for object in &self.objects {
+            match index.get(&(object.kind, object.hash)) {
+                Some(&i) => entries[i].count += 1,
+                None => {
+                    index.insert((object.kind, object.hash), entries.len());
+                    entries.push(CensusEntry {
+                        kind: object.kind,
+                        hash: object.hash,
+                        code: object.code.clone(),
+                        count: 1,
+                        population: object.population,
+                    });
+                }
+            }
+        }
+        entries.sort_by(|a, b| match b.count.cmp(&a.count) {
+            Ordering::Equal => a.population.cmp(&b.population),
+            ordering => ordering,
+        });
+        entries
+    }
+}
+
+/// Options of a census.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct CensusOptions {
+    /// Cells closer than this distance belong to the same object, the radius of the
+    /// neighbourhood when `None`.
+    pub radius: Option<usize>,
+    /// The longest period searched for.
+    pub max_period: usize,
+    /// The number of generations an object is evolved for to classify it.
+    pub max_generations: usize,
+    /// Empty space around an object evolved in isolation. When `None`, there is room for
+    /// spaceships up to c/2 and for a margin of twice the radius of the neighbourhood.
+    pub padding: Option<usize>,
+}
+
+impl Default for CensusOptions {
+    fn default() -> Self {
+        CensusOptions {
+            radius: None,
+            max_period: 32,
+            max_generations: 64,
+            padding: None,
+        }
+    }
+}
+
+/// Splits the live cells into connected components.
+///
+/// # Arguments
+///
+/// * `cells` - The cells of the board.
+/// * `radius` - Cells within this distance along both axes are connected, at least one.
+///
+/// # Returns
+///
+/// The coordinates of the cells of every component, components ordered by their first cell
+/// column by column.
+///
+pub fn components(cells: &Cells, radius: usize) -> Vec<Vec<(usize, usize)>> {
+    let size = cells.len();
+    let radius = max(radius, 1);
+    let mut seen = vec![false; size * size];
+    let mut components = Vec::new();
+    for x in 0..size {
+        for y in 0..size {
+            if cells[x][y] == 0 || seen[x * size + y] {
+                continue;
+            }
+            seen[x * size + y] = true;
+            let mut component = vec![(x, y)];
+            let mut next = 0;
+            while next < component.len() {
+                let (cx, cy) = component[next];
+                next += 1;
+                for nx in cx.saturating_sub(radius)..=min(cx + radius, size - 1) {
+                    for ny in cy.saturating_sub(radius)..=min(cy + radius, size - 1) {
+                        if cells[nx][ny] != 0 && !seen[nx * size + ny] {
+                            seen[nx * size + ny] = true;
+                            component.push((nx, ny));
+                        }
+                    }
+                }
+            }
+            components.push(component);
+        }
+    }
+    components
+}
+
+/// Places a pattern alone on a board padded for it to evolve over `options.max_generations`.
+///
+/// Censuses run inside the workers of soup searches and explorations, so the board updates on
+/// the calling thread.
+///
+fn isolate(pattern: &Pattern, config: &Config, options: &CensusOptions) -> Board {
+    let rr = config.rr as usize;
+    let padding = options
+        .padding
+        .unwrap_or(2 * rr + options.max_generations / 2);
+    let size = max(pattern.width(), pattern.height()) + 2 * padding;
+    let mut board = Board::new(size as u64, config.clone());
+    board.set_threads(Some(1));
+    pattern.place(&mut board, padding, padding);
+    board.set_hash_history(options.max_period + 1);
+    board
+}
+
+/// Evolves a pattern alone on an empty board to find out its behaviour.
+///
+/// # Arguments
+///
+/// * `pattern` - The pattern to classify.
+/// * `config` - The rule the pattern evolves under.
+/// * `options` - Census options.
+///
+/// # Returns
+///
+/// The kind of the pattern, its hash independent of position and phase and its code
+/// independent of position, phase and orientation.
+///
+pub fn classify(
+    pattern: &Pattern,
+    config: &Config,
+    options: &CensusOptions,
+) -> (ObjectKind, u64, String) {
+    let mut board = isolate(pattern, config, options);
+    let initial = board.shape();
+    for _ in 0..options.max_generations {
+        board.update();
+        if board.stats().population == 0 {
+            return (ObjectKind::Dying, initial, pattern.code());
+        }
+        if let Some(translation) = board.detect_translation(options.max_period) {
+            let kind = if translation.is_moving() {
+                ObjectKind::Spaceship {
+                    period: translation.period,
+                    dx: translation.dx,
+                    dy: translation.dy,
+                }
+            } else if translation.period == 1 {
+                ObjectKind::StillLife
+            } else {
+                ObjectKind::Oscillator {
+                    period: translation.period,
+                }
+            };
+            let prefix = kind.code_prefix(board.stats().population);
+            let phase_code = |board: &Board| {
+                let region = board.stats().bounding_box.unwrap();
+                Pattern::from_region(&board.cells, region).code_with_prefix(&prefix)
+            };
+            let mut hash = board.shape();
+            let mut code = phase_code(&board);
+            for _ in 1..translation.period {
+                board.update();
+                hash = min(hash, board.shape());
+                let next = phase_code(&board);
+                if (next.len(), &next) < (code.len(), &code) {
+                    code = next;
+                }
+            }
+            return (kind, hash, code);
+        }
+    }
+    (ObjectKind::Unclassified, initial, pattern.code())
+}
+
+impl Board {
+    /// Splits the board into objects and classifies every object by evolving it alone.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - Census options.
+    ///
+    /// # Returns
+    ///
+    /// The objects of the board.
+    ///
+    pub fn census(&self, options: &CensusOptions) -> Census {
+        let radius = options.radius.unwrap_or(self.config().rr as usize);
+        let states = self.config().states();
+        let mut known: HashMap<Pattern, (ObjectKind, u64, String)> = HashMap::new();
+        let objects = components(&self.cells, radius)
+            .into_iter()
+            .map(|component| {
+                let x0 = component.iter().map(|c| c.0).min().unwrap();
+                let y0 = component.iter().map(|c| c.1).min().unwrap();
+                let x1 = component.iter().map(|c| c.0).max().unwrap();
+                let y1 = component.iter().map(|c| c.1).max().unwrap();
+                let bounding_box = Region {
+                    x: x0,
+                    y: y0,
+                    width: x1 - x0 + 1,
+                    height: y1 - y0 + 1,
+                };
+                let mut pattern = Pattern::new(bounding_box.width, bounding_box.height);
+                for &(x, y) in &component {
+                    pattern.set(x - x0, y - y0, self.cells[x][y]);
+                }
+                let (kind, hash, code) = known
+                    .entry(pattern.clone())
+                    .or_insert_with(|| classify(&pattern, self.config(), options))
+                    .clone();
+                let mut counts = vec![0; states as usize];
+                for &state in pattern.as_slice() {
+                    counts[state as usize] += 1;
+                }
+                CensusObject {
+                    bounding_box,
+                    population: component.len() as u64,
+                    counts,
+                    hash,
+                    code,
+                    kind,
+                }
+            })
+            .collect();
+        Census { objects }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ltl_engine::neighbourhood::Neighbourhood;
+
+    fn life() -> Config {
+        Config::new(1, 0, 0, (2, 3), (3, 3), Neighbourhood::Moore)
+    }
+
+    fn board_with(cells: &[(usize, usize)]) -> Board {
+        let mut board = Board::new(24, life());
+        for &(x, y) in cells {
+            board.set_cell(x, y, 1);
+        }
+        board
+    }
+
+    #[test]
+    fn components_within_radius() {
+        let cells = board_with(&[(1, 1), (2, 2), (5, 1), (5, 3)]).cells;
+        assert_eq!(
+            components(&cells, 1),
+            vec![vec![(1, 1), (2, 2)], vec![(5, 1)], vec![(5, 3)]]
+        );
+        assert_eq!(components(&cells, 2).len(), 2);
+    }
+
+    #[test]
+    fn census_of_life_objects() {
+        let block = [(2, 2), (2, 3), (3, 2), (3, 3)];
+        let blinker = [(10, 3), (11, 3), (12, 3)];
+        let vertical_blinker = [(16, 11), (16, 12), (16, 13)];
+        let glider = [(3, 14), (4, 15), (2, 16), (3, 16), (4, 16)];
+        let cells: Vec<(usize, usize)> =
+            [&block[..], &blinker, &vertical_blinker, &glider].concat();
+        let census = board_with(&cells).census(&CensusOptions::default());
+
+        let kinds: Vec<ObjectKind> = census.objects.iter().map(|o| o.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                ObjectKind::StillLife,
+                ObjectKind::Spaceship {
+                    period: 4,
+                    dx: 1,
+                    dy: 1
+                },
+                ObjectKind::Oscillator { period: 2 },
+                ObjectKind::Oscillator { period: 2 },
+            ]
+        );
+        assert_eq!(census.objects[0].counts, vec![0, 4]);
+        assert_eq!(census.objects[1].population, 5);
+        assert_eq!(
+            census.objects[2].bounding_box,
+            Region {
+                x: 10,
+                y: 3,
+                width: 3,
+                height: 1
+            }
+        );
+        // Both phases of the blinker have the same hash.
+        assert_eq!(census.objects[2].hash, census.objects[3].hash);
+        let codes: Vec<&str> = census.objects.iter().map(|o| o.code.as_str()).collect();
+        assert_eq!(codes, vec!["xs4_33", "xq4_153", "xp2_7", "xp2_7"]);
+
+        let summary = census.summary();
+        assert_eq!(summary.len(), 3);
+        assert_eq!(summary[0].count, 2);
+        assert_eq!(summary[0].kind, ObjectKind::Oscillator { period: 2 });
+    }
+
+    #[test]
+    fn classify_dying() {
+        let mut pattern = Pattern::new(1, 1);
+        pattern.set(0, 0, 1);
+        let (kind, _, code) = classify(&pattern, &life(), &CensusOptions::default());
+        assert_eq!(kind, ObjectKind::Dying);
+        assert_eq!(kind.to_string(), "dying");
+        assert_eq!(code, "xc1_1");
+    }
+
+    #[test]
+    fn classify_board_runs_on_one_thread() {
+        let mut pattern = Pattern::new(3, 1);
+        for x in 0..3 {
+            pattern.set(x, 0, 1);
+        }
+        let board = isolate(&pattern, &life(), &CensusOptions::default());
+        assert_eq!(board.get_chunks(), vec![0..board.cells.len()]);
+    }
+
+    #[test]
+    fn kind_display() {
+        let kind = ObjectKind::Spaceship {
+            period: 4,
+            dx: 1,
+            dy: -1,
+        };
+        assert_eq!(kind.to_string(), "(1,-1)c/4 spaceship");
+        assert_eq!(kind.period(), Some(4));
+        assert_eq!(ObjectKind::StillLife.period(), Some(1));
+    }
+}
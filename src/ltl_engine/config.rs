@@ -1,6 +1,10 @@
+use crate::ltl_engine::cells::Cell;
 use crate::ltl_engine::neighbourhood::Neighbourhood;
+use crate::ltl_engine::Rule;
 use rand::{Rng, RngCore, SeedableRng};
 use rand_pcg::Pcg32;
+use std::cmp::max;
+use std::fmt;
 
 /// A struct representing configuration for larger than life game.
 #[derive(Debug, Clone, PartialEq)]
@@ -39,6 +43,12 @@ impl Config {
         }
     }
 
+    /// Returns the number of cell states, treating `cc` below 2 as the two state game.
+    ///
+    pub fn states(&self) -> u8 {
+        max(self.cc, 2)
+    }
+
     pub fn randomize(seed: Option<u64>) -> Self {
         match seed {
             None => {
@@ -79,4 +89,82 @@ impl Config {
             nn,
         }
     }
+
+    /// Check if cell satisfies birth condition
+    ///
+    /// # Arguments
+    ///
+    /// * `neighbourhood_count` - represents the number of live neighbours.
+    ///
+    /// # Returns
+    ///
+    /// A boolean indicating whether the neighbourhood_count is within the configured birth range.
+    ///
+    #[inline]
+    fn check_birth(&self, neighbourhood_count: u16) -> bool {
+        self.bb.0 <= neighbourhood_count && neighbourhood_count <= self.bb.1
+    }
+
+    /// Check if cell satisfies survival condition
+    ///
+    /// # Arguments
+    ///
+    /// * `neighbourhood_count` - represents the number of live neighbours.
+    ///
+    /// # Returns
+    ///
+    /// A boolean indicating whether the neighbourhood_count is within the configured survival range.
+    ///
+    #[inline]
+    fn check_survival(&self, neighbourhood_count: u16) -> bool {
+        self.ss.0 <= neighbourhood_count && neighbourhood_count <= self.ss.1
+    }
+}
+
+impl Rule for Config {
+    fn states(&self) -> Cell {
+        Config::states(self)
+    }
+
+    fn radius(&self) -> usize {
+        self.rr as usize
+    }
+
+    fn contains(&self, dx: isize, dy: isize) -> bool {
+        self.nn.contains(self.rr, self.mm, dx, dy)
+    }
+
+    /// Dead cells are born and cells in state 1 survive when the number of live neighbours
+    /// is in range. Cells in state 1 that don't survive start decaying through the states
+    /// above 1 when `cc > 2`, dying right away otherwise.
+    ///
+    fn next_state(&self, state: Cell, neighbours: &[Cell]) -> Cell {
+        let neighbourhood_count = neighbours.iter().filter(|&&s| s > 0).count() as u16;
+        match state {
+            0 => self.check_birth(neighbourhood_count) as u8,
+            1 => {
+                if self.check_survival(neighbourhood_count) {
+                    1
+                } else if self.cc > 2 {
+                    2
+                } else {
+                    0
+                }
+            }
+            _ => (state + 1) % self.cc,
+        }
+    }
+}
+
+impl fmt::Display for Config {
+    /// Formats the configuration in the larger than life notation, e.g.
+    /// `R5,C0,M1,S34..58,B34..45,NM`.
+    ///
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "R{},C{},M{},S{}..{},B{}..{},{}",
+            self.rr, self.cc, self.mm, self.ss.0, self.ss.1, self.bb.0, self.bb.1, self.nn
+        )
+    }
 }
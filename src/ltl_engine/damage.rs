@@ -0,0 +1,308 @@
+use crate::ltl_engine::board::Board;
+use crate::ltl_engine::cells::Region;
+use rand::{Rng, SeedableRng};
+use rand_pcg::Pcg32;
+
+/// Differences between two boards of the same size.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diff {
+    /// Number of cells in different states, the Hamming distance of the boards.
+    pub distance: u64,
+    /// Fraction of the cells in different states.
+    pub fraction: f64,
+    /// Number of cells live on one board and dead on the other.
+    pub live_distance: u64,
+    /// The smallest region holding every difference.
+    pub bounding_box: Option<Region>,
+}
+
+impl Board {
+    /// Compares the cells of two boards.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The board compared to.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the boards don't have the same size.
+    ///
+    pub fn compare(&self, other: &Board) -> Diff {
+        let size = self.cells.len();
+        assert_eq!(size, other.cells.len(), "Compared boards differ in size");
+        let mut distance = 0;
+        let mut live_distance = 0;
+        let mut bounding_box: Option<Region> = None;
+        for (x, (a, b)) in self.cells.iter().zip(other.cells.iter()).enumerate() {
+            for (y, (&a, &b)) in a.iter().zip(b.iter()).enumerate() {
+                if a == b {
+                    continue;
+                }
+                distance += 1;
+                if (a == 0) != (b == 0) {
+                    live_distance += 1;
+                }
+                let cell = Region {
+                    x,
+                    y,
+                    width: 1,
+                    height: 1,
+                };
+                bounding_box = Some(bounding_box.map_or(cell, |b| b.union(&cell)));
+            }
+        }
+        Diff {
+            distance,
+            fraction: distance as f64 / (size * size).max(1) as f64,
+            live_distance,
+            bounding_box,
+        }
+    }
+}
+
+/// Cells flipped on the twin of a board, live cells dying and dead cells coming to life.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub enum Perturbation {
+    Cells(Vec<(usize, usize)>),
+    /// Up to `count` cells picked at random within `radius` of the centre of the board, none on
+    /// an empty board.
+    Random {
+        count: usize,
+        radius: usize,
+        seed: u64,
+    },
+}
+
+impl Perturbation {
+    /// Flips the cells of the perturbation on a board.
+    ///
+    /// # Returns
+    ///
+    /// The flipped cells, without duplicates and cells outside of the board.
+    ///
+    pub fn apply(&self, board: &mut Board) -> Vec<(usize, usize)> {
+        let size = board.cells.len();
+        let mut cells = match self {
+            Perturbation::Cells(cells) => cells.clone(),
+            Perturbation::Random {
+                count,
+                radius,
+                seed,
+            } => {
+                let mut rng = Pcg32::seed_from_u64(*seed);
+                let centre = size / 2;
+                let range = centre.saturating_sub(*radius)..(centre + radius + 1).min(size);
+                if range.is_empty() {
+                    return Vec::new();
+                }
+                (0..*count)
+                    .map(|_| (rng.gen_range(range.clone()), rng.gen_range(range.clone())))
+                    .collect()
+            }
+        };
+        cells.retain(|&(x, y)| x < size && y < size);
+        cells.sort_unstable();
+        cells.dedup();
+        for &(x, y) in &cells {
+            let state = if board.get_cell(x, y) == 0 { 1 } else { 0 };
+            board.set_cell(x, y, state);
+        }
+        cells
+    }
+}
+
+/// Damage between a board and its twin after some generations.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct DamagePoint {
+    /// Generations since the perturbation.
+    pub generation: u64,
+    pub distance: u64,
+    pub fraction: f64,
+    /// The largest distance along an axis from the perturbation to a damaged cell.
+    pub radius: f64,
+}
+
+/// The spreading of a perturbation.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct DamageCurve {
+    /// The flipped cells.
+    pub perturbed: Vec<(usize, usize)>,
+    /// The centre of the flipped cells.
+    pub origin: (f64, f64),
+    pub points: Vec<DamagePoint>,
+    /// The first generation where the boards are equal again, the run stops there.
+    pub healed_at: Option<u64>,
+    /// Cells per generation the damage front moves, the slope of a least squares fit of the
+    /// radius over the generations.
+    pub velocity: f64,
+}
+
+/// Runs a board and a perturbed twin side by side, measuring their distance every
+/// generation. The board itself is left untouched.
+///
+/// # Arguments
+///
+/// * `board` - The unperturbed board.
+/// * `perturbation` - The cells flipped on the twin.
+/// * `generations` - The number of generations run.
+///
+pub fn twin_run(board: &Board, perturbation: &Perturbation, generations: usize) -> DamageCurve {
+    let mut original = board.clone();
+    let mut twin = board.clone();
+    let perturbed = perturbation.apply(&mut twin);
+    let count = perturbed.len().max(1) as f64;
+    let origin = (
+        perturbed.iter().map(|&(x, _)| x as f64).sum::<f64>() / count,
+        perturbed.iter().map(|&(_, y)| y as f64).sum::<f64>() / count,
+    );
+
+    let mut points = Vec::with_capacity(generations + 1);
+    let mut healed_at = None;
+    for generation in 0..=generations as u64 {
+        if generation > 0 {
+            original.update();
+            twin.update();
+        }
+        let diff = original.compare(&twin);
+        let radius = diff.bounding_box.map_or(0.0, |b| {
+            let (x0, y0) = (b.x as f64, b.y as f64);
+            let (x1, y1) = ((b.x + b.width - 1) as f64, (b.y + b.height - 1) as f64);
+            [origin.0 - x0, x1 - origin.0, origin.1 - y0, y1 - origin.1]
+                .into_iter()
+                .fold(0.0, f64::max)
+        });
+        points.push(DamagePoint {
+            generation,
+            distance: diff.distance,
+            fraction: diff.fraction,
+            radius,
+        });
+        if diff.distance == 0 {
+            healed_at = Some(generation);
+            break;
+        }
+    }
+    let velocity = slope(
+        points
+            .iter()
+            .filter(|p| p.distance > 0)
+            .map(|p| (p.generation as f64, p.radius)),
+    );
+    DamageCurve {
+        perturbed,
+        origin,
+        points,
+        healed_at,
+        velocity,
+    }
+}
+
+/// Returns the slope of the least squares line through the points, 0 for fewer than two.
+///
+fn slope<I: Iterator<Item = (f64, f64)>>(points: I) -> f64 {
+    let (mut n, mut sx, mut sy, mut sxx, mut sxy) = (0.0, 0.0, 0.0, 0.0, 0.0);
+    for (x, y) in points {
+        n += 1.0;
+        sx += x;
+        sy += y;
+        sxx += x * x;
+        sxy += x * y;
+    }
+    let denominator = n * sxx - sx * sx;
+    if n < 2.0 || denominator == 0.0 {
+        0.0
+    } else {
+        (n * sxy - sx * sy) / denominator
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ltl_engine::config::Config;
+    use crate::ltl_engine::neighbourhood::Neighbourhood;
+
+    fn life() -> Config {
+        Config::new(1, 0, 0, (2, 3), (3, 3), Neighbourhood::Moore)
+    }
+
+    #[test]
+    fn compare_boards() {
+        let mut a = Board::new(
+            8,
+            Config::new(1, 3, 0, (2, 3), (3, 3), Neighbourhood::Moore),
+        );
+        let mut b = a.clone();
+        assert_eq!(a.compare(&b).distance, 0);
+        assert_eq!(a.compare(&b).bounding_box, None);
+
+        a.set_cell(1, 2, 1);
+        b.set_cell(1, 2, 2);
+        b.set_cell(5, 6, 1);
+        let diff = a.compare(&b);
+        assert_eq!(diff.distance, 2);
+        assert_eq!(diff.live_distance, 1);
+        assert_eq!(diff.fraction, 2.0 / 64.0);
+        assert_eq!(
+            diff.bounding_box,
+            Some(Region {
+                x: 1,
+                y: 2,
+                width: 5,
+                height: 5,
+            })
+        );
+    }
+
+    #[test]
+    fn lone_cell_heals() {
+        let board = Board::new(16, life());
+        let curve = twin_run(&board, &Perturbation::Cells(vec![(8, 8), (20, 3)]), 10);
+        assert_eq!(curve.perturbed, vec![(8, 8)]);
+        assert_eq!(curve.healed_at, Some(1));
+        assert_eq!(curve.points.len(), 2);
+        assert_eq!(curve.points[0].distance, 1);
+        assert_eq!(curve.velocity, 0.0);
+        assert_eq!(board.generation(), 0);
+    }
+
+    #[test]
+    fn damage_spreads() {
+        let board = Board::new(
+            32,
+            Config::new(1, 0, 0, (0, 8), (1, 8), Neighbourhood::Moore),
+        );
+        let curve = twin_run(&board, &Perturbation::Cells(vec![(16, 16)]), 8);
+        assert_eq!(curve.healed_at, None);
+        assert_eq!(curve.points.len(), 9);
+        assert_eq!(curve.points[3].radius, 3.0);
+        assert!((curve.velocity - 1.0).abs() < 1e-9);
+
+        let random = Perturbation::Random {
+            count: 3,
+            radius: 2,
+            seed: 1,
+        };
+        let curve = twin_run(&board, &random, 0);
+        assert!(!curve.perturbed.is_empty());
+        assert!(curve
+            .perturbed
+            .iter()
+            .all(|&(x, y)| (14..=18).contains(&x) && (14..=18).contains(&y)));
+    }
+
+    #[test]
+    fn random_on_empty_board() {
+        let mut board = Board::new(0, life());
+        let random = Perturbation::Random {
+            count: 3,
+            radius: 2,
+            seed: 1,
+        };
+        assert_eq!(random.apply(&mut board), vec![]);
+    }
+}
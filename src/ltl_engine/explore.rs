@@ -0,0 +1,477 @@
+use crate::ltl_engine::census::ObjectKind;
+use crate::ltl_engine::config::Config;
+use crate::ltl_engine::neighbourhood::Neighbourhood;
+use crate::ltl_engine::soup::{parallel_map, seed_soup, ReportFormat, SoupOptions};
+use serde::Serialize;
+use std::cmp::Ordering;
+use std::fmt;
+use std::io::{self, Write};
+
+/// Behaviour of soups of a rule.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Behaviour {
+    /// Every cell dies.
+    DiesOut,
+    /// The soup settles into a still life.
+    Static,
+    /// The soup settles into oscillators.
+    Periodic,
+    /// The soup keeps changing without growing out of bounds.
+    Chaotic,
+    /// The soup grows until it reaches the edges of the board.
+    Explosive,
+    /// The soup emits spaceships.
+    Spaceships,
+}
+
+impl Behaviour {
+    pub const ALL: [Behaviour; 6] = [
+        Behaviour::DiesOut,
+        Behaviour::Static,
+        Behaviour::Periodic,
+        Behaviour::Chaotic,
+        Behaviour::Explosive,
+        Behaviour::Spaceships,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Behaviour::DiesOut => "dies_out",
+            Behaviour::Static => "static",
+            Behaviour::Periodic => "periodic",
+            Behaviour::Chaotic => "chaotic",
+            Behaviour::Explosive => "explosive",
+            Behaviour::Spaceships => "spaceships",
+        }
+    }
+
+    /// Returns how interesting the behaviour is, used to rank rules.
+    ///
+    fn weight(&self) -> f64 {
+        match self {
+            Behaviour::DiesOut | Behaviour::Explosive => 0.0,
+            Behaviour::Static => 0.5,
+            Behaviour::Chaotic => 1.0,
+            Behaviour::Periodic => 2.0,
+            Behaviour::Spaceships => 4.0,
+        }
+    }
+}
+
+impl fmt::Display for Behaviour {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Options of a rule classification.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExploreOptions {
+    /// The sample soups, `soups` of them are run for every rule.
+    pub soups: SoupOptions,
+    /// Whether settled soups are searched for spaceships with a census.
+    pub census: bool,
+    /// A soup whose population grows by this factor and reaches the edges is explosive.
+    pub growth: f64,
+}
+
+impl Default for ExploreOptions {
+    fn default() -> Self {
+        ExploreOptions {
+            soups: SoupOptions {
+                soups: 4,
+                max_generations: 500,
+                ..SoupOptions::default()
+            },
+            census: true,
+            growth: 2.0,
+        }
+    }
+}
+
+/// Population dynamics of one sample soup.
+///
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SoupDynamics {
+    pub seed: u64,
+    pub behaviour: Behaviour,
+    /// The first generation of the final cycle, if the soup settled.
+    pub settled_at: Option<u64>,
+    pub period: Option<u64>,
+    /// The first generation without live cells, if the soup died out.
+    pub extinct_at: Option<u64>,
+    pub initial_population: u64,
+    pub peak_population: u64,
+    pub final_population: u64,
+    /// Mean fraction of the board changing state 1 per generation, over the last quarter of
+    /// the run.
+    pub activity: f64,
+    /// Number of spaceships found by the census.
+    pub spaceships: u64,
+}
+
+/// Classification of a rule.
+///
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RuleReport {
+    pub rule: String,
+    #[serde(skip)]
+    pub config: Config,
+    pub behaviour: Behaviour,
+    /// The higher, the more interesting the rule.
+    pub score: f64,
+    /// Number of soups of every behaviour.
+    pub behaviours: Vec<(Behaviour, usize)>,
+    pub mean_activity: f64,
+    pub soups: Vec<SoupDynamics>,
+}
+
+/// Runs a soup of a rule and describes its dynamics.
+///
+/// # Arguments
+///
+/// * `config` - The rule of the soup.
+/// * `seed` - The seed of the soup.
+/// * `options` - Classification options.
+///
+pub fn soup_dynamics(config: &Config, seed: u64, options: &ExploreOptions) -> SoupDynamics {
+    let soups = &options.soups;
+    let mut board = seed_soup(config, seed, soups);
+    board.record_stats(soups.max_generations + 1);
+    let cycle = board.run_until_periodic(soups.max_generations, soups.max_period);
+
+    let history: Vec<_> = board.stats_history().unwrap().iter().cloned().collect();
+    let initial = history.first().unwrap();
+    let last = history.last().unwrap();
+    let peak = history.iter().map(|s| s.population).max().unwrap_or(0);
+    let tail = &history[history.len() - (history.len() / 4).max(1)..];
+    let area = (soups.board_size * soups.board_size).max(1) as f64;
+    let activity = tail
+        .iter()
+        .map(|s| (s.births + s.deaths) as f64 / area)
+        .sum::<f64>()
+        / tail.len() as f64;
+
+    let margin = config.rr as usize;
+    let size = soups.board_size as usize;
+    let reaches_edges = last.bounding_box.is_some_and(|b| {
+        b.x < margin
+            || b.y < margin
+            || b.x + b.width + margin > size
+            || b.y + b.height + margin > size
+    });
+    let grown = last.population as f64 >= options.growth * initial.population.max(1) as f64;
+
+    let mut spaceships = 0;
+    let behaviour = if last.population == 0 {
+        Behaviour::DiesOut
+    } else if reaches_edges && grown {
+        Behaviour::Explosive
+    } else {
+        if options.census && cycle.is_some() {
+            spaceships = board
+                .census(&soups.census)
+                .objects
+                .iter()
+                .filter(|o| matches!(o.kind, ObjectKind::Spaceship { .. }))
+                .count() as u64;
+        }
+        match cycle {
+            _ if spaceships > 0 => Behaviour::Spaceships,
+            None => Behaviour::Chaotic,
+            Some(cycle) if cycle.period == 1 => Behaviour::Static,
+            Some(_) => Behaviour::Periodic,
+        }
+    };
+    SoupDynamics {
+        seed,
+        behaviour,
+        settled_at: cycle.map(|c| c.start),
+        period: cycle.map(|c| c.period),
+        extinct_at: history
+            .iter()
+            .find(|s| s.population == 0)
+            .map(|s| s.generation),
+        initial_population: initial.population,
+        peak_population: peak,
+        final_population: last.population,
+        activity,
+        spaceships,
+    }
+}
+
+/// Runs sample soups of a rule and labels its behaviour.
+///
+/// The rule is labelled with spaceships when any soup emits one, with the most common
+/// behaviour of its soups otherwise. The score adds up the weights of the behaviours of the
+/// soups, from 0 for dying and explosive soups to 4 for spaceships, and rewards surviving soups
+/// with long transients before they settle.
+///
+/// # Arguments
+///
+/// * `config` - The classified rule.
+/// * `options` - Classification options.
+///
+pub fn classify_rule(config: &Config, options: &ExploreOptions) -> RuleReport {
+    let soups: Vec<SoupDynamics> = (0..options.soups.soups)
+        .map(|i| soup_dynamics(config, options.soups.first_seed + i as u64, options))
+        .collect();
+    let behaviours: Vec<(Behaviour, usize)> = Behaviour::ALL
+        .iter()
+        .map(|&b| (b, soups.iter().filter(|s| s.behaviour == b).count()))
+        .filter(|&(_, count)| count > 0)
+        .collect();
+    let behaviour = if soups.iter().any(|s| s.behaviour == Behaviour::Spaceships) {
+        Behaviour::Spaceships
+    } else {
+        behaviours
+            .iter()
+            .max_by_key(|&&(b, count)| (count, std::cmp::Reverse(b)))
+            .map_or(Behaviour::DiesOut, |&(b, _)| b)
+    };
+    let count = soups.len().max(1) as f64;
+    let max_generations = options.soups.max_generations.max(1) as f64;
+    let score = soups
+        .iter()
+        .map(|s| {
+            let weight = s.behaviour.weight();
+            match s.settled_at {
+                Some(generation) if weight > 0.0 => weight + generation as f64 / max_generations,
+                _ => weight,
+            }
+        })
+        .sum::<f64>()
+        / count;
+    let mean_activity = soups.iter().map(|s| s.activity).sum::<f64>() / count;
+    RuleReport {
+        rule: config.to_string(),
+        config: config.clone(),
+        behaviour,
+        score,
+        behaviours,
+        mean_activity,
+        soups,
+    }
+}
+
+/// Rules visited by a sweep.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub enum Sweep {
+    /// Rules sampled with `Config::randomize`.
+    Random { count: usize, seed: u64 },
+    /// Every combination of the given parameters. Survival and birth ranges are taken from a
+    /// grid splitting the neighbourhood area into `steps` parts.
+    Grid {
+        rr: Vec<u8>,
+        cc: u8,
+        mm: Vec<u8>,
+        nn: Vec<Neighbourhood>,
+        steps: u16,
+    },
+}
+
+impl Sweep {
+    /// Returns the rules of the sweep.
+    ///
+    pub fn configs(&self) -> Vec<Config> {
+        match self {
+            Sweep::Random { count, seed } => (0..*count as u64)
+                .map(|i| Config::randomize(Some(seed + i)))
+                .collect(),
+            Sweep::Grid {
+                rr,
+                cc,
+                mm,
+                nn,
+                steps,
+            } => {
+                let mut configs = Vec::new();
+                for &r in rr {
+                    for &m in mm {
+                        for n in nn {
+                            let ranges = ranges(n.area(r, m), *steps);
+                            for &ss in &ranges {
+                                for &bb in &ranges {
+                                    configs.push(Config::new(r, *cc, m, ss, bb, n.clone()));
+                                }
+                            }
+                        }
+                    }
+                }
+                configs
+            }
+        }
+    }
+}
+
+/// Returns the ranges `(lo, hi)` with `lo <= hi` between the points splitting `0..=area`
+/// into `steps` parts.
+///
+pub(crate) fn ranges(area: u16, steps: u16) -> Vec<(u16, u16)> {
+    let steps = steps.max(1);
+    let mut points: Vec<u16> = (0..=steps)
+        .map(|i| (area as u32 * i as u32 / steps as u32) as u16)
+        .collect();
+    points.dedup();
+    let mut ranges = Vec::new();
+    for (i, &lo) in points.iter().enumerate() {
+        for &hi in &points[i..] {
+            ranges.push((lo, hi));
+        }
+    }
+    ranges
+}
+
+/// Rules classified by a sweep, the most interesting first.
+///
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Catalogue {
+    pub rules: Vec<RuleReport>,
+}
+
+impl Catalogue {
+    /// Writes the catalogue in the given format.
+    ///
+    pub fn write<W: Write>(&self, mut writer: W, format: ReportFormat) -> io::Result<()> {
+        match format {
+            ReportFormat::Json => {
+                serde_json::to_writer_pretty(&mut writer, self).map_err(io::Error::from)?;
+                writeln!(writer)
+            }
+            ReportFormat::Text => write!(writer, "{}", self),
+        }
+    }
+}
+
+impl fmt::Display for Catalogue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{:>4}  {:<36}  {:<10}  {:>6}  {:>8}",
+            "rank", "rule", "behaviour", "score", "activity"
+        )?;
+        for (rank, report) in self.rules.iter().enumerate() {
+            writeln!(
+                f,
+                "{:>4}  {:<36}  {:<10}  {:>6.2}  {:>8.4}",
+                rank + 1,
+                report.rule,
+                report.behaviour.name(),
+                report.score,
+                report.mean_activity
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Classifies every rule of a sweep, rules in parallel.
+///
+/// # Arguments
+///
+/// * `sweep` - The visited rules.
+/// * `options` - Classification options, `soups.threads` rules are classified at a time.
+///
+/// # Returns
+///
+/// The catalogue of the rules ranked by score.
+///
+pub fn sweep(sweep: &Sweep, options: &ExploreOptions) -> Catalogue {
+    let configs = sweep.configs();
+    let mut rules = parallel_map(configs.len(), options.soups.threads, |i| {
+        classify_rule(&configs[i], options)
+    });
+    rules.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(Ordering::Equal));
+    Catalogue { rules }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options() -> ExploreOptions {
+        ExploreOptions {
+            soups: SoupOptions {
+                soups: 2,
+                board_size: 32,
+                soup_size: 8,
+                max_generations: 200,
+                max_period: 8,
+                threads: Some(2),
+                ..SoupOptions::default()
+            },
+            ..ExploreOptions::default()
+        }
+    }
+
+    fn life(ss: (u16, u16), bb: (u16, u16)) -> Config {
+        Config::new(1, 0, 0, ss, bb, Neighbourhood::Moore)
+    }
+
+    #[test]
+    fn classify_dying_rule() {
+        let report = classify_rule(&life((9, 9), (9, 9)), &options());
+        assert_eq!(report.behaviour, Behaviour::DiesOut);
+        assert_eq!(report.behaviours, vec![(Behaviour::DiesOut, 2)]);
+        assert_eq!(report.score, 0.0);
+        assert_eq!(report.soups[0].extinct_at, Some(1));
+    }
+
+    #[test]
+    fn classify_explosive_rule() {
+        let report = classify_rule(&life((0, 8), (1, 8)), &options());
+        assert_eq!(report.behaviour, Behaviour::Explosive);
+    }
+
+    #[test]
+    fn classify_static_rule() {
+        let report = classify_rule(&life((0, 8), (9, 9)), &options());
+        assert_eq!(report.behaviour, Behaviour::Static);
+        assert_eq!(report.soups[0].settled_at, Some(0));
+    }
+
+    #[test]
+    fn grid_ranges() {
+        assert_eq!(
+            ranges(8, 2),
+            vec![(0, 0), (0, 4), (0, 8), (4, 4), (4, 8), (8, 8)]
+        );
+        let sweep = Sweep::Grid {
+            rr: vec![1, 2],
+            cc: 0,
+            mm: vec![0],
+            nn: vec![Neighbourhood::Moore],
+            steps: 2,
+        };
+        assert_eq!(sweep.configs().len(), 2 * 6 * 6);
+
+        let random = Sweep::Random { count: 3, seed: 7 }.configs();
+        assert_eq!(random.len(), 3);
+        assert_eq!(random[1], Config::randomize(Some(8)));
+    }
+
+    #[test]
+    fn sweep_is_ranked() {
+        let sweep_grid = Sweep::Grid {
+            rr: vec![1],
+            cc: 0,
+            mm: vec![0],
+            nn: vec![Neighbourhood::Moore],
+            steps: 1,
+        };
+        let catalogue = sweep(&sweep_grid, &options());
+        assert_eq!(catalogue.rules.len(), 9);
+        assert!(catalogue
+            .rules
+            .windows(2)
+            .all(|pair| pair[0].score >= pair[1].score));
+
+        let mut text = Vec::new();
+        catalogue.write(&mut text, ReportFormat::Text).unwrap();
+        assert!(String::from_utf8(text).unwrap().starts_with("rank"));
+    }
+}
@@ -0,0 +1,828 @@
+use crate::ltl_engine::cells::Cell;
+use crate::ltl_engine::image::{Palette, Rgba};
+use crate::ltl_engine::Rule;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::str::FromStr;
+
+/// Neighbours of the Moore neighbourhood in the order of `@TABLE` transitions: N, NE, E, SE,
+/// S, SW, W and NW, clockwise.
+///
+const MOORE_TABLE: [(isize, isize); 8] = [
+    (0, -1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+    (-1, -1),
+];
+
+/// Neighbours of the von Neumann neighbourhood in the order of `@TABLE` transitions: N, E, S
+/// and W, clockwise.
+///
+const VON_NEUMANN_TABLE: [(isize, isize); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+
+/// Neighbours of the Moore neighbourhood in the order `@TREE` nodes test them: NW, NE, SW, SE,
+/// N, W, E and S.
+///
+const MOORE_TREE: [(isize, isize); 8] = [
+    (-1, -1),
+    (1, -1),
+    (-1, 1),
+    (1, 1),
+    (0, -1),
+    (-1, 0),
+    (1, 0),
+    (0, 1),
+];
+
+/// Neighbours of the von Neumann neighbourhood in the order `@TREE` nodes test them: N, W, E
+/// and S.
+///
+const VON_NEUMANN_TREE: [(isize, isize); 4] = [(0, -1), (-1, 0), (1, 0), (0, 1)];
+
+/// Errors raised by loading a Golly rule.
+///
+#[derive(Debug)]
+pub enum GollyError {
+    Io(io::Error),
+    /// The file has neither a `@TABLE` nor a `@TREE` section.
+    NoRule,
+    /// Rules need between 2 and 255 states, 0 when the number of states is missing.
+    States(usize),
+    /// Only the Moore and von Neumann neighbourhoods are supported.
+    Neighbourhood(String),
+    /// The symmetries don't exist or don't apply to the neighbourhood.
+    Symmetries(String),
+    /// A line couldn't be parsed.
+    Syntax(usize),
+    /// A line uses a variable defined nowhere before it.
+    Variable(usize, String),
+    /// A line uses a state the rule doesn't have.
+    State(usize, usize),
+    /// A transition doesn't have the cell, one input per neighbour and the next state.
+    Inputs {
+        line: usize,
+        expected: usize,
+        actual: usize,
+    },
+    /// The next state of a transition is a variable its inputs don't bind.
+    Output(usize),
+    /// A tree node refers to a missing node or to a node of the wrong level.
+    Node(usize),
+}
+
+impl fmt::Display for GollyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GollyError::Io(e) => write!(f, "{}", e),
+            GollyError::NoRule => write!(f, "Rules need a @TABLE or a @TREE section"),
+            GollyError::States(states) => {
+                write!(f, "Rules need 2 to 255 states, {} given", states)
+            }
+            GollyError::Neighbourhood(name) => {
+                write!(f, "Neighbourhood {} isn't supported", name)
+            }
+            GollyError::Symmetries(name) => write!(f, "Symmetries {} aren't supported", name),
+            GollyError::Syntax(line) => write!(f, "Invalid line {}", line),
+            GollyError::Variable(line, name) => {
+                write!(f, "Unknown variable {} on line {}", name, line)
+            }
+            GollyError::State(line, state) => {
+                write!(f, "State {} on line {} is outside the rule", state, line)
+            }
+            GollyError::Inputs {
+                line,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "Expected {} states on line {}, got {}",
+                expected, line, actual
+            ),
+            GollyError::Output(line) => {
+                write!(f, "Next state on line {} isn't bound by the inputs", line)
+            }
+            GollyError::Node(line) => write!(f, "Invalid tree node on line {}", line),
+        }
+    }
+}
+
+impl Error for GollyError {}
+
+impl From<io::Error> for GollyError {
+    fn from(e: io::Error) -> Self {
+        GollyError::Io(e)
+    }
+}
+
+/// How the next state of a cell is looked up.
+///
+#[derive(Debug, Clone, PartialEq)]
+enum Transitions {
+    /// For every input and state, the transitions it matches as a bitset, `words` 64 bit
+    /// words long. The first transition every input matches gives the next state.
+    Table {
+        matches: Vec<u64>,
+        words: usize,
+        outputs: Vec<Cell>,
+    },
+    /// Nodes of `states` children each, the children of the root being chosen by the first
+    /// input. Children of the last level are next states.
+    Tree { nodes: Vec<usize>, root: usize },
+}
+
+/// A rule in Golly's `.rule` format, written as a `@TABLE` of transitions or as a `@TREE` of
+/// decisions over the states of the neighbours. Colours of the `@COLORS` section make up the
+/// palette of the rule.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct GollyRule {
+    name: String,
+    states: Cell,
+    moore: bool,
+    /// Positions of the inputs in the neighbourhoods passed by boards, `None` for the cell.
+    inputs: Vec<Option<usize>>,
+    transitions: Transitions,
+    colors: Vec<(Cell, Rgba)>,
+}
+
+impl GollyRule {
+    /// Loads a rule from a `.rule` file.
+    ///
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, GollyError> {
+        fs::read_to_string(path)?.parse()
+    }
+
+    /// Returns the name following `@RULE`.
+    ///
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the palette of the rule: the default palette with the colours of the
+    /// `@COLORS` section.
+    ///
+    pub fn palette(&self) -> Palette {
+        let mut colors = Palette::for_config(self).colors().to_vec();
+        for &(state, color) in &self.colors {
+            colors[state as usize] = color;
+        }
+        Palette::new(colors)
+    }
+
+    /// Returns the offsets of the neighbours in the order boards pass them.
+    ///
+    fn offsets(&self) -> Vec<(isize, isize)> {
+        (-1..=1)
+            .flat_map(|dx| (-1..=1).map(move |dy| (dx, dy)))
+            .filter(|&(dx, dy)| self.contains(dx, dy))
+            .collect()
+    }
+
+    /// Returns the positions in board neighbourhoods of neighbours in the given order.
+    ///
+    fn positions(&self, order: &[(isize, isize)]) -> Vec<Option<usize>> {
+        let offsets = self.offsets();
+        order
+            .iter()
+            .map(|offset| offsets.iter().position(|o| o == offset))
+            .collect()
+    }
+}
+
+impl Rule for GollyRule {
+    fn states(&self) -> Cell {
+        self.states
+    }
+
+    fn radius(&self) -> usize {
+        1
+    }
+
+    fn contains(&self, dx: isize, dy: isize) -> bool {
+        (dx, dy) != (0, 0) && (self.moore || dx == 0 || dy == 0)
+    }
+
+    /// # Panics
+    ///
+    /// This function will panic if the neighbours don't match the neighbourhood of the rule,
+    /// or a state is outside the rule.
+    ///
+    fn next_state(&self, state: Cell, neighbours: &[Cell]) -> Cell {
+        let states = self.states as usize;
+        assert_eq!(
+            neighbours.len(),
+            if self.moore { 8 } else { 4 },
+            "Neighbours don't match the neighbourhood"
+        );
+        assert!(
+            state < self.states && neighbours.iter().all(|&n| n < self.states),
+            "State is outside the rule"
+        );
+        let value = |input: &Option<usize>| input.map_or(state, |i| neighbours[i]) as usize;
+        match &self.transitions {
+            Transitions::Table {
+                matches,
+                words,
+                outputs,
+            } => {
+                for word in 0..*words {
+                    let mut matched = u64::MAX;
+                    for (i, input) in self.inputs.iter().enumerate() {
+                        matched &= matches[(i * states + value(input)) * words + word];
+                    }
+                    if matched != 0 {
+                        return outputs[word * 64 + matched.trailing_zeros() as usize];
+                    }
+                }
+                state
+            }
+            Transitions::Tree { nodes, root } => {
+                let mut node = *root;
+                for input in &self.inputs {
+                    node = nodes[node * states + value(input)];
+                }
+                node as Cell
+            }
+        }
+    }
+
+    fn positional(&self) -> bool {
+        true
+    }
+}
+
+impl fmt::Display for GollyRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+impl FromStr for GollyRule {
+    type Err = GollyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut name = String::new();
+        let mut sections: HashMap<&str, Vec<(usize, &str)>> = HashMap::new();
+        let mut section = "";
+        for (i, line) in s.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            if let Some(header) = line.strip_prefix('@') {
+                let mut words = header.split_whitespace();
+                section = words.next().unwrap_or("");
+                if section == "RULE" {
+                    name = words.next().unwrap_or("").to_string();
+                }
+                sections.entry(section).or_default();
+            } else if !line.is_empty() {
+                sections.entry(section).or_default().push((i + 1, line));
+            }
+        }
+        let mut rule = if let Some(lines) = sections.get("TABLE") {
+            parse_table(lines)?
+        } else if let Some(lines) = sections.get("TREE") {
+            parse_tree(lines)?
+        } else {
+            return Err(GollyError::NoRule);
+        };
+        rule.name = name;
+        if let Some(lines) = sections.get("COLORS") {
+            rule.colors = parse_colors(lines, rule.states)?;
+        }
+        Ok(rule)
+    }
+}
+
+/// Splits a `key:value` or `key=value` line.
+///
+fn setting(line: &str) -> Option<(&str, &str)> {
+    line.split_once([':', '='])
+        .map(|(key, value)| (key.trim(), value.trim()))
+}
+
+/// Parses the number of states of a rule.
+///
+fn parse_states(value: Option<&str>) -> Result<Cell, GollyError> {
+    let states = value.and_then(|v| v.parse::<usize>().ok()).unwrap_or(0);
+    if !(2..=Cell::MAX as usize).contains(&states) {
+        return Err(GollyError::States(states));
+    }
+    Ok(states as Cell)
+}
+
+/// Parses a state of a rule with `states` states.
+///
+fn parse_state(token: &str, states: Cell, line: usize) -> Result<Option<Cell>, GollyError> {
+    match token.parse::<usize>() {
+        Ok(state) if state < states as usize => Ok(Some(state as Cell)),
+        Ok(state) => Err(GollyError::State(line, state)),
+        Err(_) => Ok(None),
+    }
+}
+
+/// Returns the permutations of the neighbours given by the symmetries of a table, `None` for
+/// `permute`.
+///
+fn symmetries(name: &str, n: usize) -> Result<Option<Vec<Vec<usize>>>, GollyError> {
+    let rotations = |step: usize| {
+        (0..n)
+            .step_by(step)
+            .map(|k| (0..n).map(|i| (i + k) % n).collect())
+            .collect::<Vec<Vec<usize>>>()
+    };
+    let reflected = |permutations: Vec<Vec<usize>>| {
+        let mirrored: Vec<Vec<usize>> = permutations
+            .iter()
+            .map(|p| (0..n).map(|i| p[(n - i) % n]).collect())
+            .collect();
+        [permutations, mirrored].concat()
+    };
+    Ok(Some(match name {
+        "none" => rotations(n),
+        "rotate4" => rotations(n / 4),
+        "rotate8" if n == 8 => rotations(1),
+        "reflect" => reflected(rotations(n)),
+        "rotate4reflect" => reflected(rotations(n / 4)),
+        "rotate8reflect" if n == 8 => reflected(rotations(1)),
+        "permute" => return Ok(None),
+        _ => return Err(GollyError::Symmetries(name.to_string())),
+    }))
+}
+
+/// Rearranges a slice into the next permutation in lexicographic order, returning false
+/// after the last one.
+///
+fn next_permutation<T: Ord>(v: &mut [T]) -> bool {
+    let Some(i) = (1..v.len()).rev().find(|&i| v[i - 1] < v[i]) else {
+        return false;
+    };
+    let j = (i..v.len()).rev().find(|&j| v[i - 1] < v[j]).unwrap_or(i);
+    v.swap(i - 1, j);
+    v[i..].reverse();
+    true
+}
+
+/// A transition: the states every input matches, the cell first, and the next state.
+///
+type Transition = (Vec<Vec<Cell>>, Cell);
+
+/// Expands the variables of a transition. Variables used more than once are bound, taking
+/// the same value everywhere, and are enumerated.
+///
+fn expand(
+    tokens: &[&str],
+    states: Cell,
+    variables: &HashMap<&str, Vec<Cell>>,
+    line: usize,
+) -> Result<Vec<Transition>, GollyError> {
+    let mut bound: Vec<&str> = Vec::new();
+    for token in tokens {
+        if parse_state(token, states, line)?.is_none() {
+            if !variables.contains_key(token) {
+                return Err(GollyError::Variable(line, token.to_string()));
+            }
+            if !bound.contains(token) && tokens.iter().filter(|&t| t == token).count() > 1 {
+                bound.push(token);
+            }
+        }
+    }
+    let mut assignments: Vec<Vec<Cell>> = vec![Vec::new()];
+    for name in &bound {
+        assignments = assignments
+            .iter()
+            .flat_map(|a| {
+                variables[name].iter().map(move |&value| {
+                    let mut a = a.clone();
+                    a.push(value);
+                    a
+                })
+            })
+            .collect();
+    }
+    let (output, inputs) = tokens.split_last().ok_or(GollyError::Syntax(line))?;
+    assignments
+        .iter()
+        .map(|assignment| {
+            let value = |token: &str| {
+                let position = bound.iter().position(|&name| name == token);
+                match parse_state(token, states, line)? {
+                    Some(state) => Ok(vec![state]),
+                    None => match position {
+                        Some(i) => Ok(vec![assignment[i]]),
+                        None => Ok(variables[token].clone()),
+                    },
+                }
+            };
+            let output = match value(output)?[..] {
+                [state] => state,
+                _ => return Err(GollyError::Output(line)),
+            };
+            let inputs = inputs
+                .iter()
+                .map(|token| value(token))
+                .collect::<Result<_, GollyError>>()?;
+            Ok((inputs, output))
+        })
+        .collect()
+}
+
+/// Parses the lines of a `@TABLE` section.
+///
+fn parse_table(lines: &[(usize, &str)]) -> Result<GollyRule, GollyError> {
+    let mut states = None;
+    let mut moore = true;
+    let mut symmetry = "none";
+    let mut variables: HashMap<&str, Vec<Cell>> = HashMap::new();
+    let mut transitions: Vec<Transition> = Vec::new();
+    for &(line, text) in lines {
+        if let Some(variable) = text.strip_prefix("var ") {
+            let states = states.ok_or(GollyError::States(0))?;
+            let (name, values) = variable.split_once('=').ok_or(GollyError::Syntax(line))?;
+            let values = values
+                .trim()
+                .strip_prefix('{')
+                .and_then(|v| v.strip_suffix('}'))
+                .ok_or(GollyError::Syntax(line))?;
+            let mut set = Vec::new();
+            for token in values.split(',').map(str::trim) {
+                match parse_state(token, states, line)? {
+                    Some(state) => set.push(state),
+                    None => set.extend(
+                        variables
+                            .get(token)
+                            .ok_or_else(|| GollyError::Variable(line, token.to_string()))?,
+                    ),
+                }
+            }
+            set.sort_unstable();
+            set.dedup();
+            variables.insert(name.trim(), set);
+        } else if let Some((key, value)) = setting(text) {
+            match key {
+                "n_states" => states = Some(parse_states(Some(value))?),
+                "neighborhood" => {
+                    moore = match value {
+                        "Moore" => true,
+                        "vonNeumann" => false,
+                        _ => return Err(GollyError::Neighbourhood(value.to_string())),
+                    }
+                }
+                "symmetries" => symmetry = value,
+                _ => return Err(GollyError::Syntax(line)),
+            }
+        } else {
+            let states = states.ok_or(GollyError::States(0))?;
+            let tokens: Vec<&str> = if text.contains(',') {
+                text.split(',').map(str::trim).collect()
+            } else {
+                text.split_whitespace()
+                    .flat_map(|word| (0..word.len()).map(move |i| &word[i..i + 1]))
+                    .collect()
+            };
+            let expected = if moore { 10 } else { 6 };
+            if tokens.len() != expected {
+                return Err(GollyError::Inputs {
+                    line,
+                    expected,
+                    actual: tokens.len(),
+                });
+            }
+            let n = expected - 2;
+            let permutations = symmetries(symmetry, n)?;
+            for (inputs, output) in expand(&tokens, states, &variables, line)? {
+                let mut expanded: Vec<Vec<Vec<Cell>>> = Vec::new();
+                match &permutations {
+                    Some(permutations) => {
+                        for p in permutations {
+                            let mut permuted = vec![inputs[0].clone()];
+                            permuted.extend(p.iter().map(|&i| inputs[1 + i].clone()));
+                            if !expanded.contains(&permuted) {
+                                expanded.push(permuted);
+                            }
+                        }
+                    }
+                    None => {
+                        let mut neighbours = inputs[1..].to_vec();
+                        neighbours.sort_unstable();
+                        loop {
+                            expanded.push([&inputs[..1], &neighbours[..]].concat());
+                            if !next_permutation(&mut neighbours) {
+                                break;
+                            }
+                        }
+                    }
+                }
+                transitions.extend(expanded.into_iter().map(|inputs| (inputs, output)));
+            }
+        }
+    }
+    let states = states.ok_or(GollyError::States(0))?;
+    // Validate the symmetries of tables without transitions too.
+    symmetries(symmetry, if moore { 8 } else { 4 })?;
+
+    let words = transitions.len().div_ceil(64).max(1);
+    let inputs = if moore { 9 } else { 5 };
+    let mut matches = vec![0; inputs * states as usize * words];
+    for (t, (sets, _)) in transitions.iter().enumerate() {
+        for (i, set) in sets.iter().enumerate() {
+            for &state in set {
+                matches[(i * states as usize + state as usize) * words + t / 64] |= 1 << (t % 64);
+            }
+        }
+    }
+    let mut rule = GollyRule {
+        name: String::new(),
+        states,
+        moore,
+        inputs: Vec::new(),
+        transitions: Transitions::Table {
+            matches,
+            words,
+            outputs: transitions.iter().map(|&(_, output)| output).collect(),
+        },
+        colors: Vec::new(),
+    };
+    let order: &[(isize, isize)] = if moore {
+        &MOORE_TABLE
+    } else {
+        &VON_NEUMANN_TABLE
+    };
+    rule.inputs = [vec![None], rule.positions(order)].concat();
+    Ok(rule)
+}
+
+/// Parses the lines of a `@TREE` section.
+///
+fn parse_tree(lines: &[(usize, &str)]) -> Result<GollyRule, GollyError> {
+    let mut settings: HashMap<&str, &str> = HashMap::new();
+    let mut nodes: Vec<usize> = Vec::new();
+    let mut levels: Vec<usize> = Vec::new();
+    for &(line, text) in lines {
+        if let Some((key, value)) = setting(text) {
+            settings.insert(key, value);
+            continue;
+        }
+        let states = parse_states(settings.get("num_states").copied())? as usize;
+        let values = text
+            .split_whitespace()
+            .map(|v| v.parse::<usize>().map_err(|_| GollyError::Syntax(line)))
+            .collect::<Result<Vec<usize>, GollyError>>()?;
+        let Some((&level, children)) = values.split_first() else {
+            return Err(GollyError::Syntax(line));
+        };
+        if children.len() != states {
+            return Err(GollyError::Inputs {
+                line,
+                expected: states + 1,
+                actual: values.len(),
+            });
+        }
+        for &child in children {
+            let valid = match level {
+                0 => false,
+                1 => child < states,
+                _ => levels.get(child) == Some(&(level - 1)),
+            };
+            if !valid {
+                return Err(GollyError::Node(line));
+            }
+        }
+        nodes.extend(children);
+        levels.push(level);
+    }
+    let states = parse_states(settings.get("num_states").copied())?;
+    let moore = match settings.get("num_neighbors").copied() {
+        Some("8") => true,
+        Some("4") => false,
+        other => return Err(GollyError::Neighbourhood(other.unwrap_or("").to_string())),
+    };
+    let inputs = if moore { 9 } else { 5 };
+    let last = lines.last().map_or(0, |&(line, _)| line);
+    if levels.last() != Some(&inputs) {
+        return Err(GollyError::Node(last));
+    }
+    if let Some(count) = settings.get("num_nodes") {
+        if count.parse::<usize>().ok() != Some(levels.len()) {
+            return Err(GollyError::Node(last));
+        }
+    }
+    let mut rule = GollyRule {
+        name: String::new(),
+        states,
+        moore,
+        inputs: Vec::new(),
+        transitions: Transitions::Tree {
+            nodes,
+            root: levels.len() - 1,
+        },
+        colors: Vec::new(),
+    };
+    let order: &[(isize, isize)] = if moore {
+        &MOORE_TREE
+    } else {
+        &VON_NEUMANN_TREE
+    };
+    rule.inputs = [rule.positions(order), vec![None]].concat();
+    Ok(rule)
+}
+
+/// Parses the lines of a `@COLORS` section: `state r g b` sets the colour of a state, and
+/// `r g b r g b` a gradient over the states above 0.
+///
+fn parse_colors(lines: &[(usize, &str)], states: Cell) -> Result<Vec<(Cell, Rgba)>, GollyError> {
+    let mut colors = Vec::new();
+    for &(line, text) in lines {
+        let values = text
+            .split_whitespace()
+            .map(|v| v.parse::<u8>().map_err(|_| GollyError::Syntax(line)))
+            .collect::<Result<Vec<u8>, GollyError>>()?;
+        match values[..] {
+            [state, r, g, b] if state < states => colors.push((state, [r, g, b, 255])),
+            [state, _, _, _] => return Err(GollyError::State(line, state as usize)),
+            [r0, g0, b0, r1, g1, b1] => {
+                let live = states - 1;
+                for state in 1..states {
+                    let t = match live {
+                        1 => 0.0,
+                        _ => (state - 1) as f32 / (live - 1) as f32,
+                    };
+                    let mix = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+                    colors.push((state, [mix(r0, r1), mix(g0, g1), mix(b0, b1), 255]));
+                }
+            }
+            _ => return Err(GollyError::Syntax(line)),
+        }
+    }
+    Ok(colors)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ltl_engine::board::Board;
+
+    const WIREWORLD: &str = "@RULE WireWorld
+# Electrons move along conductors.
+@TABLE
+n_states:4
+neighborhood:Moore
+symmetries:permute
+var a={0,1,2,3}
+var b={a}
+var c={a}
+var d={a}
+var e={a}
+var f={a}
+var g={a}
+var h={a}
+var o={0,2,3}
+var p={o}
+var q={o}
+var r={o}
+var s={o}
+var t={o}
+var u={o}
+1,a,b,c,d,e,f,g,h,2
+2,a,b,c,d,e,f,g,h,3
+3,1,o,p,q,r,s,t,u,1
+3,1,1,o,p,q,r,s,t,1
+@COLORS
+0 48 48 48
+1 0 128 255
+2 255 255 255
+3 255 128 0
+";
+
+    #[test]
+    fn wireworld_table() {
+        let rule: GollyRule = WIREWORLD.parse().unwrap();
+        assert_eq!(rule.name(), "WireWorld");
+        assert_eq!(rule.states(), 4);
+        let mut board = Board::new(8, rule.clone());
+        for x in 0..8 {
+            board.set_cell(x, 3, 3);
+        }
+        board.set_cell(1, 3, 2);
+        board.set_cell(2, 3, 1);
+        for generation in 0..4 {
+            assert_eq!(board.get_cell(2 + generation, 3), 1);
+            assert_eq!(board.get_cell(1 + generation, 3), 2);
+            assert_eq!(board.get_cell(generation, 3), 3);
+            board.update();
+        }
+        // Two heads next to a conductor cell make it a head, three don't.
+        assert_eq!(rule.next_state(3, &[1, 1, 0, 0, 0, 0, 0, 0]), 1);
+        assert_eq!(rule.next_state(3, &[1, 1, 0, 0, 0, 0, 0, 1]), 3);
+
+        let palette = rule.palette();
+        assert_eq!(palette.color(0), [48, 48, 48, 255]);
+        assert_eq!(palette.color(3), [255, 128, 0, 255]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn next_state_of_short_neighbourhood() {
+        let rule: GollyRule = WIREWORLD.parse().unwrap();
+        rule.next_state(3, &[1, 1]);
+    }
+
+    #[test]
+    fn bound_variables_and_symmetries() {
+        // A dead cell takes the state of two opposite neighbours in the same state.
+        let table = "@RULE Opposites
+@TABLE
+n_states:3
+neighborhood:vonNeumann
+symmetries:rotate4
+var a={1,2}
+var b={0,1,2}
+var c={b}
+0,a,b,a,c,a
+";
+        let rule: GollyRule = table.parse().unwrap();
+        let mut board = Board::new(5, rule);
+        board.set_cell(2, 1, 2);
+        board.set_cell(2, 3, 2);
+        board.set_cell(0, 0, 2);
+        board.set_cell(2, 0, 1);
+        board.update();
+        assert_eq!(board.get_cell(2, 2), 2);
+        // Different states don't match the bound variable.
+        assert_eq!(board.get_cell(1, 0), 0);
+        assert_eq!(board.get_cell(2, 1), 2);
+
+        let compact = "@TABLE\nn_states:2\nneighborhood:vonNeumann\nsymmetries:none\n010001\n";
+        let rule: GollyRule = compact.parse().unwrap();
+        assert_eq!(rule.next_state(0, &[0, 1, 0, 0]), 1);
+        assert_eq!(rule.next_state(0, &[1, 0, 0, 0]), 0);
+        assert_eq!(rule.next_state(1, &[1, 0, 0, 0]), 1);
+    }
+
+    #[test]
+    fn tree() {
+        // The next state is the north neighbour xor the south neighbour.
+        let tree = "@RULE Xor
+@TREE
+num_states=2
+num_neighbors=4
+num_nodes=9
+1 0 0
+1 1 1
+2 0 1
+2 1 0
+3 2 2
+3 3 3
+4 4 4
+4 5 5
+5 6 7
+@COLORS
+255 0 0 0 0 255
+";
+        let rule: GollyRule = tree.parse().unwrap();
+        assert_eq!(rule.palette().color(1), [255, 0, 0, 255]);
+        let mut board = Board::new(5, rule);
+        board.set_cell(2, 2, 1);
+        board.update();
+        assert_eq!(board.get_cell(2, 1), 1);
+        assert_eq!(board.get_cell(2, 3), 1);
+        assert_eq!(board.get_cell(2, 2), 0);
+        assert_eq!(board.get_cell(1, 2), 0);
+    }
+
+    #[test]
+    fn invalid_rules() {
+        let error = |text: &str| text.parse::<GollyRule>().unwrap_err().to_string();
+        assert_eq!(error("@RULE Empty"), GollyError::NoRule.to_string());
+        assert_eq!(
+            error("@TABLE\nn_states:2\nneighborhood:hexagonal"),
+            "Neighbourhood hexagonal isn't supported"
+        );
+        assert_eq!(
+            error("@TABLE\nn_states:2\nneighborhood:vonNeumann\nsymmetries:rotate8"),
+            "Symmetries rotate8 aren't supported"
+        );
+        assert_eq!(
+            error("@TABLE\nn_states:2\n0,1,2,0,0,0,0,0,0,1"),
+            "State 2 on line 3 is outside the rule"
+        );
+        assert_eq!(
+            error("@TABLE\nn_states:2\n0,1,x,0,0,0,0,0,0,1"),
+            "Unknown variable x on line 3"
+        );
+        assert_eq!(
+            error("@TABLE\nn_states:2\nvar a={0,1}\n0,1,0,0,0,0,0,0,0,a"),
+            "Next state on line 4 isn't bound by the inputs"
+        );
+        assert_eq!(
+            error("@TREE\nnum_states=2\nnum_neighbors=4\n1 0 0\n2 0 3"),
+            "Invalid tree node on line 5"
+        );
+    }
+}
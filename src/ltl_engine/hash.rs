@@ -0,0 +1,558 @@
+use crate::ltl_engine::cells::{Cell, Cells, Region};
+use std::collections::VecDeque;
+use std::fmt;
+
+/// The Mersenne prime 2^61 - 1, modulus of the board hashes.
+pub const MODULUS: u64 = (1 << 61) - 1;
+
+/// Bases of the polynomial hash along the x and y axes.
+const X_BASE: u64 = 0x0f2c_3b6e_9a1d_4c57;
+const Y_BASE: u64 = 0x1b87_d1e4_5f03_a2c9;
+
+/// Number of generations whose hashes are kept by default.
+pub const HASH_HISTORY: usize = 1024;
+
+#[inline]
+pub fn add(a: u64, b: u64) -> u64 {
+    let sum = a + b;
+    if sum >= MODULUS {
+        sum - MODULUS
+    } else {
+        sum
+    }
+}
+
+#[inline]
+pub fn sub(a: u64, b: u64) -> u64 {
+    if a >= b {
+        a - b
+    } else {
+        a + MODULUS - b
+    }
+}
+
+#[inline]
+pub fn mul(a: u64, b: u64) -> u64 {
+    let product = a as u128 * b as u128;
+    let folded = (product & MODULUS as u128) as u64 + (product >> 61) as u64;
+    if folded >= MODULUS {
+        folded - MODULUS
+    } else {
+        folded
+    }
+}
+
+pub fn pow(mut base: u64, mut exp: u64) -> u64 {
+    let mut result = 1;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mul(result, base);
+        }
+        base = mul(base, base);
+        exp >>= 1;
+    }
+    result
+}
+
+/// Returns the multiplicative inverse modulo 2^61 - 1.
+///
+pub fn inverse(a: u64) -> u64 {
+    pow(a, MODULUS - 2)
+}
+
+/// Polynomial hash of the cells of a board, the sum of `state * X^x * Y^y` over all cells
+/// modulo 2^61 - 1. A change of a single cell changes the hash by its own term only, so it
+/// can be maintained cheaply.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct StateHasher {
+    x_powers: Vec<u64>,
+    y_powers: Vec<u64>,
+    x_inverse_powers: Vec<u64>,
+    y_inverse_powers: Vec<u64>,
+}
+
+impl StateHasher {
+    /// Create a hasher for boards of the given size.
+    ///
+    pub fn new(size: usize) -> Self {
+        StateHasher {
+            x_powers: powers(X_BASE, size),
+            y_powers: powers(Y_BASE, size),
+            x_inverse_powers: powers(inverse(X_BASE), size),
+            y_inverse_powers: powers(inverse(Y_BASE), size),
+        }
+    }
+
+    /// Returns the term of a cell.
+    ///
+    #[inline]
+    pub fn cell(&self, x: usize, y: usize, state: Cell) -> u64 {
+        if state == 0 {
+            return 0;
+        }
+        mul(mul(state as u64, self.x_powers[x]), self.y_powers[y])
+    }
+
+    /// Returns the sum of the terms of a column.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - The x coordinate of the column.
+    /// * `column` - The cells of the column.
+    ///
+    pub fn column(&self, x: usize, column: &[Cell]) -> u64 {
+        let sum = column
+            .iter()
+            .zip(&self.y_powers)
+            .filter(|(&state, _)| state != 0)
+            .fold(0, |sum, (&state, &power)| {
+                add(sum, mul(state as u64, power))
+            });
+        mul(sum, self.x_powers[x])
+    }
+
+    /// Returns the hash of all cells.
+    ///
+    pub fn hash(&self, cells: &Cells) -> u64 {
+        cells
+            .iter()
+            .enumerate()
+            .fold(0, |hash, (x, column)| add(hash, self.column(x, column)))
+    }
+
+    /// Returns the hash after a cell changed from `before` to `after`.
+    ///
+    pub fn replace(&self, hash: u64, x: usize, y: usize, before: Cell, after: Cell) -> u64 {
+        add(sub(hash, self.cell(x, y, before)), self.cell(x, y, after))
+    }
+
+    /// Returns the hash of the cells moved so that the bounding box starts at the origin.
+    /// Patterns equal up to a translation have equal shapes.
+    ///
+    pub fn shape(&self, hash: u64, bounding_box: Option<Region>) -> u64 {
+        match bounding_box {
+            None => hash,
+            Some(b) => mul(
+                mul(hash, self.x_inverse_powers[b.x]),
+                self.y_inverse_powers[b.y],
+            ),
+        }
+    }
+
+    /// Describes a generation for cycle and translation detection.
+    ///
+    /// # Arguments
+    ///
+    /// * `hash` - The hash of the generation.
+    /// * `bounding_box` - The bounding box of the live cells.
+    /// * `margin` - The distance from the edges of the board a pattern must keep to evolve
+    ///   as on an unbounded plane, the radius of the neighbourhood.
+    ///
+    pub fn snapshot(&self, hash: u64, bounding_box: Option<Region>, margin: usize) -> Snapshot {
+        let size = self.x_powers.len();
+        let clear = bounding_box.is_none_or(|b| {
+            b.x >= margin
+                && b.y >= margin
+                && b.x + b.width + margin <= size
+                && b.y + b.height + margin <= size
+        });
+        Snapshot {
+            hash,
+            shape: self.shape(hash, bounding_box),
+            bounding_box,
+            clear,
+        }
+    }
+}
+
+/// Hashes of a generation.
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Snapshot {
+    pub hash: u64,
+    /// The hash of the cells relative to their bounding box.
+    pub shape: u64,
+    pub bounding_box: Option<Region>,
+    /// Whether the live cells are far enough from the edges of the board not to feel them.
+    pub clear: bool,
+}
+
+impl Snapshot {
+    fn origin(&self) -> (i64, i64) {
+        self.bounding_box
+            .map_or((0, 0), |b| (b.x as i64, b.y as i64))
+    }
+}
+
+fn powers(base: u64, count: usize) -> Vec<u64> {
+    let mut powers = Vec::with_capacity(count);
+    let mut power = 1;
+    for _ in 0..count {
+        powers.push(power);
+        power = mul(power, base);
+    }
+    powers
+}
+
+/// A repetition of board states.
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Cycle {
+    /// The first generation of the repeating sequence.
+    pub start: u64,
+    /// The number of generations after which the states repeat, `1` for a fixed point.
+    pub period: u64,
+}
+
+/// A pattern repeating up to a translation, such as a spaceship.
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Translation {
+    /// The first generation of the repeating sequence.
+    pub start: u64,
+    /// The number of generations after which the pattern repeats.
+    pub period: u64,
+    /// Displacement along the x axis during one period.
+    pub dx: i64,
+    /// Displacement along the y axis during one period.
+    pub dy: i64,
+}
+
+impl Translation {
+    /// Returns the speed of the pattern as a fraction of the speed of light, one cell per
+    /// generation.
+    ///
+    pub fn speed(&self) -> f64 {
+        self.dx.abs().max(self.dy.abs()) as f64 / self.period as f64
+    }
+
+    /// Returns true if the pattern moves.
+    ///
+    pub fn is_moving(&self) -> bool {
+        self.dx != 0 || self.dy != 0
+    }
+}
+
+impl fmt::Display for Translation {
+    /// Formats the translation in the usual `(dx,dy)c/period` notation.
+    ///
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({},{})c/{}", self.dx, self.dy, self.period)
+    }
+}
+
+/// Hashes of consecutive generations, the latest last.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct HashHistory {
+    capacity: usize,
+    /// Generation of the latest hash.
+    generation: u64,
+    snapshots: VecDeque<Snapshot>,
+}
+
+impl HashHistory {
+    /// Create a history holding a single generation.
+    ///
+    /// # Arguments
+    ///
+    /// * `capacity` - The number of generations kept, at least one.
+    /// * `generation` - The generation of the snapshot.
+    /// * `snapshot` - The hashes of the generation.
+    ///
+    pub fn new(capacity: usize, generation: u64, snapshot: Snapshot) -> Self {
+        let mut snapshots = VecDeque::with_capacity(capacity);
+        snapshots.push_back(snapshot);
+        HashHistory {
+            capacity: capacity.max(1),
+            generation,
+            snapshots,
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Changes the number of generations kept, dropping the oldest ones if needed.
+    ///
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity.max(1);
+        while self.snapshots.len() > self.capacity {
+            self.snapshots.pop_front();
+        }
+    }
+
+    /// Returns the hashes of the latest generation.
+    ///
+    pub fn latest(&self) -> &Snapshot {
+        self.snapshots.back().unwrap()
+    }
+
+    /// Appends the hashes of the next generation.
+    ///
+    pub fn push(&mut self, snapshot: Snapshot) {
+        if self.snapshots.len() == self.capacity {
+            self.snapshots.pop_front();
+        }
+        self.snapshots.push_back(snapshot);
+        self.generation += 1;
+    }
+
+    /// Forgets all generations, keeping only the given one.
+    ///
+    pub fn restart(&mut self, generation: u64, snapshot: Snapshot) {
+        self.snapshots.clear();
+        self.snapshots.push_back(snapshot);
+        self.generation = generation;
+    }
+
+    /// Looks for an earlier generation equal to the latest one.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_period` - The longest period searched for, limited by the capacity.
+    ///
+    /// # Returns
+    ///
+    /// The shortest cycle the latest generation is part of, with the earliest known
+    /// generation it started at.
+    ///
+    pub fn detect_cycle(&self, max_period: usize) -> Option<Cycle> {
+        let (start, period) =
+            self.find_repeat(self.snapshots.len(), max_period, |a, b| a.hash == b.hash)?;
+        Some(Cycle { start, period })
+    }
+
+    /// Looks for an earlier generation equal to the latest one up to a translation. Only the
+    /// generations clear of the edges of the board are considered, so the boundary doesn't
+    /// take part in the movement.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_period` - The longest period searched for, limited by the capacity.
+    ///
+    /// # Returns
+    ///
+    /// The shortest period of the latest generation with its displacement, `None` when no
+    /// earlier clear generation has the same shape.
+    ///
+    pub fn detect_translation(&self, max_period: usize) -> Option<Translation> {
+        let clear = self
+            .snapshots
+            .iter()
+            .rev()
+            .take_while(|snapshot| snapshot.clear)
+            .count();
+        let (x1, y1) = self.latest().origin();
+        let period = (1..=max_period.min(clear.saturating_sub(1)))
+            .find(|&p| self.snapshots[self.snapshots.len() - 1 - p].shape == self.latest().shape)?;
+        let (x0, y0) = self.snapshots[self.snapshots.len() - 1 - period].origin();
+        let (dx, dy) = (x1 - x0, y1 - y0);
+        let (start, period) = self.find_repeat(clear, period, |a, b| {
+            let ((ax, ay), (bx, by)) = (a.origin(), b.origin());
+            a.shape == b.shape && (bx - ax, by - ay) == (dx, dy)
+        })?;
+        Some(Translation {
+            start,
+            period,
+            dx,
+            dy,
+        })
+    }
+
+    /// Finds the shortest period after which the latest snapshot repeats and walks back to the
+    /// earliest generation of the repetition.
+    ///
+    /// # Arguments
+    ///
+    /// * `window` - The number of latest snapshots searched.
+    /// * `max_period` - The longest period searched for.
+    /// * `repeats` - Returns true if the second snapshot repeats the first one.
+    ///
+    fn find_repeat<F: Fn(&Snapshot, &Snapshot) -> bool>(
+        &self,
+        window: usize,
+        max_period: usize,
+        repeats: F,
+    ) -> Option<(u64, u64)> {
+        let last = self.snapshots.len() - 1;
+        let first = self.snapshots.len() - window;
+        let latest = &self.snapshots[last];
+        let period = (1..=max_period.min(last - first))
+            .find(|&p| repeats(&self.snapshots[last - p], latest))?;
+        let mut start = last - period;
+        while start > first
+            && repeats(
+                &self.snapshots[start - 1],
+                &self.snapshots[start - 1 + period],
+            )
+        {
+            start -= 1;
+        }
+        Some((self.generation - (last - start) as u64, period as u64))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ltl_engine::stats::bounding_box;
+
+    fn snapshot(hash: u64) -> Snapshot {
+        Snapshot {
+            hash,
+            shape: hash,
+            bounding_box: None,
+            clear: true,
+        }
+    }
+
+    #[test]
+    fn modular_arithmetic() {
+        assert_eq!(add(MODULUS - 1, 2), 1);
+        assert_eq!(sub(1, 2), MODULUS - 1);
+        assert_eq!(mul(MODULUS - 1, MODULUS - 1), 1);
+        assert_eq!(mul(inverse(X_BASE), X_BASE), 1);
+    }
+
+    #[test]
+    fn hash_distinguishes_positions() {
+        let hasher = StateHasher::new(2);
+        let a = Cells::from(vec![vec![1, 0], vec![0, 0]]);
+        let b = Cells::from(vec![vec![0, 1], vec![0, 0]]);
+        let c = Cells::from(vec![vec![0, 0], vec![1, 0]]);
+        assert_eq!(hasher.hash(&Cells::new(2)), 0);
+        assert_ne!(hasher.hash(&a), hasher.hash(&b));
+        assert_ne!(hasher.hash(&b), hasher.hash(&c));
+    }
+
+    #[test]
+    fn replace_matches_full_hash() {
+        let hasher = StateHasher::new(3);
+        let mut cells = Cells::from(vec![vec![0, 1, 2], vec![3, 0, 1], vec![0, 0, 1]]);
+        let hash = hasher.hash(&cells);
+        cells[1][2] = 4;
+        assert_eq!(hasher.replace(hash, 1, 2, 1, 4), hasher.hash(&cells));
+    }
+
+    #[test]
+    fn detect_fixed_point() {
+        let mut history = HashHistory::new(8, 0, snapshot(5));
+        history.push(snapshot(6));
+        history.push(snapshot(7));
+        assert_eq!(history.detect_cycle(4), None);
+        history.push(snapshot(7));
+        assert_eq!(
+            history.detect_cycle(4),
+            Some(Cycle {
+                start: 2,
+                period: 1
+            })
+        );
+    }
+
+    #[test]
+    fn detect_oscillator() {
+        let mut history = HashHistory::new(8, 10, snapshot(1));
+        for hash in [2, 3, 4, 2, 3, 4, 2] {
+            history.push(snapshot(hash));
+        }
+        assert_eq!(history.detect_cycle(2), None);
+        assert_eq!(
+            history.detect_cycle(5),
+            Some(Cycle {
+                start: 11,
+                period: 3
+            })
+        );
+    }
+
+    #[test]
+    fn capacity_limits_history() {
+        let mut history = HashHistory::new(3, 0, snapshot(1));
+        for hash in [2, 3, 1] {
+            history.push(snapshot(hash));
+        }
+        assert_eq!(history.detect_cycle(3), None);
+        history.set_capacity(1);
+        assert_eq!(history.latest().hash, 1);
+    }
+
+    #[test]
+    fn shape_ignores_translation() {
+        let hasher = StateHasher::new(4);
+        let a = Cells::from(vec![
+            vec![0, 1, 0, 0],
+            vec![0, 2, 1, 0],
+            vec![0; 4],
+            vec![0; 4],
+        ]);
+        let b = Cells::from(vec![
+            vec![0; 4],
+            vec![0; 4],
+            vec![0, 0, 1, 0],
+            vec![0, 0, 2, 1],
+        ]);
+        let shape = |cells: &Cells| hasher.shape(hasher.hash(cells), bounding_box(cells));
+        assert_eq!(shape(&a), shape(&b));
+        assert_ne!(hasher.hash(&a), hasher.hash(&b));
+    }
+
+    #[test]
+    fn detect_glider_like_translation() {
+        let hasher = StateHasher::new(16);
+        let at = |x: usize, y: usize, shape: u64| Snapshot {
+            hash: x as u64 * 100 + y as u64,
+            shape,
+            bounding_box: Some(Region {
+                x,
+                y,
+                width: 2,
+                height: 2,
+            }),
+            clear: true,
+        };
+        let mut history = HashHistory::new(16, 0, hasher.snapshot(0, None, 1));
+        history.push(at(5, 5, 1));
+        history.push(at(5, 5, 2));
+        history.push(at(6, 5, 1));
+        history.push(at(6, 5, 2));
+        history.push(at(7, 5, 1));
+        assert_eq!(history.detect_cycle(4), None);
+        let translation = history.detect_translation(4).unwrap();
+        assert_eq!(
+            translation,
+            Translation {
+                start: 1,
+                period: 2,
+                dx: 1,
+                dy: 0
+            }
+        );
+        assert_eq!(translation.speed(), 0.5);
+        assert_eq!(translation.to_string(), "(1,0)c/2");
+    }
+
+    #[test]
+    fn translation_ignores_boundary() {
+        let hasher = StateHasher::new(8);
+        let region = |x| {
+            Some(Region {
+                x,
+                y: 3,
+                width: 1,
+                height: 1,
+            })
+        };
+        assert!(hasher.snapshot(0, region(2), 2).clear);
+        assert!(!hasher.snapshot(0, region(1), 2).clear);
+        assert!(!hasher.snapshot(0, region(6), 2).clear);
+
+        let mut history = HashHistory::new(8, 0, hasher.snapshot(1, region(6), 2));
+        history.push(hasher.snapshot(1, region(6), 2));
+        assert_eq!(history.detect_translation(2), None);
+        assert!(history.detect_cycle(2).is_some());
+    }
+}
@@ -0,0 +1,616 @@
+use crate::ltl_engine::cells::Cell;
+use crate::ltl_engine::Rule;
+use std::error::Error;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::str::FromStr;
+
+const PNG: &str = "png";
+const PPM: &str = "ppm";
+const SCALE: &str = "scale";
+const CROP: &str = "crop";
+
+pub type Rgba = [u8; 4];
+
+pub const WHITE: Rgba = [255, 255, 255, 255];
+pub const BLACK: Rgba = [0, 0, 0, 255];
+pub const LIGHT_GREY: Rgba = [200, 200, 200, 255];
+
+/// The largest number of colours of a palette, one per cell state.
+///
+pub const MAX_COLORS: usize = Cell::MAX as usize + 1;
+
+/// A mapping from cell states to colours.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct Palette {
+    colors: Vec<Rgba>,
+}
+
+impl Palette {
+    /// Creates a new palette with one colour per cell state.
+    ///
+    /// # Arguments
+    ///
+    /// * `colors` - Colours of the states, starting from the dead state.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `colors` is empty or holds more than 256 colours, the
+    /// number of cell states.
+    ///
+    pub fn new(colors: Vec<Rgba>) -> Self {
+        if colors.is_empty() {
+            panic!("Palette requires at least one colour");
+        }
+        if colors.len() > MAX_COLORS {
+            panic!("Palette supports at most {} colours", MAX_COLORS);
+        }
+        Palette { colors }
+    }
+
+    /// Creates a palette where live cells use `live` and decaying states fade linearly
+    /// from `live` towards `faded`.
+    ///
+    /// # Arguments
+    ///
+    /// * `states` - The number of cell states.
+    /// * `background` - The colour of dead cells.
+    /// * `live` - The colour of live cells.
+    /// * `faded` - The colour of the last decaying state.
+    ///
+    /// # Returns
+    ///
+    /// A palette with `states` colours.
+    ///
+    pub fn gradient(states: u8, background: Rgba, live: Rgba, faded: Rgba) -> Self {
+        let mut colors = vec![background, live];
+        let decaying = states.saturating_sub(2) as usize;
+        for i in 1..=decaying {
+            let t = i as f32 / decaying as f32;
+            let mut color = [0; 4];
+            for (c, (l, f)) in color.iter_mut().zip(live.iter().zip(faded.iter())) {
+                *c = (*l as f32 + (*f as f32 - *l as f32) * t).round() as u8;
+            }
+            colors.push(color);
+        }
+        colors.truncate(states.max(1) as usize);
+        Palette { colors }
+    }
+
+    /// Creates the default palette for the given configuration or rule: white background,
+    /// black live cells and states above 1 fading to light grey.
+    ///
+    pub fn for_config<R: Rule>(config: &R) -> Self {
+        Self::gradient(config.states(), WHITE, BLACK, LIGHT_GREY)
+    }
+
+    /// Returns the colour of the given state. States beyond the palette use its last colour.
+    ///
+    pub fn color(&self, state: u8) -> Rgba {
+        let last = self.colors.len() - 1;
+        self.colors[(state as usize).min(last)]
+    }
+
+    /// Returns the colours of the palette.
+    ///
+    pub fn colors(&self) -> &[Rgba] {
+        &self.colors
+    }
+}
+
+/// How an image is fitted to a board of a different size.
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Fit {
+    /// Resample the image to the board with nearest neighbour scaling.
+    Scale,
+    /// Keep one pixel per cell, cropping or padding around the centre of the image.
+    Crop,
+}
+
+impl FromStr for Fit {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            SCALE => Ok(Fit::Scale),
+            CROP => Ok(Fit::Crop),
+            _ => Err(()),
+        }
+    }
+}
+
+/// How pixel colours are mapped to cell states.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub enum Quantize {
+    /// Pixels darker than the given luminance become live cells, the rest stay dead.
+    Threshold(u8),
+    /// Every pixel takes the state of the nearest colour of the palette.
+    Nearest(Palette),
+}
+
+/// Errors raised while reading an image.
+///
+#[derive(Debug)]
+pub enum ImageError {
+    Io(io::Error),
+    Decoding(String),
+    TooManyStates { required: usize, available: usize },
+}
+
+impl fmt::Display for ImageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImageError::Io(e) => write!(f, "{}", e),
+            ImageError::Decoding(e) => write!(f, "Invalid image: {}", e),
+            ImageError::TooManyStates {
+                required,
+                available,
+            } => write!(
+                f,
+                "Image requires {} states, config allows {}",
+                required, available
+            ),
+        }
+    }
+}
+
+impl Error for ImageError {}
+
+impl From<io::Error> for ImageError {
+    fn from(e: io::Error) -> Self {
+        ImageError::Io(e)
+    }
+}
+
+impl From<png::DecodingError> for ImageError {
+    fn from(e: png::DecodingError) -> Self {
+        match e {
+            png::DecodingError::IoError(e) => ImageError::Io(e),
+            e => ImageError::Decoding(e.to_string()),
+        }
+    }
+}
+
+/// Supported still image formats.
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ImageFormat {
+    Png,
+    Ppm,
+}
+
+impl FromStr for ImageFormat {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            PNG => Ok(ImageFormat::Png),
+            PPM => Ok(ImageFormat::Ppm),
+            _ => Err(()),
+        }
+    }
+}
+
+impl ImageFormat {
+    /// Guesses the image format from the extension of the given path.
+    ///
+    pub fn from_path(path: &Path) -> Option<Self> {
+        path.extension()?.to_str()?.parse().ok()
+    }
+}
+
+/// An RGBA image stored row by row.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct Image {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<u8>,
+}
+
+impl Image {
+    /// Creates a new image filled with the given colour.
+    ///
+    pub fn new(width: usize, height: usize, fill: Rgba) -> Self {
+        Image {
+            width,
+            height,
+            pixels: fill.repeat(width * height),
+        }
+    }
+
+    /// Returns the colour of the pixel at the given coordinates.
+    ///
+    pub fn get_pixel(&self, x: usize, y: usize) -> Rgba {
+        let i = (y * self.width + x) * 4;
+        [
+            self.pixels[i],
+            self.pixels[i + 1],
+            self.pixels[i + 2],
+            self.pixels[i + 3],
+        ]
+    }
+
+    /// Sets the colour of the pixel at the given coordinates.
+    ///
+    pub fn set_pixel(&mut self, x: usize, y: usize, color: Rgba) {
+        let i = (y * self.width + x) * 4;
+        self.pixels[i..i + 4].copy_from_slice(&color);
+    }
+
+    /// Returns the luminance of the pixel at the given coordinates, composited over white.
+    ///
+    pub fn luminance(&self, x: usize, y: usize) -> u8 {
+        let [r, g, b] = self.composited(x, y);
+        ((299 * r as u32 + 587 * g as u32 + 114 * b as u32) / 1000) as u8
+    }
+
+    /// Returns the colour of the pixel at the given coordinates, composited over white.
+    ///
+    fn composited(&self, x: usize, y: usize) -> [u8; 3] {
+        let [r, g, b, a] = self.get_pixel(x, y);
+        let blend = |c: u8| ((c as u32 * a as u32 + 255 * (255 - a as u32)) / 255) as u8;
+        [blend(r), blend(g), blend(b)]
+    }
+
+    /// Returns the state of the pixel at the given coordinates.
+    ///
+    pub fn quantize(&self, x: usize, y: usize, quantize: &Quantize) -> u8 {
+        match quantize {
+            Quantize::Threshold(threshold) => (self.luminance(x, y) < *threshold) as u8,
+            Quantize::Nearest(palette) => {
+                let color = self.composited(x, y);
+                let distance = |c: &Rgba| -> u32 {
+                    (0..3)
+                        .map(|i| (c[i] as i32 - color[i] as i32).pow(2) as u32)
+                        .sum()
+                };
+                palette
+                    .colors()
+                    .iter()
+                    .enumerate()
+                    .min_by_key(|(_, c)| distance(c))
+                    .map_or(0, |(i, _)| i as u8)
+            }
+        }
+    }
+
+    /// Decodes a PNG, PPM or PGM image.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The encoded image.
+    ///
+    pub fn decode(data: &[u8]) -> Result<Image, ImageError> {
+        if data.starts_with(b"\x89PNG") {
+            Self::decode_png(data)
+        } else if data.starts_with(b"P") {
+            Self::decode_pnm(data)
+        } else {
+            Err(ImageError::Decoding(
+                "only PNG, PPM and PGM images are supported".to_string(),
+            ))
+        }
+    }
+
+    /// Reads a PNG, PPM or PGM image from a file.
+    ///
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Image, ImageError> {
+        Self::decode(&fs::read(path)?)
+    }
+
+    fn decode_png(data: &[u8]) -> Result<Image, ImageError> {
+        let mut decoder = png::Decoder::new(data);
+        decoder.set_transformations(png::Transformations::normalize_to_color8());
+        let mut reader = decoder.read_info()?;
+        let mut buffer = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut buffer)?;
+        let (width, height) = (info.width as usize, info.height as usize);
+        let channels = info.color_type.samples();
+        let mut image = Image::new(width, height, WHITE);
+        for y in 0..height {
+            let line = &buffer[y * info.line_size..];
+            for x in 0..width {
+                let p = &line[x * channels..(x + 1) * channels];
+                let color = match p {
+                    [l] => [*l, *l, *l, 255],
+                    [l, a] => [*l, *l, *l, *a],
+                    [r, g, b] => [*r, *g, *b, 255],
+                    [r, g, b, a] => [*r, *g, *b, *a],
+                    _ => unreachable!(),
+                };
+                image.set_pixel(x, y, color);
+            }
+        }
+        Ok(image)
+    }
+
+    /// Decodes plain and binary PGM (P2, P5) and PPM (P3, P6) images.
+    ///
+    fn decode_pnm(data: &[u8]) -> Result<Image, ImageError> {
+        let invalid = |msg: &str| ImageError::Decoding(msg.to_string());
+        let magic = data.get(..2).ok_or_else(|| invalid("truncated header"))?;
+        let (channels, binary) = match magic {
+            b"P2" => (1, false),
+            b"P3" => (3, false),
+            b"P5" => (1, true),
+            b"P6" => (3, true),
+            _ => return Err(invalid("only P2, P3, P5 and P6 images are supported")),
+        };
+
+        let mut pos = 2;
+        let next_token = |pos: &mut usize| -> Result<usize, ImageError> {
+            loop {
+                while *pos < data.len() && data[*pos].is_ascii_whitespace() {
+                    *pos += 1;
+                }
+                if *pos < data.len() && data[*pos] == b'#' {
+                    while *pos < data.len() && data[*pos] != b'\n' {
+                        *pos += 1;
+                    }
+                } else {
+                    break;
+                }
+            }
+            let start = *pos;
+            while *pos < data.len() && data[*pos].is_ascii_digit() {
+                *pos += 1;
+            }
+            std::str::from_utf8(&data[start..*pos])
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .ok_or_else(|| invalid("malformed header"))
+        };
+        let width = next_token(&mut pos)?;
+        let height = next_token(&mut pos)?;
+        let max_value = next_token(&mut pos)?;
+        if max_value == 0 || max_value > 65535 {
+            return Err(invalid("maximum value must be between 1 and 65535"));
+        }
+
+        // Pixels are stored as RGBA, so the image must fit 4 bytes per pixel.
+        let pixels = width
+            .checked_mul(height)
+            .filter(|pixels| pixels.checked_mul(4).is_some())
+            .ok_or_else(|| invalid("image too large"))?;
+        let samples = pixels * channels;
+        let values: Vec<usize> = if binary {
+            pos += 1;
+            let size = if max_value > 255 { 2 } else { 1 };
+            let raw = data
+                .get(pos..pos.saturating_add(samples * size))
+                .ok_or_else(|| invalid("truncated pixel data"))?;
+            raw.chunks_exact(size)
+                .map(|v| v.iter().fold(0, |acc, &b| (acc << 8) | b as usize))
+                .collect()
+        } else {
+            (0..samples)
+                .map(|_| next_token(&mut pos))
+                .collect::<Result<_, _>>()?
+        };
+
+        let mut image = Image::new(width, height, WHITE);
+        for (i, p) in values.chunks_exact(channels).enumerate() {
+            let scale = |v: usize| (v.min(max_value) * 255 / max_value) as u8;
+            let color = match p {
+                [l] => [scale(*l), scale(*l), scale(*l), 255],
+                [r, g, b] => [scale(*r), scale(*g), scale(*b), 255],
+                _ => unreachable!(),
+            };
+            image.set_pixel(i % width, i / width, color);
+        }
+        Ok(image)
+    }
+
+    /// Returns the pixels of the image without the alpha channel.
+    ///
+    pub fn to_rgb(&self) -> Vec<u8> {
+        self.pixels
+            .chunks_exact(4)
+            .flat_map(|p| [p[0], p[1], p[2]])
+            .collect()
+    }
+
+    /// Encodes the image as PNG.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - The destination of the encoded image.
+    ///
+    pub fn write_png<W: Write>(&self, writer: W) -> io::Result<()> {
+        let mut encoder = png::Encoder::new(writer, self.width as u32, self.height as u32);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&self.pixels)?;
+        Ok(())
+    }
+
+    /// Encodes the image as binary PPM (P6). The alpha channel is dropped.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - The destination of the encoded image.
+    ///
+    pub fn write_ppm<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        write!(writer, "P6\n{} {}\n255\n", self.width, self.height)?;
+        writer.write_all(&self.to_rgb())
+    }
+
+    /// Encodes the image in the given format.
+    ///
+    pub fn write<W: Write>(&self, writer: W, format: ImageFormat) -> io::Result<()> {
+        match format {
+            ImageFormat::Png => self.write_png(writer),
+            ImageFormat::Ppm => self.write_ppm(writer),
+        }
+    }
+
+    /// Encodes the image in memory in the given format.
+    ///
+    pub fn encode(&self, format: ImageFormat) -> io::Result<Vec<u8>> {
+        let mut buffer = Vec::new();
+        self.write(&mut buffer, format)?;
+        Ok(buffer)
+    }
+
+    /// Saves the image to a file, choosing the format from the file extension.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the file, ending with `.png` or `.ppm`.
+    ///
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let path = path.as_ref();
+        let format = ImageFormat::from_path(path).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Only .png and .ppm files are supported",
+            )
+        })?;
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write(&mut writer, format)?;
+        writer.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ltl_engine::config::Config;
+    use crate::ltl_engine::neighbourhood::Neighbourhood;
+
+    #[test]
+    fn gradient_two_states() {
+        let palette = Palette::gradient(2, WHITE, BLACK, LIGHT_GREY);
+        assert_eq!(palette.colors(), &[WHITE, BLACK]);
+    }
+
+    #[test]
+    fn gradient_decaying_states() {
+        let palette = Palette::gradient(4, WHITE, [0, 0, 0, 255], [100, 200, 50, 255]);
+        assert_eq!(
+            palette.colors(),
+            &[
+                WHITE,
+                [0, 0, 0, 255],
+                [50, 100, 25, 255],
+                [100, 200, 50, 255]
+            ]
+        );
+    }
+
+    #[test]
+    fn palette_for_config() {
+        let config = Config::new(1, 0, 0, (2, 3), (3, 3), Neighbourhood::Moore);
+        assert_eq!(Palette::for_config(&config).colors().len(), 2);
+    }
+
+    #[test]
+    fn palette_color_out_of_range() {
+        let palette = Palette::new(vec![WHITE, BLACK]);
+        assert_eq!(palette.color(7), BLACK);
+    }
+
+    #[test]
+    #[should_panic]
+    fn palette_empty() {
+        Palette::new(vec![]);
+    }
+
+    #[test]
+    fn format_from_path() {
+        assert_eq!(
+            ImageFormat::from_path(Path::new("out/board.PNG")),
+            Some(ImageFormat::Png)
+        );
+        assert_eq!(
+            ImageFormat::from_path(Path::new("board.ppm")),
+            Some(ImageFormat::Ppm)
+        );
+        assert_eq!(ImageFormat::from_path(Path::new("board.jpg")), None);
+    }
+
+    #[test]
+    fn image_pixels() {
+        let mut image = Image::new(2, 1, WHITE);
+        image.set_pixel(1, 0, BLACK);
+        assert_eq!(image.get_pixel(0, 0), WHITE);
+        assert_eq!(image.get_pixel(1, 0), BLACK);
+        assert_eq!(image.to_rgb(), vec![255, 255, 255, 0, 0, 0]);
+    }
+
+    #[test]
+    fn encode_ppm() {
+        let image = Image::new(1, 1, BLACK);
+        let ppm = image.encode(ImageFormat::Ppm).unwrap();
+        assert_eq!(ppm, b"P6\n1 1\n255\n\0\0\0".to_vec());
+    }
+
+    #[test]
+    fn encode_png() {
+        let image = Image::new(3, 2, BLACK);
+        let encoded = image.encode(ImageFormat::Png).unwrap();
+        assert_eq!(&encoded[..8], b"\x89PNG\r\n\x1a\n");
+    }
+
+    #[test]
+    fn decode_png_roundtrip() {
+        let mut image = Image::new(3, 2, WHITE);
+        image.set_pixel(2, 1, [10, 20, 30, 40]);
+        let decoded = Image::decode(&image.encode(ImageFormat::Png).unwrap()).unwrap();
+        assert_eq!(decoded, image);
+    }
+
+    #[test]
+    fn decode_ppm_roundtrip() {
+        let mut image = Image::new(2, 2, WHITE);
+        image.set_pixel(0, 1, [1, 2, 3, 255]);
+        let decoded = Image::decode(&image.encode(ImageFormat::Ppm).unwrap()).unwrap();
+        assert_eq!(decoded, image);
+    }
+
+    #[test]
+    fn decode_plain_pgm() {
+        let decoded = Image::decode(b"P2\n# comment\n2 1\n15\n0 15\n").unwrap();
+        assert_eq!(decoded.get_pixel(0, 0), BLACK);
+        assert_eq!(decoded.get_pixel(1, 0), WHITE);
+    }
+
+    #[test]
+    fn decode_truncated_pgm() {
+        assert!(matches!(
+            Image::decode(b"P5 4 4 255\n\0\0"),
+            Err(ImageError::Decoding(_))
+        ));
+    }
+
+    #[test]
+    fn decode_oversized_ppm() {
+        assert!(matches!(
+            Image::decode(b"P6 4294967296 4294967296 255\n"),
+            Err(ImageError::Decoding(msg)) if msg == "image too large"
+        ));
+    }
+
+    #[test]
+    fn decode_unknown_format() {
+        assert!(matches!(
+            Image::decode(b"GIF89a"),
+            Err(ImageError::Decoding(_))
+        ));
+    }
+
+    #[test]
+    fn quantize_threshold_transparent() {
+        let mut image = Image::new(2, 1, [0, 0, 0, 0]);
+        image.set_pixel(1, 0, BLACK);
+        assert_eq!(image.quantize(0, 0, &Quantize::Threshold(128)), 0);
+        assert_eq!(image.quantize(1, 0, &Quantize::Threshold(128)), 1);
+    }
+}
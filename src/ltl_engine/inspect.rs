@@ -0,0 +1,167 @@
+use crate::ltl_engine::board::Board;
+use crate::ltl_engine::cells::Cell;
+use crate::ltl_engine::neighbourhood::Neighbourhood;
+
+/// The condition deciding the next state of a cell.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Condition {
+    /// A dead cell is born when its count is in the birth range.
+    Birth,
+    /// A cell in state 1 stays alive when its count is in the survival range.
+    Survival,
+    /// A cell in a state above 1 moves on to the next state whatever its count.
+    Decay,
+}
+
+impl Condition {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Condition::Birth => "birth",
+            Condition::Survival => "survival",
+            Condition::Decay => "decay",
+        }
+    }
+}
+
+/// How the next state of a cell is computed.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Explanation {
+    pub state: Cell,
+    /// Cells of the neighbourhood as `(x, y, state)`, the cell itself included when `mm` is 1.
+    /// The board doesn't wrap, so cells near the edges have smaller neighbourhoods.
+    pub neighbours: Vec<(usize, usize, Cell)>,
+    /// Number of neighbours in a non-zero state.
+    pub count: u16,
+    pub condition: Condition,
+    /// The range the count was checked against, `None` for decaying cells.
+    pub range: Option<(u16, u16)>,
+    /// Whether the count is in the range, always true for decaying cells.
+    pub satisfied: bool,
+    pub next_state: Cell,
+}
+
+impl Board {
+    /// Explains how the next state of a cell follows from its neighbourhood.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - The x coordinate of the cell.
+    /// * `y` - The y coordinate of the cell.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the coordinates are outside the board.
+    ///
+    pub fn explain_cell(&self, x: usize, y: usize) -> Explanation {
+        let config = self.config();
+        let state = self.cells[x][y];
+        let r = config.rr as usize;
+        let last = self.cells.len() - 1;
+        let mut neighbours = Vec::new();
+        for x_i in x.saturating_sub(r)..=(x + r).min(last) {
+            for y_i in y.saturating_sub(r)..=(y + r).min(last) {
+                if config.mm == 0 && (x_i, y_i) == (x, y) {
+                    continue;
+                }
+                if config.nn == Neighbourhood::Neumann && x_i.abs_diff(x) + y_i.abs_diff(y) > r {
+                    continue;
+                }
+                neighbours.push((x_i, y_i, self.cells[x_i][y_i]));
+            }
+        }
+        let count = neighbours.iter().filter(|&&(_, _, s)| s > 0).count() as u16;
+        let (condition, range) = match state {
+            0 => (Condition::Birth, Some(config.bb)),
+            1 => (Condition::Survival, Some(config.ss)),
+            _ => (Condition::Decay, None),
+        };
+        Explanation {
+            state,
+            neighbours,
+            count,
+            condition,
+            range,
+            satisfied: range.is_none_or(|(min, max)| min <= count && count <= max),
+            next_state: self.update_cell(x, y),
+        }
+    }
+
+    /// Returns the number of live cells in the neighbourhood of every cell, column by column.
+    ///
+    pub fn neighbour_counts(&self) -> Vec<u16> {
+        let size = self.cells.len();
+        (0..size)
+            .flat_map(|x| (0..size).map(move |y| (x, y)))
+            .map(|(x, y)| self.get_neighbourhood_count(x, y))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ltl_engine::config::Config;
+
+    #[test]
+    fn explain_life_cells() {
+        let config = Config::new(1, 0, 0, (2, 3), (3, 3), Neighbourhood::Moore);
+        let mut board = Board::new(5, config);
+        for x in 1..4 {
+            board.set_cell(x, 2, 1);
+        }
+        let centre = board.explain_cell(2, 2);
+        assert_eq!(centre.neighbours.len(), 8);
+        assert_eq!(
+            (centre.state, centre.count, centre.condition, centre.range),
+            (1, 2, Condition::Survival, Some((2, 3)))
+        );
+        assert!(centre.satisfied);
+        assert_eq!(centre.next_state, 1);
+
+        let tip = board.explain_cell(1, 2);
+        assert_eq!((tip.count, tip.satisfied, tip.next_state), (1, false, 0));
+        let above = board.explain_cell(2, 1);
+        assert_eq!(above.condition, Condition::Birth);
+        assert_eq!(
+            (above.count, above.satisfied, above.next_state),
+            (3, true, 1)
+        );
+        let corner = board.explain_cell(0, 0);
+        assert_eq!(corner.neighbours, vec![(0, 1, 0), (1, 0, 0), (1, 1, 0)]);
+    }
+
+    #[test]
+    fn explain_neumann_and_decay() {
+        let config = Config::new(2, 4, 1, (1, 3), (2, 2), Neighbourhood::Neumann);
+        let mut board = Board::new(7, config);
+        board.set_cell(3, 3, 2);
+        board.set_cell(3, 1, 1);
+        board.set_cell(4, 4, 1);
+        let explanation = board.explain_cell(3, 3);
+        assert_eq!(explanation.neighbours.len(), 13);
+        assert!(explanation.neighbours.contains(&(3, 3, 2)));
+        assert_eq!(explanation.count, 3);
+        assert_eq!(explanation.condition, Condition::Decay);
+        assert_eq!((explanation.range, explanation.satisfied), (None, true));
+        assert_eq!(explanation.next_state, 3);
+    }
+
+    #[test]
+    fn neighbour_counts_match_explanations() {
+        let config = Config::new(2, 3, 0, (3, 6), (4, 5), Neighbourhood::Neumann);
+        let mut board = Board::new(9, config);
+        board.randomize(Some(5));
+        let counts = board.neighbour_counts();
+        let mut next = board.clone();
+        next.update();
+        for x in 0..9 {
+            for y in 0..9 {
+                let explanation = board.explain_cell(x, y);
+                assert_eq!(counts[x * 9 + y], explanation.count);
+                assert_eq!(next.get_cell(x, y), explanation.next_state);
+            }
+        }
+    }
+}
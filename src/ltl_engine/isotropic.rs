@@ -0,0 +1,448 @@
+use crate::ltl_engine::cells::Cell;
+use crate::ltl_engine::config::Config;
+use crate::ltl_engine::neighbourhood::Neighbourhood;
+use crate::ltl_engine::Rule;
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+/// Offsets of the neighbours of a cell, in the order boards pass them to rules. Bit `n` of an
+/// arrangement is set when the neighbour at `OFFSETS[n]` is alive.
+///
+const OFFSETS: [(isize, isize); 8] = [
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, -1),
+    (0, 1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+];
+
+/// Letters naming the arrangements of every number of live neighbours, in Hensel's notation.
+///
+const LETTERS: [&str; 9] = [
+    "",
+    "ce",
+    "ceaikn",
+    "ceaiknjqry",
+    "ceaiknjqrytwz",
+    "ceaiknjqry",
+    "ceaikn",
+    "ce",
+    "",
+];
+
+/// An arrangement of every letter of up to 4 live neighbours, as Golly writes them: bit
+/// `3 * (dy + 1) + dx + 1` for the neighbour at `(dx, dy)`. Arrangements of more neighbours
+/// are the complements of those of fewer.
+///
+const REPRESENTATIVES: [&[u16]; 5] = [
+    &[0],
+    &[1, 2],
+    &[5, 10, 3, 40, 33, 68],
+    &[69, 42, 11, 7, 98, 13, 14, 70, 41, 97],
+    &[325, 170, 15, 45, 99, 71, 106, 102, 43, 101, 105, 78, 108],
+];
+
+/// The eight neighbours in Golly's bit order, leaving out the centre.
+///
+const NEIGHBOURS: u16 = 0x1ef;
+
+/// Errors raised by parsing a rule string or converting a config.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub enum IsotropicError {
+    /// Rule strings are written `B{conditions}/S{conditions}`.
+    Syntax,
+    /// A letter doesn't name an arrangement of the number of neighbours it follows.
+    Letter(u8, char),
+    /// Only 2 state Moore configs of radius 1 have an isotropic form.
+    Config,
+}
+
+impl fmt::Display for IsotropicError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IsotropicError::Syntax => {
+                write!(f, "Rules are written B{{conditions}}/S{{conditions}}")
+            }
+            IsotropicError::Letter(count, letter) => {
+                write!(f, "Letter '{}' isn't an arrangement of {}", letter, count)
+            }
+            IsotropicError::Config => {
+                write!(
+                    f,
+                    "Only 2 state Moore configs of radius 1 are isotropic rules"
+                )
+            }
+        }
+    }
+}
+
+impl Error for IsotropicError {}
+
+/// Isotropic non-totalistic rule of radius 1: a dead cell is born, or a live cell survives,
+/// depending on the arrangement of its eight live neighbours up to rotations and reflections,
+/// written in Hensel's notation, e.g. `B2-a/S12`.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct IsotropicRule {
+    /// Next states of dead cells, indexed by arrangement.
+    birth: Vec<bool>,
+    /// Next states of live cells, indexed by arrangement.
+    survival: Vec<bool>,
+}
+
+impl IsotropicRule {
+    /// Create a rule from the arrangements of every number of neighbours it covers.
+    ///
+    /// # Arguments
+    ///
+    /// * `birth` - Bit `n` of `birth[count]` is set when dead cells are born with the `n`th
+    ///   letter of `count` live neighbours, bit 0 standing for 0 and 8 neighbours.
+    /// * `survival` - The same for live cells surviving.
+    ///
+    pub fn new(birth: [u16; 9], survival: [u16; 9]) -> Self {
+        let classes = classes();
+        let table = |letters: [u16; 9]| {
+            let mut table = vec![false; 256];
+            for (arrangement, &(count, letter)) in classes.iter().enumerate() {
+                table[arrangement] = letters[count as usize] >> letter & 1 == 1;
+            }
+            table
+        };
+        IsotropicRule {
+            birth: table(birth),
+            survival: table(survival),
+        }
+    }
+
+    /// Returns the isotropic form of a totalistic config.
+    ///
+    /// # Returns
+    ///
+    /// An error unless the config has 2 states and a Moore neighbourhood of radius 1.
+    ///
+    pub fn from_config(config: &Config) -> Result<Self, IsotropicError> {
+        if config.rr != 1 || config.nn != Neighbourhood::Moore || config.states() != 2 {
+            return Err(IsotropicError::Config);
+        }
+        let letters = |(min, max): (u16, u16), centre: u16| {
+            let mut letters = [0; 9];
+            for (count, letters) in letters.iter_mut().enumerate() {
+                if (min..=max).contains(&(count as u16 + centre)) {
+                    *letters = all_letters(count);
+                }
+            }
+            letters
+        };
+        // The centre is dead when a cell is born and alive when it survives.
+        Ok(IsotropicRule::new(
+            letters(config.bb, 0),
+            letters(config.ss, config.mm as u16),
+        ))
+    }
+
+    /// Returns the arrangement of the 8 neighbours of a cell, in the order boards pass them.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if there aren't exactly 8 neighbours.
+    ///
+    pub fn arrangement(neighbours: &[Cell]) -> u8 {
+        assert_eq!(neighbours.len(), 8, "Isotropic rules need 8 neighbours");
+        neighbours
+            .iter()
+            .enumerate()
+            .filter(|(_, &s)| s > 0)
+            .fold(0, |arrangement, (n, _)| arrangement | 1 << n)
+    }
+
+    /// Returns the next state of a cell with live neighbours arranged as `arrangement`.
+    ///
+    pub fn lookup(&self, state: Cell, arrangement: u8) -> Cell {
+        match state {
+            0 => self.birth[arrangement as usize] as Cell,
+            _ => self.survival[arrangement as usize] as Cell,
+        }
+    }
+}
+
+impl Rule for IsotropicRule {
+    fn states(&self) -> Cell {
+        2
+    }
+
+    fn radius(&self) -> usize {
+        1
+    }
+
+    fn contains(&self, dx: isize, dy: isize) -> bool {
+        (dx, dy) != (0, 0)
+    }
+
+    fn next_state(&self, state: Cell, neighbours: &[Cell]) -> Cell {
+        self.lookup(state, IsotropicRule::arrangement(neighbours))
+    }
+
+    fn positional(&self) -> bool {
+        true
+    }
+}
+
+impl fmt::Display for IsotropicRule {
+    /// Formats the rule the way Golly does: counts in increasing order, their letters left out
+    /// when every arrangement is covered and written after `-` when most of them are.
+    ///
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "B{}/S{}",
+            conditions(&self.birth),
+            conditions(&self.survival)
+        )
+    }
+}
+
+impl FromStr for IsotropicRule {
+    type Err = IsotropicError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (first, second) = s.trim().split_once('/').ok_or(IsotropicError::Syntax)?;
+        let condition = |part: &str, prefix: char| {
+            part.strip_prefix(prefix)
+                .or_else(|| part.strip_prefix(prefix.to_ascii_lowercase()))
+                .map(parse_conditions)
+        };
+        match (condition(first, 'B'), condition(second, 'S')) {
+            (Some(birth), Some(survival)) => Ok(IsotropicRule::new(birth?, survival?)),
+            _ => match (condition(second, 'B'), condition(first, 'S')) {
+                (Some(birth), Some(survival)) => Ok(IsotropicRule::new(birth?, survival?)),
+                _ => Err(IsotropicError::Syntax),
+            },
+        }
+    }
+}
+
+/// Returns the letters of every arrangement of `count` neighbours.
+///
+fn all_letters(count: usize) -> u16 {
+    (1 << LETTERS[count].len().max(1)) - 1
+}
+
+/// Returns the arrangements of every letter of `count` neighbours, in Golly's bit order.
+///
+fn representatives(count: usize) -> Vec<u16> {
+    match count {
+        0..=4 => REPRESENTATIVES[count].to_vec(),
+        _ => REPRESENTATIVES[8 - count]
+            .iter()
+            .map(|&r| r ^ NEIGHBOURS)
+            .collect(),
+    }
+}
+
+/// Returns an arrangement in Golly's bit order as a board arrangement.
+///
+fn arrangement(golly: u16, symmetry: usize) -> u8 {
+    OFFSETS
+        .iter()
+        .enumerate()
+        .filter(|&(_, &(dx, dy))| {
+            let (dx, dy) = match symmetry {
+                0 => (dx, dy),
+                1 => (-dy, dx),
+                2 => (-dx, -dy),
+                3 => (dy, -dx),
+                4 => (-dx, dy),
+                5 => (dx, -dy),
+                6 => (dy, dx),
+                _ => (-dy, -dx),
+            };
+            golly >> (3 * (dy + 1) + dx + 1) & 1 == 1
+        })
+        .fold(0, |arrangement, (n, _)| arrangement | 1 << n)
+}
+
+/// Returns the number of live neighbours and the letter of every arrangement.
+///
+fn classes() -> [(u8, u8); 256] {
+    let mut classes = [(0, 0); 256];
+    for count in 0..=8 {
+        for (letter, &golly) in representatives(count).iter().enumerate() {
+            for symmetry in 0..8 {
+                classes[arrangement(golly, symmetry) as usize] = (count as u8, letter as u8);
+            }
+        }
+    }
+    classes
+}
+
+/// Parses the conditions following `B` or `S`, returning the letters of every count.
+///
+fn parse_conditions(s: &str) -> Result<[u16; 9], IsotropicError> {
+    let mut letters = [0; 9];
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        let count = c.to_digit(9).ok_or(IsotropicError::Syntax)? as usize;
+        let negated = chars.next_if_eq(&'-').is_some();
+        let mut given = 0;
+        while let Some(letter) = chars.next_if(|c| c.is_ascii_alphabetic()) {
+            let n = LETTERS[count]
+                .find(letter)
+                .ok_or(IsotropicError::Letter(count as u8, letter))?;
+            given |= 1 << n;
+        }
+        letters[count] |= match (negated, given) {
+            (true, 0) => return Err(IsotropicError::Syntax),
+            (true, _) => all_letters(count) & !given,
+            (false, 0) => all_letters(count),
+            (false, _) => given,
+        };
+    }
+    Ok(letters)
+}
+
+/// Formats the arrangements of a table covered by the conditions of `B` or `S`.
+///
+fn conditions(table: &[bool]) -> String {
+    let mut conditions = String::new();
+    for (count, letters) in LETTERS.iter().enumerate() {
+        let covered: Vec<bool> = representatives(count)
+            .iter()
+            .map(|&golly| table[arrangement(golly, 0) as usize])
+            .collect();
+        let letters = |included: bool| {
+            letters
+                .chars()
+                .zip(&covered)
+                .filter(|&(_, &c)| c == included)
+                .map(|(letter, _)| letter)
+                .collect::<String>()
+        };
+        let n = covered.iter().filter(|&&c| c).count();
+        if n == 0 {
+            continue;
+        }
+        conditions.push_str(&count.to_string());
+        if n == covered.len() {
+            continue;
+        }
+        if 2 * n > covered.len() {
+            conditions.push('-');
+            conditions.push_str(&letters(false));
+        } else {
+            conditions.push_str(&letters(true));
+        }
+    }
+    conditions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ltl_engine::board::Board;
+
+    #[test]
+    fn arrangements_cover_every_neighbourhood() {
+        let classes = classes();
+        for count in 0..=8 {
+            for letter in 0..representatives(count).len() {
+                let class: Vec<usize> = (0..256)
+                    .filter(|&a| classes[a] == (count as u8, letter as u8))
+                    .collect();
+                assert!(!class.is_empty());
+                assert!(class
+                    .iter()
+                    .all(|&a| (a as u8).count_ones() == count as u32));
+            }
+        }
+        let letters: usize = (0..=8).map(|count| representatives(count).len()).sum();
+        assert_eq!(letters, 51);
+    }
+
+    #[test]
+    fn parse_and_format() {
+        let rule: IsotropicRule = "B2-a/S12".parse().unwrap();
+        assert_eq!(rule.to_string(), "B2-a/S12");
+        let rule: IsotropicRule = "b2ceikn/s21".parse().unwrap();
+        assert_eq!(rule.to_string(), "B2-a/S12");
+        let rule: IsotropicRule = "S23/B3".parse().unwrap();
+        assert_eq!(rule.to_string(), "B3/S23");
+        let rule: IsotropicRule = "B3aijnqry/S2-c3-e4a".parse().unwrap();
+        assert_eq!(rule.to_string(), "B3-cek/S2-c3-e4a");
+        assert_eq!("B/S".parse::<IsotropicRule>().unwrap().to_string(), "B/S");
+        assert_eq!(
+            "B2x/S23".parse::<IsotropicRule>(),
+            Err(IsotropicError::Letter(2, 'x'))
+        );
+        assert_eq!(
+            "B0c/S23".parse::<IsotropicRule>(),
+            Err(IsotropicError::Letter(0, 'c'))
+        );
+        assert_eq!(
+            "B9/S23".parse::<IsotropicRule>(),
+            Err(IsotropicError::Syntax)
+        );
+        assert_eq!("B3".parse::<IsotropicRule>(), Err(IsotropicError::Syntax));
+    }
+
+    #[test]
+    fn arrangements_of_letters() {
+        let rule: IsotropicRule = "B2a/S".parse().unwrap();
+        // A live edge neighbour next to a live corner neighbour.
+        let a = [0, 0, 0, 1, 0, 1, 0, 0];
+        assert_eq!(rule.next_state(0, &a), 1);
+        // Opposite edges are 2i.
+        let i = [0, 1, 0, 0, 0, 0, 1, 0];
+        assert_eq!(rule.next_state(0, &i), 0);
+        assert_eq!(rule.next_state(1, &a), 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn arrangement_of_too_many_neighbours() {
+        IsotropicRule::arrangement(&[1; 9]);
+    }
+
+    #[test]
+    fn totalistic_config() {
+        let config = Config::new(1, 0, 0, (2, 3), (3, 3), Neighbourhood::Moore);
+        let rule = IsotropicRule::from_config(&config).unwrap();
+        assert_eq!(rule.to_string(), "B3/S23");
+        let mut life = Board::new(16, config);
+        life.randomize(Some(3));
+        let mut board = Board::new(16, rule);
+        board.set_cells(life.cells.as_slice()).unwrap();
+        for _ in 0..8 {
+            life.update();
+            board.update();
+        }
+        assert_eq!(board.cells, life.cells);
+
+        let config = Config::new(1, 0, 1, (3, 4), (3, 3), Neighbourhood::Moore);
+        let rule = IsotropicRule::from_config(&config).unwrap();
+        assert_eq!(rule.to_string(), "B3/S23");
+        let config = Config::new(1, 3, 0, (2, 3), (3, 3), Neighbourhood::Moore);
+        assert_eq!(
+            IsotropicRule::from_config(&config),
+            Err(IsotropicError::Config)
+        );
+    }
+
+    #[test]
+    fn edges_are_dead() {
+        let rule: IsotropicRule = "B1e/S".parse().unwrap();
+        let mut board = Board::new(3, rule);
+        board.set_cell(0, 1, 1);
+        board.update();
+        // Cells outside the board pad the neighbourhood, keeping the corner cell's only live
+        // neighbour on an edge.
+        assert_eq!(board.get_cell(1, 1), 1);
+        assert_eq!(board.get_cell(0, 0), 1);
+        assert_eq!(board.get_cell(1, 0), 0);
+        assert_eq!(board.get_cell(0, 1), 0);
+    }
+}
@@ -1,3 +1,27 @@
+pub mod activity;
 pub mod board;
+pub mod cells;
+pub mod census;
 pub mod config;
+pub mod damage;
+pub mod explore;
+pub mod golly;
+pub mod hash;
+pub mod image;
+pub mod inspect;
+pub mod isotropic;
 pub mod neighbourhood;
+pub mod pattern;
+pub mod phase;
+pub mod presets;
+pub mod recorder;
+pub mod rule;
+pub mod sat;
+pub mod search;
+pub mod soup;
+pub mod stats;
+pub mod table;
+pub mod template;
+pub mod video;
+
+pub use rule::Rule;
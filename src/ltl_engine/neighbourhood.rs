@@ -72,6 +72,25 @@ impl Neighbourhood {
         };
         min(if mm == 1 { area } else { area - 1 } as u16, 255)
     }
+
+    /// Check whether the cell at an offset from a cell belongs to its neighbourhood.
+    ///
+    /// # Arguments
+    ///
+    /// * `rr` - The radius of the neighborhood.
+    /// * `mm` - Is the centre cell included in neighbourhood.
+    /// * `dx` - The offset along the x axis, within the radius.
+    /// * `dy` - The offset along the y axis, within the radius.
+    ///
+    pub fn contains(&self, rr: u8, mm: u8, dx: isize, dy: isize) -> bool {
+        if dx == 0 && dy == 0 {
+            return mm != 0;
+        }
+        match self {
+            Neighbourhood::Moore => true,
+            Neighbourhood::Neumann => dx.unsigned_abs() + dy.unsigned_abs() <= rr as usize,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,658 @@
+use crate::ltl_engine::board::Board;
+use crate::ltl_engine::cells::{Cell, Cells, Region};
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+/// Prefix of the code of a pattern whose behaviour is unknown, followed by its population.
+///
+pub const CODE_PREFIX: &str = "xc";
+
+/// Characters of the columns of a strip, the `n`th standing for bits `n`.
+///
+const WECHSLER: &[u8; 32] = b"0123456789abcdefghijklmnopqrstuv";
+
+/// Characters of the runs following `y`, the `n`th standing for `n + 4` empty columns.
+///
+const RUNS: &[u8; 36] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+
+/// Height of the strips of a pattern code.
+///
+const STRIP: usize = 5;
+
+/// Longest line of an RLE file.
+///
+const RLE_LINE: usize = 70;
+
+/// One of the 8 symmetries of the square.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Symmetry {
+    Identity,
+    /// Clockwise rotation by a quarter turn.
+    Rotate90,
+    Rotate180,
+    Rotate270,
+    /// Mirror reversing the x axis.
+    FlipX,
+    /// Mirror reversing the y axis.
+    FlipY,
+    /// Mirror along the main diagonal, swapping the axes.
+    Transpose,
+    /// Mirror along the anti-diagonal.
+    AntiTranspose,
+}
+
+impl Symmetry {
+    pub const ALL: [Symmetry; 8] = [
+        Symmetry::Identity,
+        Symmetry::Rotate90,
+        Symmetry::Rotate180,
+        Symmetry::Rotate270,
+        Symmetry::FlipX,
+        Symmetry::FlipY,
+        Symmetry::Transpose,
+        Symmetry::AntiTranspose,
+    ];
+
+    /// Returns the name of the symmetry, as parsed by `from_str`.
+    ///
+    pub fn name(&self) -> &'static str {
+        match self {
+            Symmetry::Identity => "identity",
+            Symmetry::Rotate90 => "rotate90",
+            Symmetry::Rotate180 => "rotate180",
+            Symmetry::Rotate270 => "rotate270",
+            Symmetry::FlipX => "flip_x",
+            Symmetry::FlipY => "flip_y",
+            Symmetry::Transpose => "transpose",
+            Symmetry::AntiTranspose => "anti_transpose",
+        }
+    }
+
+    /// Returns whether the symmetry swaps the width and height of a pattern.
+    ///
+    pub fn swaps_axes(&self) -> bool {
+        matches!(
+            self,
+            Symmetry::Rotate90
+                | Symmetry::Rotate270
+                | Symmetry::Transpose
+                | Symmetry::AntiTranspose
+        )
+    }
+
+    /// Maps the coordinates of a cell of a `width` by `height` pattern to its coordinates in
+    /// the transformed pattern.
+    ///
+    pub fn apply(&self, x: usize, y: usize, width: usize, height: usize) -> (usize, usize) {
+        match self {
+            Symmetry::Identity => (x, y),
+            Symmetry::Rotate90 => (height - 1 - y, x),
+            Symmetry::Rotate180 => (width - 1 - x, height - 1 - y),
+            Symmetry::Rotate270 => (y, width - 1 - x),
+            Symmetry::FlipX => (width - 1 - x, y),
+            Symmetry::FlipY => (x, height - 1 - y),
+            Symmetry::Transpose => (y, x),
+            Symmetry::AntiTranspose => (height - 1 - y, width - 1 - x),
+        }
+    }
+}
+
+impl FromStr for Symmetry {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.to_ascii_lowercase();
+        Symmetry::ALL
+            .into_iter()
+            .find(|symmetry| symmetry.name() == s)
+            .ok_or(())
+    }
+}
+
+/// Errors raised by decoding a pattern code.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub enum CodeError {
+    /// The code has no prefix followed by `_`.
+    Prefix,
+    Character(char),
+}
+
+impl fmt::Display for CodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CodeError::Prefix => write!(f, "Pattern code lacks a prefix"),
+            CodeError::Character(c) => write!(f, "Invalid character '{}' in pattern code", c),
+        }
+    }
+}
+
+impl Error for CodeError {}
+
+/// A rectangular pattern of cells, detached from any board. Cells are stored column by
+/// column, like the cells of a board.
+///
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Pattern {
+    width: usize,
+    height: usize,
+    cells: Vec<Cell>,
+}
+
+impl Pattern {
+    /// Create a pattern of dead cells.
+    ///
+    pub fn new(width: usize, height: usize) -> Self {
+        Pattern {
+            width,
+            height,
+            cells: vec![0; width * height],
+        }
+    }
+
+    /// Copies a region of a board.
+    ///
+    /// # Arguments
+    ///
+    /// * `cells` - The cells of the board.
+    /// * `region` - The copied region, clamped to the board.
+    ///
+    pub fn from_region(cells: &Cells, region: Region) -> Self {
+        let region = region.clamp(cells.len());
+        let mut pattern = Pattern::new(region.width, region.height);
+        for x in 0..region.width {
+            let column = &cells[region.x + x][region.y..region.y + region.height];
+            pattern.cells[x * region.height..(x + 1) * region.height].copy_from_slice(column);
+        }
+        pattern
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns all cells, column by column.
+    ///
+    pub fn as_slice(&self) -> &[Cell] {
+        &self.cells
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Cell {
+        self.cells[x * self.height + y]
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, state: Cell) {
+        self.cells[x * self.height + y] = state;
+    }
+
+    /// Returns the number of cells in a non-zero state.
+    ///
+    pub fn population(&self) -> u64 {
+        self.cells.iter().filter(|&&state| state != 0).count() as u64
+    }
+
+    /// Returns the smallest pattern holding every cell in a non-zero state.
+    ///
+    pub fn trim(&self) -> Pattern {
+        let live = |x: usize, y: usize| self.get(x, y) != 0;
+        let columns: Vec<usize> = (0..self.width)
+            .filter(|&x| (0..self.height).any(|y| live(x, y)))
+            .collect();
+        let rows: Vec<usize> = (0..self.height)
+            .filter(|&y| (0..self.width).any(|x| live(x, y)))
+            .collect();
+        match (columns.first(), columns.last(), rows.first(), rows.last()) {
+            (Some(&x0), Some(&x1), Some(&y0), Some(&y1)) => {
+                let mut pattern = Pattern::new(x1 - x0 + 1, y1 - y0 + 1);
+                for x in 0..pattern.width {
+                    for y in 0..pattern.height {
+                        pattern.set(x, y, self.get(x0 + x, y0 + y));
+                    }
+                }
+                pattern
+            }
+            _ => Pattern::new(0, 0),
+        }
+    }
+
+    /// Returns the pattern transformed by a symmetry of the square.
+    ///
+    pub fn transform(&self, symmetry: Symmetry) -> Pattern {
+        let mut pattern = if symmetry.swaps_axes() {
+            Pattern::new(self.height, self.width)
+        } else {
+            Pattern::new(self.width, self.height)
+        };
+        for x in 0..self.width {
+            for y in 0..self.height {
+                let (tx, ty) = symmetry.apply(x, y, self.width, self.height);
+                pattern.set(tx, ty, self.get(x, y));
+            }
+        }
+        pattern
+    }
+
+    /// Returns the representative of the pattern under translations and the symmetries of
+    /// the square: the trimmed orientation with the shortest code, the lexicographically
+    /// smallest one among those.
+    ///
+    pub fn canonical(&self) -> Pattern {
+        let trimmed = self.trim();
+        Symmetry::ALL
+            .iter()
+            .map(|&symmetry| {
+                let pattern = trimmed.transform(symmetry);
+                (pattern.layers(), pattern)
+            })
+            .min_by(|(a, _), (b, _)| a.len().cmp(&b.len()).then_with(|| a.cmp(b)))
+            .map(|(_, pattern)| pattern)
+            .unwrap()
+    }
+
+    /// Returns the code of the canonical pattern, stable across runs and equal for patterns
+    /// differing by a translation or a symmetry of the square.
+    ///
+    /// The code reads `xc{population}_{layers}` and is built like apgcodes: every state has a
+    /// layer of extended Wechsler notation, layers of states above 1 following the first one.
+    /// Two state patterns therefore get the apgcode body of their orientation.
+    ///
+    pub fn code(&self) -> String {
+        self.code_with_prefix(&format!("{}{}", CODE_PREFIX, self.population()))
+    }
+
+    /// Returns the code of the canonical pattern behind the given prefix.
+    ///
+    pub fn code_with_prefix(&self, prefix: &str) -> String {
+        format!("{}_{}", prefix, self.canonical().layers())
+    }
+
+    /// Recreates a pattern from its code. The pattern is the canonical orientation of the
+    /// encoded pattern, trimmed to its non-zero cells.
+    ///
+    /// # Arguments
+    ///
+    /// * `code` - A code returned by `code` or `code_with_prefix`, any prefix is accepted.
+    ///
+    pub fn from_code(code: &str) -> Result<Pattern, CodeError> {
+        let (_, layers) = code.split_once('_').ok_or(CodeError::Prefix)?;
+        let mut cells: Vec<(usize, usize, Cell)> = Vec::new();
+        for (layer, state) in layers.split('_').zip(1..=Cell::MAX) {
+            decode_layer(layer, state, &mut cells)?;
+        }
+        let width = cells.iter().map(|&(x, _, _)| x + 1).max().unwrap_or(0);
+        let height = cells.iter().map(|&(_, y, _)| y + 1).max().unwrap_or(0);
+        let mut pattern = Pattern::new(width, height);
+        for (x, y, state) in cells {
+            pattern.set(x, y, state);
+        }
+        Ok(pattern)
+    }
+
+    /// Writes the pattern in the run length encoded format of Golly. Two state patterns use
+    /// `b` and `o`, patterns with more states `.` and the letters `A` to `X` preceded by `p`
+    /// to `y`.
+    ///
+    /// # Arguments
+    ///
+    /// * `rule` - The rule written to the header.
+    ///
+    pub fn to_rle(&self, rule: &str) -> String {
+        let two_state = self.cells.iter().all(|&state| state <= 1);
+        let mut tokens = Vec::new();
+        let mut rows = 0;
+        for y in 0..self.height {
+            let mut runs: Vec<(usize, Cell)> = Vec::new();
+            for x in 0..self.width {
+                match runs.last_mut() {
+                    Some((count, state)) if *state == self.get(x, y) => *count += 1,
+                    _ => runs.push((1, self.get(x, y))),
+                }
+            }
+            if runs.last().is_some_and(|&(_, state)| state == 0) {
+                runs.pop();
+            }
+            if !runs.is_empty() {
+                if rows > 0 {
+                    tokens.push(rle_run(rows, "$"));
+                }
+                rows = 0;
+                tokens.extend(
+                    runs.into_iter()
+                        .map(|(count, state)| rle_run(count, &rle_state(state, two_state))),
+                );
+            }
+            rows += 1;
+        }
+        tokens.push("!".to_string());
+
+        let mut rle = format!("x = {}, y = {}, rule = {}\n", self.width, self.height, rule);
+        let mut line = 0;
+        for token in tokens {
+            if line > 0 && line + token.len() > RLE_LINE {
+                rle.push('\n');
+                line = 0;
+            }
+            line += token.len();
+            rle.push_str(&token);
+        }
+        rle.push('\n');
+        rle
+    }
+
+    /// Encodes every state from 1 up to the largest one as a layer of extended Wechsler
+    /// notation, layers separated by `_`.
+    ///
+    fn layers(&self) -> String {
+        let states = self.cells.iter().copied().max().unwrap_or(0).max(1);
+        (1..=states)
+            .map(|state| self.layer(state))
+            .collect::<Vec<_>>()
+            .join("_")
+    }
+
+    /// Encodes the cells in the given state, strips of 5 rows separated by `z`.
+    ///
+    fn layer(&self, state: Cell) -> String {
+        let mut strips: Vec<String> = (0..self.height)
+            .step_by(STRIP)
+            .map(|top| {
+                let columns: Vec<usize> = (0..self.width)
+                    .map(|x| {
+                        (top..(top + STRIP).min(self.height))
+                            .filter(|&y| self.get(x, y) == state)
+                            .map(|y| 1 << (y - top))
+                            .sum()
+                    })
+                    .collect();
+                let end = columns.iter().rposition(|&c| c != 0).map_or(0, |i| i + 1);
+                let mut strip = String::new();
+                let mut empty = 0;
+                for &column in &columns[..end] {
+                    if column == 0 {
+                        empty += 1;
+                        continue;
+                    }
+                    push_empty(&mut strip, empty);
+                    empty = 0;
+                    strip.push(WECHSLER[column] as char);
+                }
+                strip
+            })
+            .collect();
+        while strips.last().is_some_and(|strip| strip.is_empty()) {
+            strips.pop();
+        }
+        strips.join("z")
+    }
+
+    /// Writes the pattern to a board. Cells falling outside of the board are dropped.
+    ///
+    /// # Arguments
+    ///
+    /// * `board` - The board written to.
+    /// * `x` - The x coordinate of the top left corner of the pattern on the board.
+    /// * `y` - The y coordinate of the top left corner of the pattern on the board.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the pattern holds states the board doesn't support.
+    ///
+    pub fn place(&self, board: &mut Board, x: usize, y: usize) {
+        let size = board.cells.len();
+        for px in 0..self.width.min(size.saturating_sub(x)) {
+            for py in 0..self.height.min(size.saturating_sub(y)) {
+                board.set_cell(x + px, y + py, self.get(px, py));
+            }
+        }
+    }
+}
+
+/// Returns a run of RLE tags, the count being left out for single tags.
+///
+fn rle_run(count: usize, tag: &str) -> String {
+    if count == 1 {
+        tag.to_string()
+    } else {
+        format!("{}{}", count, tag)
+    }
+}
+
+/// Returns the RLE tag of a cell state.
+///
+fn rle_state(state: Cell, two_state: bool) -> String {
+    match (state, two_state) {
+        (0, true) => "b".to_string(),
+        (_, true) => "o".to_string(),
+        (0, false) => ".".to_string(),
+        _ => {
+            let index = state as usize - 1;
+            let letter = (b'A' + (index % 24) as u8) as char;
+            match index / 24 {
+                0 => letter.to_string(),
+                prefix => format!("{}{}", (b'o' + prefix as u8) as char, letter),
+            }
+        }
+    }
+}
+
+/// Appends a run of empty columns to a strip.
+///
+fn push_empty(strip: &mut String, mut count: usize) {
+    while count > 0 {
+        match count {
+            1 => strip.push('0'),
+            2 => strip.push('w'),
+            3 => strip.push('x'),
+            _ => {
+                let run = count.min(RUNS.len() + 3);
+                strip.push('y');
+                strip.push(RUNS[run - 4] as char);
+                count -= run;
+                continue;
+            }
+        }
+        break;
+    }
+}
+
+/// Decodes a layer of extended Wechsler notation.
+///
+/// # Arguments
+///
+/// * `layer` - The encoded layer.
+/// * `state` - The state of the cells of the layer.
+/// * `cells` - The decoded cells are appended to it.
+///
+fn decode_layer(
+    layer: &str,
+    state: Cell,
+    cells: &mut Vec<(usize, usize, Cell)>,
+) -> Result<(), CodeError> {
+    let mut x = 0;
+    let mut top = 0;
+    let mut chars = layer.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            'w' => x += 2,
+            'x' => x += 3,
+            'y' => {
+                let run = chars.next().ok_or(CodeError::Character(c))?;
+                let i = RUNS
+                    .iter()
+                    .position(|&r| r as char == run)
+                    .ok_or(CodeError::Character(run))?;
+                x += i + 4;
+            }
+            'z' => {
+                x = 0;
+                top += STRIP;
+            }
+            _ => {
+                let column = WECHSLER
+                    .iter()
+                    .position(|&w| w as char == c)
+                    .ok_or(CodeError::Character(c))?;
+                for bit in 0..STRIP {
+                    if column & (1 << bit) != 0 {
+                        cells.push((x, top + bit, state));
+                    }
+                }
+                x += 1;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ltl_engine::config::Config;
+    use crate::ltl_engine::neighbourhood::Neighbourhood;
+
+    #[test]
+    fn place_on_board() {
+        let mut pattern = Pattern::new(2, 3);
+        pattern.set(0, 0, 2);
+        pattern.set(1, 2, 1);
+        assert_eq!(pattern.as_slice(), &[2, 0, 0, 0, 0, 1]);
+
+        let config = Config::new(1, 3, 0, (2, 3), (3, 3), Neighbourhood::Moore);
+        let mut board = Board::new(4, config);
+        pattern.place(&mut board, 3, 1);
+        assert_eq!(board.get_cell(3, 1), 2);
+        assert_eq!(board.stats().population, 1);
+
+        let copy = Pattern::from_region(
+            &board.cells,
+            Region {
+                x: 3,
+                y: 1,
+                width: 2,
+                height: 3,
+            },
+        );
+        assert_eq!(copy.width(), 1);
+        assert_eq!(copy.as_slice(), &[2, 0, 0]);
+    }
+
+    fn pattern(cells: &[(usize, usize, Cell)]) -> Pattern {
+        let width = cells.iter().map(|c| c.0 + 1).max().unwrap();
+        let height = cells.iter().map(|c| c.1 + 1).max().unwrap();
+        let mut pattern = Pattern::new(width, height);
+        for &(x, y, state) in cells {
+            pattern.set(x, y, state);
+        }
+        pattern
+    }
+
+    #[test]
+    fn transform_symmetries() {
+        // 1 2
+        // 3 0
+        // 4 0
+        let p = pattern(&[(0, 0, 1), (1, 0, 2), (0, 1, 3), (0, 2, 4)]);
+        let rotated = p.transform(Symmetry::Rotate90);
+        assert_eq!((rotated.width(), rotated.height()), (3, 2));
+        assert_eq!(rotated.get(2, 0), 1);
+        assert_eq!(rotated.get(2, 1), 2);
+        assert_eq!(rotated.get(0, 0), 4);
+        for symmetry in Symmetry::ALL {
+            let once = p.transform(symmetry);
+            let back = Symmetry::ALL
+                .iter()
+                .map(|&inverse| once.transform(inverse))
+                .find(|q| *q == p);
+            assert!(back.is_some());
+        }
+        assert_eq!(
+            p.transform(Symmetry::Rotate90)
+                .transform(Symmetry::Rotate90),
+            p.transform(Symmetry::Rotate180)
+        );
+        assert_eq!(
+            p.transform(Symmetry::Transpose).transform(Symmetry::FlipY),
+            p.transform(Symmetry::Rotate270)
+        );
+    }
+
+    #[test]
+    fn life_apgcodes() {
+        let block = pattern(&[(3, 2, 1), (4, 2, 1), (3, 3, 1), (4, 3, 1)]);
+        assert_eq!(block.trim().width(), 2);
+        assert_eq!(block.code(), "xc4_33");
+        assert_eq!(block.code_with_prefix("xs4"), "xs4_33");
+
+        let blinker = pattern(&[(0, 1, 1), (1, 1, 1), (2, 1, 1)]);
+        assert_eq!(blinker.code(), "xc3_7");
+        assert_eq!(blinker.transform(Symmetry::Transpose).code(), "xc3_7");
+
+        let glider = pattern(&[(1, 0, 1), (2, 1, 1), (0, 2, 1), (1, 2, 1), (2, 2, 1)]);
+        let codes: Vec<String> = Symmetry::ALL
+            .iter()
+            .map(|&s| glider.transform(s).code())
+            .collect();
+        assert!(codes.iter().all(|code| *code == codes[0]));
+        assert_eq!(codes[0], "xc5_153");
+
+        let mut tall = Pattern::new(1, 60);
+        tall.set(0, 0, 1);
+        tall.set(0, 59, 1);
+        let mut wide = Pattern::new(60, 1);
+        wide.set(0, 0, 1);
+        wide.set(59, 0, 1);
+        assert_eq!(tall.canonical(), wide.canonical());
+        assert_eq!(wide.canonical().layers(), "1yzyf1");
+    }
+
+    #[test]
+    fn decode_codes() {
+        let p = pattern(&[(0, 0, 1), (1, 0, 2), (0, 1, 3), (0, 7, 4), (9, 6, 1)]);
+        let code = p.code();
+        assert!(code.starts_with("xc5_"));
+        let decoded = Pattern::from_code(&code).unwrap();
+        assert_eq!(decoded, p.canonical());
+        assert_eq!(decoded.code(), code);
+        assert_eq!(Pattern::from_code("xc0_").unwrap().width(), 0);
+
+        let mut long = Pattern::new(50, 1);
+        long.set(0, 0, 1);
+        long.set(49, 0, 1);
+        assert_eq!(Pattern::from_code(&long.code()).unwrap(), long);
+
+        assert_eq!(Pattern::from_code("33"), Err(CodeError::Prefix));
+        assert_eq!(Pattern::from_code("xs4_3#"), Err(CodeError::Character('#')));
+        assert_eq!(Pattern::from_code("xs4_3y"), Err(CodeError::Character('y')));
+    }
+
+    #[test]
+    fn rle() {
+        let glider = pattern(&[(1, 0, 1), (2, 1, 1), (0, 2, 1), (1, 2, 1), (2, 2, 1)]);
+        assert_eq!(
+            glider.to_rle("R1,C0,M0,S2..3,B3..3,NM"),
+            "x = 3, y = 3, rule = R1,C0,M0,S2..3,B3..3,NM\nbo$2bo$3o!\n"
+        );
+        let p = pattern(&[(0, 0, 2), (1, 0, 2), (2, 3, 1), (0, 4, 30)]);
+        assert_eq!(
+            p.to_rle("R1,C31,M0,S2..3,B3..3,NM").lines().nth(1),
+            Some("2B3$2.A$pF!")
+        );
+
+        let mut long = Pattern::new(100, 1);
+        for x in (0..100).step_by(2) {
+            long.set(x, 0, 1);
+        }
+        let rle = long.to_rle("R1,C0,M0,S2..3,B3..3,NM");
+        assert!(rle.lines().all(|line| line.len() <= RLE_LINE));
+        assert_eq!(
+            rle.lines().skip(1).collect::<String>(),
+            "ob".repeat(49) + "o!"
+        );
+    }
+}
@@ -0,0 +1,293 @@
+use crate::ltl_engine::config::Config;
+use crate::ltl_engine::explore::{ranges, soup_dynamics, ExploreOptions};
+use crate::ltl_engine::image::{Image, Rgba};
+use crate::ltl_engine::soup::{parallel_map, SoupOptions};
+use serde::Serialize;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::str::FromStr;
+
+const DENSITY: &str = "density";
+const ACTIVITY: &str = "activity";
+const EXTINCTION: &str = "extinction";
+
+/// Colours of the heatmap, from the lowest to the highest value.
+///
+pub(crate) const HEATMAP: [Rgba; 5] = [
+    [68, 1, 84, 255],
+    [59, 82, 139, 255],
+    [33, 145, 140, 255],
+    [94, 201, 98, 255],
+    [253, 231, 37, 255],
+];
+
+/// A summary metric of the soups of a phase diagram cell.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PhaseMetric {
+    Density,
+    Activity,
+    Extinction,
+}
+
+impl FromStr for PhaseMetric {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            DENSITY => Ok(PhaseMetric::Density),
+            ACTIVITY => Ok(PhaseMetric::Activity),
+            EXTINCTION => Ok(PhaseMetric::Extinction),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Summary of the soups run with one survival and birth range.
+///
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PhaseCell {
+    pub ss: (u16, u16),
+    pub bb: (u16, u16),
+    /// Mean density of the last generation.
+    pub density: f64,
+    /// Mean fraction of the board changing state 1 per generation, over the last quarter of
+    /// the runs.
+    pub activity: f64,
+    /// Mean generation at which the soups died out, counting surviving soups as
+    /// `max_generations`.
+    pub extinction_time: f64,
+    /// Number of soups which died out.
+    pub extinct: usize,
+}
+
+impl PhaseCell {
+    pub fn value(&self, metric: PhaseMetric) -> f64 {
+        match metric {
+            PhaseMetric::Density => self.density,
+            PhaseMetric::Activity => self.activity,
+            PhaseMetric::Extinction => self.extinction_time,
+        }
+    }
+}
+
+/// Metrics over a grid of survival and birth ranges of a rule. Rows follow the survival
+/// ranges and columns the birth ranges.
+///
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PhaseDiagram {
+    pub rule: String,
+    pub seeds: usize,
+    pub max_generations: usize,
+    pub ss: Vec<(u16, u16)>,
+    pub bb: Vec<(u16, u16)>,
+    /// The cells, row by row.
+    pub cells: Vec<PhaseCell>,
+}
+
+impl PhaseDiagram {
+    /// Writes the diagram as CSV with one line per cell.
+    ///
+    pub fn write_csv<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writeln!(
+            writer,
+            "ss_min,ss_max,bb_min,bb_max,density,activity,extinction_time,extinct"
+        )?;
+        for cell in &self.cells {
+            writeln!(
+                writer,
+                "{},{},{},{},{},{},{},{}",
+                cell.ss.0,
+                cell.ss.1,
+                cell.bb.0,
+                cell.bb.1,
+                cell.density,
+                cell.activity,
+                cell.extinction_time,
+                cell.extinct
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Saves the diagram as CSV.
+    ///
+    pub fn save_csv<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write_csv(&mut writer)?;
+        writer.flush()
+    }
+
+    /// Renders a metric as a heatmap. Birth ranges go from left to right and survival ranges
+    /// from bottom to top, values are scaled between their minimum and maximum.
+    ///
+    /// # Arguments
+    ///
+    /// * `metric` - The rendered metric.
+    /// * `scale` - The size in pixels of every cell.
+    ///
+    pub fn heatmap(&self, metric: PhaseMetric, scale: usize) -> Image {
+        let scale = scale.max(1);
+        let rows = self.ss.len();
+        let columns = self.bb.len();
+        let values: Vec<f64> = self.cells.iter().map(|c| c.value(metric)).collect();
+        let min = values.iter().cloned().fold(f64::INFINITY, f64::min);
+        let max = values.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+        let mut image = Image::new(columns * scale, rows * scale, HEATMAP[0]);
+        for row in 0..rows {
+            for column in 0..columns {
+                let value = values[row * columns + column];
+                let t = if max > min {
+                    (value - min) / (max - min)
+                } else {
+                    0.0
+                };
+                let color = heat(t);
+                let top = (rows - 1 - row) * scale;
+                for y in top..top + scale {
+                    for x in column * scale..(column + 1) * scale {
+                        image.set_pixel(x, y, color);
+                    }
+                }
+            }
+        }
+        image
+    }
+}
+
+/// Returns the heatmap colour of a value between 0 and 1.
+///
+pub(crate) fn heat(t: f64) -> Rgba {
+    let position = t.clamp(0.0, 1.0) * (HEATMAP.len() - 1) as f64;
+    let i = (position as usize).min(HEATMAP.len() - 2);
+    let t = position - i as f64;
+    let mut color = [0; 4];
+    for (c, (a, b)) in color
+        .iter_mut()
+        .zip(HEATMAP[i].iter().zip(HEATMAP[i + 1].iter()))
+    {
+        *c = (*a as f64 + (*b as f64 - *a as f64) * t).round() as u8;
+    }
+    color
+}
+
+/// Returns the survival or birth ranges of a phase diagram of the given rule, splitting its
+/// neighbourhood into `steps` parts.
+///
+pub fn phase_ranges(config: &Config, steps: u16) -> Vec<(u16, u16)> {
+    ranges(config.nn.area(config.rr, config.mm), steps)
+}
+
+/// Runs soups of a rule over a grid of survival and birth ranges, cells in parallel.
+///
+/// # Arguments
+///
+/// * `config` - The rule, its `ss` and `bb` are replaced by the ranges of the grid.
+/// * `ss` - The survival ranges, one per row.
+/// * `bb` - The birth ranges, one per column.
+/// * `options` - The soups of every cell, `threads` cells are run at a time.
+///
+pub fn phase_diagram(
+    config: &Config,
+    ss: &[(u16, u16)],
+    bb: &[(u16, u16)],
+    options: &SoupOptions,
+) -> PhaseDiagram {
+    let options = ExploreOptions {
+        soups: options.clone(),
+        census: false,
+        ..ExploreOptions::default()
+    };
+    let soups = &options.soups;
+    let area = (soups.board_size * soups.board_size).max(1) as f64;
+    let cells = parallel_map(ss.len() * bb.len(), soups.threads, |i| {
+        let mut config = config.clone();
+        config.ss = ss[i / bb.len()];
+        config.bb = bb[i % bb.len()];
+        let dynamics: Vec<_> = (0..soups.soups)
+            .map(|seed| soup_dynamics(&config, soups.first_seed + seed as u64, &options))
+            .collect();
+        let count = dynamics.len().max(1) as f64;
+        PhaseCell {
+            ss: config.ss,
+            bb: config.bb,
+            density: dynamics
+                .iter()
+                .map(|d| d.final_population as f64 / area)
+                .sum::<f64>()
+                / count,
+            activity: dynamics.iter().map(|d| d.activity).sum::<f64>() / count,
+            extinction_time: dynamics
+                .iter()
+                .map(|d| d.extinct_at.unwrap_or(soups.max_generations as u64) as f64)
+                .sum::<f64>()
+                / count,
+            extinct: dynamics.iter().filter(|d| d.extinct_at.is_some()).count(),
+        }
+    });
+    PhaseDiagram {
+        rule: config.to_string(),
+        seeds: soups.soups,
+        max_generations: soups.max_generations,
+        ss: ss.to_vec(),
+        bb: bb.to_vec(),
+        cells,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ltl_engine::neighbourhood::Neighbourhood;
+
+    #[test]
+    fn life_phase_diagram() {
+        let config = Config::new(1, 0, 0, (2, 3), (3, 3), Neighbourhood::Moore);
+        let options = SoupOptions {
+            soups: 2,
+            board_size: 24,
+            soup_size: 8,
+            max_generations: 100,
+            max_period: 8,
+            threads: Some(2),
+            ..SoupOptions::default()
+        };
+        let ss = [(2, 3), (9, 9)];
+        let bb = [(3, 3), (9, 9)];
+        let diagram = phase_diagram(&config, &ss, &bb, &options);
+        assert_eq!(diagram.cells.len(), 4);
+        assert_eq!(diagram.cells[1].bb, (9, 9));
+
+        let dying = &diagram.cells[3];
+        assert_eq!(dying.extinct, 2);
+        assert_eq!(dying.density, 0.0);
+        assert_eq!(dying.extinction_time, 1.0);
+        assert!(diagram.cells[0].extinction_time > 1.0);
+
+        let mut csv = Vec::new();
+        diagram.write_csv(&mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        assert_eq!(csv.lines().count(), 5);
+        assert!(csv.lines().nth(4).unwrap().starts_with("9,9,9,9,0,0,1,2"));
+
+        let image = diagram.heatmap(PhaseMetric::Extinction, 3);
+        assert_eq!((image.width, image.height), (6, 6));
+        assert_eq!(image.get_pixel(5, 0), HEATMAP[0]);
+        assert_eq!(image.get_pixel(0, 5), HEATMAP[4]);
+    }
+
+    #[test]
+    fn heat_colours() {
+        assert_eq!(heat(0.0), HEATMAP[0]);
+        assert_eq!(heat(1.0), HEATMAP[4]);
+        assert_eq!(heat(0.5), HEATMAP[2]);
+        assert_eq!(
+            phase_ranges(
+                &Config::new(1, 0, 0, (0, 0), (0, 0), Neighbourhood::Moore),
+                2
+            ),
+            vec![(0, 0), (0, 4), (0, 8), (4, 4), (4, 8), (8, 8)]
+        );
+    }
+}
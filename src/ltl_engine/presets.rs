@@ -0,0 +1,268 @@
+use crate::ltl_engine::config::Config;
+use crate::ltl_engine::neighbourhood::Neighbourhood;
+
+/// A well-known larger than life rule with metadata describing it.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct Preset {
+    pub name: &'static str,
+    pub config: Config,
+    pub source: &'static str,
+    pub description: &'static str,
+    /// Recommended size of the board.
+    pub board_size: u64,
+    /// Recommended density of live cells in random soups.
+    pub density: f64,
+}
+
+const MCELL: &str = "MCell rule library (Mirek Wojtowicz)";
+const EVANS: &str = "K. M. Evans, Larger than Life: Digital Creatures in a Family of Two-Dimensional Cellular Automata (2001)";
+
+/// The catalogue of built-in presets.
+pub const PRESETS: &[Preset] = &[
+    Preset {
+        name: "Bosco's Rule",
+        config: Config {
+            rr: 5,
+            cc: 0,
+            mm: 1,
+            ss: (34, 58),
+            bb: (34, 45),
+            nn: Neighbourhood::Moore,
+        },
+        source: EVANS,
+        description: "The classic larger than life rule, home of the bugs: gliders that leave soups behind.",
+        board_size: 200,
+        density: 0.5,
+    },
+    Preset {
+        name: "Bugsmovie",
+        config: Config {
+            rr: 10,
+            cc: 0,
+            mm: 1,
+            ss: (123, 212),
+            bb: (123, 170),
+            nn: Neighbourhood::Moore,
+        },
+        source: MCELL,
+        description: "Radius 10 relative of Bosco's Rule with large, slow bugs.",
+        board_size: 300,
+        density: 0.5,
+    },
+    Preset {
+        name: "Globe",
+        config: Config {
+            rr: 8,
+            cc: 0,
+            mm: 0,
+            ss: (163, 223),
+            bb: (74, 252),
+            nn: Neighbourhood::Moore,
+        },
+        source: MCELL,
+        description: "Blobs grow into round, globe-like shells.",
+        board_size: 250,
+        density: 0.4,
+    },
+    Preset {
+        name: "Majority",
+        config: Config {
+            rr: 4,
+            cc: 0,
+            mm: 1,
+            ss: (41, 81),
+            bb: (41, 81),
+            nn: Neighbourhood::Moore,
+        },
+        source: MCELL,
+        description: "Every cell follows the majority of its 9x9 neighbourhood; soups coarsen into smooth regions.",
+        board_size: 150,
+        density: 0.5,
+    },
+    Preset {
+        name: "Majorly",
+        config: Config {
+            rr: 7,
+            cc: 0,
+            mm: 1,
+            ss: (113, 225),
+            bb: (113, 225),
+            nn: Neighbourhood::Moore,
+        },
+        source: MCELL,
+        description: "Majority vote over a 15x15 neighbourhood with even smoother boundaries.",
+        board_size: 200,
+        density: 0.5,
+    },
+    Preset {
+        name: "Marine",
+        config: Config {
+            rr: 6,
+            cc: 0,
+            mm: 1,
+            ss: (51, 89),
+            bb: (51, 67),
+            nn: Neighbourhood::Moore,
+        },
+        source: MCELL,
+        description: "Radius 6 relative of Bosco's Rule whose bugs swim through drifting, sea-like soups.",
+        board_size: 250,
+        density: 0.5,
+    },
+    Preset {
+        name: "ModernArt",
+        config: Config {
+            rr: 10,
+            cc: 255,
+            mm: 1,
+            ss: (2, 3),
+            bb: (3, 3),
+            nn: Neighbourhood::Moore,
+        },
+        source: MCELL,
+        description: "Life thresholds over a radius 10 neighbourhood with long decay trails.",
+        board_size: 200,
+        density: 0.05,
+    },
+    Preset {
+        name: "Waffle",
+        config: Config {
+            rr: 7,
+            cc: 0,
+            mm: 1,
+            ss: (100, 200),
+            bb: (75, 170),
+            nn: Neighbourhood::Moore,
+        },
+        source: MCELL,
+        description: "Soups settle into waffle-like lattices of holes.",
+        board_size: 200,
+        density: 0.5,
+    },
+    Preset {
+        name: "Gnarl",
+        config: Config {
+            rr: 1,
+            cc: 0,
+            mm: 0,
+            ss: (1, 1),
+            bb: (1, 1),
+            nn: Neighbourhood::Moore,
+        },
+        source: MCELL,
+        description: "B1/S1: a single cell explodes into gnarled, tree-like growth.",
+        board_size: 100,
+        density: 0.01,
+    },
+    Preset {
+        name: "Conway's Life",
+        config: Config {
+            rr: 1,
+            cc: 0,
+            mm: 0,
+            ss: (2, 3),
+            bb: (3, 3),
+            nn: Neighbourhood::Moore,
+        },
+        source: "J. H. Conway (1970)",
+        description: "The Game of Life written as a radius 1 larger than life rule.",
+        board_size: 70,
+        density: 0.35,
+    },
+    Preset {
+        name: "Exploding",
+        config: Config {
+            rr: 1,
+            cc: 8,
+            mm: 0,
+            ss: (2, 3),
+            bb: (2, 3),
+            nn: Neighbourhood::Moore,
+        },
+        source: "configs/exploding.json",
+        description: "Multistate rule whose patterns expand with decaying trails.",
+        board_size: 70,
+        density: 0.1,
+    },
+];
+
+impl Preset {
+    /// Find a preset by name, ignoring case.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the preset.
+    ///
+    /// # Returns
+    ///
+    /// The preset with the given name, if any.
+    ///
+    pub fn find(name: &str) -> Option<&'static Preset> {
+        PRESETS.iter().find(|p| p.name.eq_ignore_ascii_case(name))
+    }
+}
+
+impl Config {
+    /// Returns the configuration of the built-in preset with the given name, ignoring case.
+    ///
+    pub fn preset(name: &str) -> Option<Config> {
+        Preset::find(name).map(|p| p.config.clone())
+    }
+
+    /// Returns the catalogue of built-in presets.
+    ///
+    pub fn presets() -> &'static [Preset] {
+        PRESETS
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn preset_by_name() {
+        let config = Config::preset("bosco's rule").unwrap();
+        assert_eq!(
+            config,
+            Config::new(5, 0, 1, (34, 58), (34, 45), Neighbourhood::Moore)
+        );
+    }
+
+    #[test]
+    fn preset_unknown() {
+        assert_eq!(Config::preset("Seeds of Doubt"), None);
+    }
+
+    #[test]
+    fn presets_have_unique_names() {
+        let mut names: Vec<&str> = Config::presets().iter().map(|p| p.name).collect();
+        names.sort();
+        names.dedup();
+        assert_eq!(names.len(), PRESETS.len());
+    }
+
+    #[test]
+    fn presets_are_valid() {
+        for preset in Config::presets() {
+            let config = &preset.config;
+            let area = config.nn.area(config.rr, config.mm);
+            assert!(
+                config.ss.0 <= config.ss.1 && config.ss.1 <= area,
+                "{}",
+                preset.name
+            );
+            assert!(
+                config.bb.0 <= config.bb.1 && config.bb.1 <= area,
+                "{}",
+                preset.name
+            );
+            assert!(
+                preset.density > 0.0 && preset.density < 1.0,
+                "{}",
+                preset.name
+            );
+        }
+    }
+}
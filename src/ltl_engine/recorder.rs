@@ -0,0 +1,411 @@
+use crate::ltl_engine::board::Board;
+use crate::ltl_engine::cells::Region;
+use crate::ltl_engine::image::{Image, Palette};
+use crate::ltl_engine::Rule;
+use std::cmp::max;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::str::FromStr;
+
+const GIF: &str = "gif";
+const APNG: &str = "apng";
+const PNG: &str = "png";
+
+/// Supported animation formats.
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AnimationFormat {
+    Gif,
+    Apng,
+}
+
+impl FromStr for AnimationFormat {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            GIF => Ok(AnimationFormat::Gif),
+            APNG | PNG => Ok(AnimationFormat::Apng),
+            _ => Err(()),
+        }
+    }
+}
+
+impl AnimationFormat {
+    /// Guesses the animation format from the extension of the given path.
+    ///
+    pub fn from_path(path: &Path) -> Option<Self> {
+        path.extension()?.to_str()?.parse().ok()
+    }
+}
+
+/// Options of an animated recording.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordOptions {
+    /// Number of generations to simulate.
+    pub generations: usize,
+    /// Only every `frame_skip`-th generation is stored as a frame.
+    pub frame_skip: usize,
+    /// The side of the square of pixels drawn for every cell.
+    pub scale: usize,
+    /// Time between frames in milliseconds.
+    pub delay_ms: u16,
+    /// Region of the board to record, the whole board when `None`.
+    pub region: Option<Region>,
+    /// Maximum size of the encoded animation in bytes. Frames are dropped evenly until the
+    /// animation fits, keeping the playback duration.
+    pub max_bytes: Option<usize>,
+}
+
+impl Default for RecordOptions {
+    fn default() -> Self {
+        RecordOptions {
+            generations: 100,
+            frame_skip: 1,
+            scale: 1,
+            delay_ms: 100,
+            region: None,
+            max_bytes: None,
+        }
+    }
+}
+
+/// Cell states of a recorded region, row by row.
+///
+pub(crate) type Frame = Vec<u8>;
+
+impl<R: Rule> Board<R> {
+    /// Run the simulation and record it as an animation.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - The destination of the encoded animation.
+    /// * `format` - The animation format.
+    /// * `palette` - The colours of the cell states.
+    /// * `options` - Recording options.
+    ///
+    /// # Returns
+    ///
+    /// The number of frames written.
+    ///
+    pub fn record<W: Write>(
+        &mut self,
+        mut writer: W,
+        format: AnimationFormat,
+        palette: &Palette,
+        options: &RecordOptions,
+    ) -> io::Result<usize> {
+        let region = options
+            .region
+            .unwrap_or(Region::full(self.cells.len()))
+            .clamp(self.cells.len());
+        if region.width == 0 || region.height == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Recorded region is empty",
+            ));
+        }
+
+        let frame_skip = max(options.frame_skip, 1);
+        let mut frames = vec![self.capture(&region)];
+        for generation in 1..=options.generations {
+            self.update();
+            if generation % frame_skip == 0 {
+                frames.push(self.capture(&region));
+            }
+        }
+
+        let scale = max(options.scale, 1);
+        let mut step = 1;
+        loop {
+            let selected: Vec<&Frame> = frames.iter().step_by(step).collect();
+            let delay_ms = options.delay_ms.saturating_mul(step as u16);
+            let encoded = match format {
+                AnimationFormat::Gif => encode_gif(&selected, &region, palette, scale, delay_ms)?,
+                AnimationFormat::Apng => encode_apng(&selected, &region, palette, scale, delay_ms)?,
+            };
+            match options.max_bytes {
+                Some(max_bytes) if encoded.len() > max_bytes => {
+                    if selected.len() == 1 {
+                        return Err(io::Error::new(
+                            io::ErrorKind::InvalidInput,
+                            "A single frame exceeds the maximum file size",
+                        ));
+                    }
+                    step *= 2;
+                }
+                _ => {
+                    writer.write_all(&encoded)?;
+                    return Ok(selected.len());
+                }
+            }
+        }
+    }
+
+    /// Run the simulation and save the animation to a file, choosing the format from the file
+    /// extension.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the file, ending with `.gif`, `.png` or `.apng`.
+    /// * `palette` - The colours of the cell states.
+    /// * `options` - Recording options.
+    ///
+    /// # Returns
+    ///
+    /// The number of frames written.
+    ///
+    pub fn save_recording<P: AsRef<Path>>(
+        &mut self,
+        path: P,
+        palette: &Palette,
+        options: &RecordOptions,
+    ) -> io::Result<usize> {
+        let path = path.as_ref();
+        let format = AnimationFormat::from_path(path).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Only .gif, .png and .apng files are supported",
+            )
+        })?;
+        let mut writer = BufWriter::new(File::create(path)?);
+        let frames = self.record(&mut writer, format, palette, options)?;
+        writer.flush()?;
+        Ok(frames)
+    }
+
+    /// Copy the cell states of the region, row by row.
+    ///
+    pub(crate) fn capture(&self, region: &Region) -> Frame {
+        let mut frame = Vec::with_capacity(region.width * region.height);
+        for y in region.y..region.y + region.height {
+            for x in region.x..region.x + region.width {
+                frame.push(self.cells[x][y]);
+            }
+        }
+        frame
+    }
+}
+
+/// Scale a frame of cell states up by `scale` in both directions.
+///
+pub(crate) fn scale_frame(frame: &Frame, region: &Region, scale: usize) -> Vec<u8> {
+    let mut scaled = Vec::with_capacity(frame.len() * scale * scale);
+    for row in frame.chunks_exact(region.width) {
+        let scaled_row: Vec<u8> = row
+            .iter()
+            .flat_map(|&s| std::iter::repeat_n(s, scale))
+            .collect();
+        for _ in 0..scale {
+            scaled.extend_from_slice(&scaled_row);
+        }
+    }
+    scaled
+}
+
+fn encode_gif(
+    frames: &[&Frame],
+    region: &Region,
+    palette: &Palette,
+    scale: usize,
+    delay_ms: u16,
+) -> io::Result<Vec<u8>> {
+    let width = u16::try_from(region.width * scale).map_err(|_| too_large())?;
+    let height = u16::try_from(region.height * scale).map_err(|_| too_large())?;
+    let colors: Vec<u8> = palette
+        .colors()
+        .iter()
+        .take(256)
+        .flat_map(|c| [c[0], c[1], c[2]])
+        .collect();
+    let last = (colors.len() / 3 - 1) as u8;
+
+    let mut buffer = Vec::new();
+    {
+        let mut encoder =
+            gif::Encoder::new(&mut buffer, width, height, &colors).map_err(io::Error::other)?;
+        encoder
+            .set_repeat(gif::Repeat::Infinite)
+            .map_err(io::Error::other)?;
+        for frame in frames {
+            let indices: Vec<u8> = scale_frame(frame, region, scale)
+                .into_iter()
+                .map(|s| s.min(last))
+                .collect();
+            let mut gif_frame = gif::Frame::from_indexed_pixels(width, height, indices, None);
+            gif_frame.delay = delay_ms / 10;
+            encoder.write_frame(&gif_frame).map_err(io::Error::other)?;
+        }
+    }
+    Ok(buffer)
+}
+
+fn encode_apng(
+    frames: &[&Frame],
+    region: &Region,
+    palette: &Palette,
+    scale: usize,
+    delay_ms: u16,
+) -> io::Result<Vec<u8>> {
+    let width = (region.width * scale) as u32;
+    let height = (region.height * scale) as u32;
+
+    let mut buffer = Vec::new();
+    {
+        let mut encoder = png::Encoder::new(&mut buffer, width, height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.set_animated(frames.len() as u32, 0)?;
+        encoder.set_frame_delay(delay_ms, 1000)?;
+        let mut writer = encoder.write_header()?;
+        for frame in frames {
+            let mut image = Image::new(width as usize, height as usize, palette.color(0));
+            for (i, &s) in scale_frame(frame, region, scale).iter().enumerate() {
+                if s != 0 {
+                    image.set_pixel(i % width as usize, i / width as usize, palette.color(s));
+                }
+            }
+            writer.write_image_data(&image.pixels)?;
+        }
+        writer.finish()?;
+    }
+    Ok(buffer)
+}
+
+fn too_large() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidInput,
+        "GIF frames are limited to 65535 pixels per side",
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ltl_engine::config::Config;
+    use crate::ltl_engine::image::{BLACK, WHITE};
+    use crate::ltl_engine::neighbourhood::Neighbourhood;
+
+    fn blinker_board() -> Board {
+        let config = Config::new(1, 0, 0, (2, 3), (3, 3), Neighbourhood::Moore);
+        let mut board = Board::new(5, config);
+        for x in 1..4 {
+            board.set_cell(x, 2, 1);
+        }
+        board
+    }
+
+    fn palette() -> Palette {
+        Palette::new(vec![WHITE, BLACK])
+    }
+
+    #[test]
+    fn format_from_path() {
+        assert_eq!(
+            AnimationFormat::from_path(Path::new("run.gif")),
+            Some(AnimationFormat::Gif)
+        );
+        assert_eq!(
+            AnimationFormat::from_path(Path::new("run.apng")),
+            Some(AnimationFormat::Apng)
+        );
+        assert_eq!(AnimationFormat::from_path(Path::new("run.mp4")), None);
+    }
+
+    #[test]
+    fn capture_region() {
+        let board = blinker_board();
+        let region = Region {
+            x: 1,
+            y: 1,
+            width: 3,
+            height: 2,
+        };
+        assert_eq!(board.capture(&region), vec![0, 0, 0, 1, 1, 1]);
+    }
+
+    #[test]
+    fn scale_frame_doubles() {
+        let region = Region {
+            x: 0,
+            y: 0,
+            width: 2,
+            height: 1,
+        };
+        assert_eq!(
+            scale_frame(&vec![0, 1], &region, 2),
+            vec![0, 0, 1, 1, 0, 0, 1, 1]
+        );
+    }
+
+    #[test]
+    fn record_gif() {
+        let mut board = blinker_board();
+        let mut buffer = Vec::new();
+        let options = RecordOptions {
+            generations: 4,
+            frame_skip: 2,
+            ..RecordOptions::default()
+        };
+        let frames = board
+            .record(&mut buffer, AnimationFormat::Gif, &palette(), &options)
+            .unwrap();
+        assert_eq!(frames, 3);
+        assert_eq!(&buffer[..6], b"GIF89a");
+    }
+
+    #[test]
+    fn record_apng() {
+        let mut board = blinker_board();
+        let mut buffer = Vec::new();
+        let options = RecordOptions {
+            generations: 2,
+            scale: 3,
+            ..RecordOptions::default()
+        };
+        let frames = board
+            .record(&mut buffer, AnimationFormat::Apng, &palette(), &options)
+            .unwrap();
+        assert_eq!(frames, 3);
+        assert_eq!(&buffer[..8], b"\x89PNG\r\n\x1a\n");
+        assert!(buffer.windows(4).any(|w| w == b"acTL"));
+    }
+
+    #[test]
+    fn record_max_bytes_drops_frames() {
+        let options = RecordOptions {
+            generations: 16,
+            scale: 4,
+            ..RecordOptions::default()
+        };
+        let mut full = Vec::new();
+        let all_frames = blinker_board()
+            .record(&mut full, AnimationFormat::Gif, &palette(), &options)
+            .unwrap();
+
+        let limited = RecordOptions {
+            max_bytes: Some(full.len() / 2),
+            ..options
+        };
+        let mut buffer = Vec::new();
+        let frames = blinker_board()
+            .record(&mut buffer, AnimationFormat::Gif, &palette(), &limited)
+            .unwrap();
+        assert!(frames < all_frames);
+        assert!(buffer.len() <= full.len() / 2);
+    }
+
+    #[test]
+    fn record_max_bytes_too_small() {
+        let options = RecordOptions {
+            generations: 2,
+            max_bytes: Some(10),
+            ..RecordOptions::default()
+        };
+        let result =
+            blinker_board().record(&mut Vec::new(), AnimationFormat::Gif, &palette(), &options);
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,121 @@
+use crate::ltl_engine::cells::Cell;
+
+/// The update rule of a board: a neighbourhood and a transition function. `Board` stores,
+/// updates, hashes and renders cells the same way whatever its rule, `Config` being the
+/// Larger than Life rule boards use by default.
+///
+pub trait Rule: Clone + Send + Sync {
+    /// Returns the number of cell states, at least 2. Cells hold states `0..states()`.
+    ///
+    fn states(&self) -> Cell;
+
+    /// Returns the largest offset of the neighbourhood along either axis.
+    ///
+    fn radius(&self) -> usize;
+
+    /// Returns whether the cell at an offset from a cell belongs to its neighbourhood. Offsets
+    /// range over `-radius..=radius` along both axes, `(0, 0)` being the cell itself.
+    ///
+    fn contains(&self, dx: isize, dy: isize) -> bool;
+
+    /// Returns the next state of a cell.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - The current state of the cell.
+    /// * `neighbours` - The states of its neighbourhood, ordered by `dx` then `dy`. The board
+    ///   doesn't wrap, so cells near the edges get fewer neighbours unless the rule is
+    ///   positional.
+    ///
+    fn next_state(&self, state: Cell, neighbours: &[Cell]) -> Cell;
+
+    /// Returns whether `next_state` depends on where neighbours are rather than on how many
+    /// there are. Neighbourhoods of positional rules always hold every offset, cells outside
+    /// the board being dead.
+    ///
+    fn positional(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ltl_engine::board::Board;
+    use crate::ltl_engine::config::Config;
+    use crate::ltl_engine::neighbourhood::Neighbourhood;
+
+    /// Cyclic cellular automaton: a cell moves on to the next state when enough of its von
+    /// Neumann neighbours are in that state.
+    ///
+    #[derive(Debug, Clone, PartialEq)]
+    struct Cyclic {
+        states: Cell,
+        threshold: usize,
+    }
+
+    impl Rule for Cyclic {
+        fn states(&self) -> Cell {
+            self.states
+        }
+
+        fn radius(&self) -> usize {
+            1
+        }
+
+        fn contains(&self, dx: isize, dy: isize) -> bool {
+            dx.abs() + dy.abs() == 1
+        }
+
+        fn next_state(&self, state: Cell, neighbours: &[Cell]) -> Cell {
+            let next = (state + 1) % self.states;
+            if neighbours.iter().filter(|&&s| s == next).count() >= self.threshold {
+                next
+            } else {
+                state
+            }
+        }
+    }
+
+    #[test]
+    fn cyclic_board() {
+        let rule = Cyclic {
+            states: 3,
+            threshold: 1,
+        };
+        let mut board = Board::new(4, rule.clone());
+        board.set_cell(1, 1, 1);
+        board.set_cell(2, 1, 2);
+        board.update();
+        assert_eq!(board.get_cell(0, 1), 1);
+        assert_eq!(board.get_cell(1, 1), 2);
+        assert_eq!(board.get_cell(2, 1), 0);
+        assert_eq!(board.get_cell(3, 3), 0);
+        assert_eq!(board.generation(), 1);
+        assert_eq!(board.stats().counts, vec![12, 3, 1]);
+        assert_eq!(board.config(), &rule);
+
+        let mut twin = board.clone();
+        twin.update();
+        board.update();
+        assert_eq!(board.hash(), twin.hash());
+    }
+
+    #[test]
+    fn config_neighbourhoods() {
+        for (nn, mm) in [
+            (Neighbourhood::Moore, 0),
+            (Neighbourhood::Moore, 1),
+            (Neighbourhood::Neumann, 0),
+            (Neighbourhood::Neumann, 1),
+        ] {
+            let config = Config::new(3, 0, mm, (2, 3), (3, 3), nn.clone());
+            let r = config.radius() as isize;
+            let area = (-r..=r)
+                .flat_map(|dx| (-r..=r).map(move |dy| (dx, dy)))
+                .filter(|&(dx, dy)| config.contains(dx, dy))
+                .count();
+            assert_eq!(area as u16, nn.area(3, mm));
+        }
+    }
+}
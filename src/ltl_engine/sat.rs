@@ -0,0 +1,576 @@
+use std::ops::Not;
+
+/// Growth of the activity increment after every conflict.
+///
+const ACTIVITY_DECAY: f64 = 1.0 / 0.95;
+
+/// Activities are rescaled once one of them exceeds this value.
+///
+const ACTIVITY_LIMIT: f64 = 1e100;
+
+/// Conflicts between restarts, multiplied by the terms of the Luby sequence.
+///
+const RESTART_INTERVAL: u64 = 100;
+
+/// A boolean variable of a solver.
+///
+pub type Var = usize;
+
+/// A variable or its negation.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Lit(usize);
+
+impl Lit {
+    pub fn new(var: Var, positive: bool) -> Self {
+        Lit(2 * var + !positive as usize)
+    }
+
+    pub fn var(self) -> Var {
+        self.0 / 2
+    }
+
+    pub fn is_positive(self) -> bool {
+        self.0.is_multiple_of(2)
+    }
+
+    fn index(self) -> usize {
+        self.0
+    }
+}
+
+impl Not for Lit {
+    type Output = Lit;
+
+    fn not(self) -> Lit {
+        Lit(self.0 ^ 1)
+    }
+}
+
+/// Outcome of a satisfiability check.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub enum SatResult {
+    /// The value of every variable in a satisfying assignment.
+    Sat(Vec<bool>),
+    Unsat,
+    /// The conflict budget ran out.
+    Unknown,
+}
+
+/// A conflict driven clause learning SAT solver, with two watched literals, first UIP
+/// learning, VSIDS decisions, phase saving and Luby restarts.
+///
+/// Clauses can be added between calls to `solve`, which makes it possible to enumerate
+/// solutions by blocking the previous ones.
+///
+#[derive(Debug, Clone, Default)]
+pub struct Solver {
+    clauses: Vec<Vec<Lit>>,
+    watches: Vec<Vec<usize>>,
+    values: Vec<Option<bool>>,
+    levels: Vec<usize>,
+    reasons: Vec<Option<usize>>,
+    trail: Vec<Lit>,
+    trail_limits: Vec<usize>,
+    propagated: usize,
+    activity: Vec<f64>,
+    increment: f64,
+    heap: VarHeap,
+    phases: Vec<bool>,
+    seen: Vec<bool>,
+    /// False once the clauses are known to be unsatisfiable.
+    consistent: bool,
+    conflicts: u64,
+}
+
+impl Solver {
+    pub fn new() -> Self {
+        Solver {
+            increment: 1.0,
+            consistent: true,
+            ..Solver::default()
+        }
+    }
+
+    /// Adds a new variable.
+    ///
+    pub fn new_var(&mut self) -> Var {
+        let var = self.values.len();
+        self.values.push(None);
+        self.levels.push(0);
+        self.reasons.push(None);
+        self.activity.push(0.0);
+        self.phases.push(false);
+        self.seen.push(false);
+        self.watches.push(Vec::new());
+        self.watches.push(Vec::new());
+        self.heap.insert(var, &self.activity);
+        var
+    }
+
+    /// Adds a clause, the disjunction of the given literals.
+    ///
+    /// # Returns
+    ///
+    /// False if the clauses became unsatisfiable.
+    ///
+    pub fn add_clause(&mut self, literals: &[Lit]) -> bool {
+        if !self.consistent {
+            return false;
+        }
+        self.backtrack(0);
+        let mut clause: Vec<Lit> = Vec::with_capacity(literals.len());
+        for &lit in literals {
+            match self.value(lit) {
+                Some(true) => return true,
+                Some(false) => {}
+                None => {
+                    if clause.contains(&!lit) {
+                        return true;
+                    }
+                    if !clause.contains(&lit) {
+                        clause.push(lit);
+                    }
+                }
+            }
+        }
+        match clause.len() {
+            0 => self.consistent = false,
+            1 => {
+                self.assign(clause[0], None);
+                self.consistent = self.propagate().is_none();
+            }
+            _ => {
+                self.attach(clause);
+            }
+        }
+        self.consistent
+    }
+
+    /// Looks for an assignment satisfying every clause.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_conflicts` - Gives up with `SatResult::Unknown` after that many conflicts.
+    ///
+    pub fn solve(&mut self, max_conflicts: Option<u64>) -> SatResult {
+        if !self.consistent {
+            return SatResult::Unsat;
+        }
+        let budget = max_conflicts.map(|c| self.conflicts + c);
+        let mut restart = 1;
+        let mut restart_conflicts = 0;
+        loop {
+            if let Some(conflict) = self.propagate() {
+                self.conflicts += 1;
+                restart_conflicts += 1;
+                if self.trail_limits.is_empty() {
+                    self.consistent = false;
+                    return SatResult::Unsat;
+                }
+                let (learnt, level) = self.analyze(conflict);
+                self.backtrack(level);
+                if learnt.len() == 1 {
+                    self.assign(learnt[0], None);
+                } else {
+                    let asserting = learnt[0];
+                    let clause = self.attach(learnt);
+                    self.assign(asserting, Some(clause));
+                }
+                self.increment *= ACTIVITY_DECAY;
+                if budget.is_some_and(|b| self.conflicts >= b) {
+                    self.backtrack(0);
+                    return SatResult::Unknown;
+                }
+            } else if restart_conflicts >= RESTART_INTERVAL * luby(restart) {
+                restart += 1;
+                restart_conflicts = 0;
+                self.backtrack(0);
+            } else {
+                match self.pick_branch() {
+                    Some(var) => {
+                        self.trail_limits.push(self.trail.len());
+                        self.assign(Lit::new(var, self.phases[var]), None);
+                    }
+                    None => {
+                        let model = self.values.iter().map(|v| v.unwrap()).collect();
+                        self.backtrack(0);
+                        return SatResult::Sat(model);
+                    }
+                }
+            }
+        }
+    }
+
+    fn value(&self, lit: Lit) -> Option<bool> {
+        self.values[lit.var()].map(|v| v == lit.is_positive())
+    }
+
+    fn level(&self) -> usize {
+        self.trail_limits.len()
+    }
+
+    fn attach(&mut self, clause: Vec<Lit>) -> usize {
+        let index = self.clauses.len();
+        self.watches[clause[0].index()].push(index);
+        self.watches[clause[1].index()].push(index);
+        self.clauses.push(clause);
+        index
+    }
+
+    fn assign(&mut self, lit: Lit, reason: Option<usize>) {
+        let var = lit.var();
+        self.values[var] = Some(lit.is_positive());
+        self.levels[var] = self.level();
+        self.reasons[var] = reason;
+        self.trail.push(lit);
+    }
+
+    /// Propagates the assignments of the trail.
+    ///
+    /// # Returns
+    ///
+    /// The index of a falsified clause, if any.
+    ///
+    fn propagate(&mut self) -> Option<usize> {
+        while self.propagated < self.trail.len() {
+            let falsified = !self.trail[self.propagated];
+            self.propagated += 1;
+            let mut watchers = std::mem::take(&mut self.watches[falsified.index()]);
+            let mut kept = 0;
+            let mut conflict = None;
+            let mut i = 0;
+            while i < watchers.len() {
+                let index = watchers[i];
+                i += 1;
+                let clause = &mut self.clauses[index];
+                if clause[0] == falsified {
+                    clause.swap(0, 1);
+                }
+                let first = clause[0];
+                if self.values[first.var()] == Some(first.is_positive()) {
+                    watchers[kept] = index;
+                    kept += 1;
+                    continue;
+                }
+                let replacement = (2..clause.len()).find(|&k| {
+                    let lit = clause[k];
+                    self.values[lit.var()] != Some(!lit.is_positive())
+                });
+                if let Some(k) = replacement {
+                    clause.swap(1, k);
+                    let watched = clause[1];
+                    self.watches[watched.index()].push(index);
+                    continue;
+                }
+                watchers[kept] = index;
+                kept += 1;
+                if self.values[first.var()].is_some() {
+                    conflict = Some(index);
+                    while i < watchers.len() {
+                        watchers[kept] = watchers[i];
+                        kept += 1;
+                        i += 1;
+                    }
+                } else {
+                    self.assign(first, Some(index));
+                }
+            }
+            watchers.truncate(kept);
+            self.watches[falsified.index()] = watchers;
+            if conflict.is_some() {
+                return conflict;
+            }
+        }
+        None
+    }
+
+    /// Derives the first UIP clause of a conflict.
+    ///
+    /// # Returns
+    ///
+    /// The learnt clause, its asserting literal first and a literal of the backtrack level
+    /// second, and the backtrack level.
+    ///
+    fn analyze(&mut self, conflict: usize) -> (Vec<Lit>, usize) {
+        let level = self.level();
+        let mut learnt = vec![Lit(0)];
+        let mut pending = 0;
+        let mut clause = conflict;
+        let mut skip_first = false;
+        let mut index = self.trail.len();
+        loop {
+            for k in skip_first as usize..self.clauses[clause].len() {
+                let lit = self.clauses[clause][k];
+                let var = lit.var();
+                if self.seen[var] || self.levels[var] == 0 {
+                    continue;
+                }
+                self.seen[var] = true;
+                self.bump(var);
+                if self.levels[var] == level {
+                    pending += 1;
+                } else {
+                    learnt.push(lit);
+                }
+            }
+            loop {
+                index -= 1;
+                if self.seen[self.trail[index].var()] {
+                    break;
+                }
+            }
+            let lit = self.trail[index];
+            self.seen[lit.var()] = false;
+            pending -= 1;
+            if pending == 0 {
+                learnt[0] = !lit;
+                break;
+            }
+            clause = self.reasons[lit.var()].unwrap();
+            skip_first = true;
+        }
+        for lit in &learnt[1..] {
+            self.seen[lit.var()] = false;
+        }
+        let mut backtrack = 0;
+        for k in 1..learnt.len() {
+            let level = self.levels[learnt[k].var()];
+            if level > backtrack {
+                backtrack = level;
+                learnt.swap(1, k);
+            }
+        }
+        (learnt, backtrack)
+    }
+
+    fn bump(&mut self, var: Var) {
+        self.activity[var] += self.increment;
+        if self.activity[var] > ACTIVITY_LIMIT {
+            for activity in self.activity.iter_mut() {
+                *activity /= ACTIVITY_LIMIT;
+            }
+            self.increment /= ACTIVITY_LIMIT;
+        }
+        self.heap.increase(var, &self.activity);
+    }
+
+    fn backtrack(&mut self, level: usize) {
+        if self.level() <= level {
+            return;
+        }
+        let limit = self.trail_limits[level];
+        for lit in self.trail.drain(limit..) {
+            let var = lit.var();
+            self.values[var] = None;
+            self.reasons[var] = None;
+            self.phases[var] = lit.is_positive();
+            self.heap.insert(var, &self.activity);
+        }
+        self.trail_limits.truncate(level);
+        self.propagated = self.trail.len();
+    }
+
+    fn pick_branch(&mut self) -> Option<Var> {
+        while let Some(var) = self.heap.pop(&self.activity) {
+            if self.values[var].is_none() {
+                return Some(var);
+            }
+        }
+        None
+    }
+}
+
+/// Returns the `i`th term of the Luby sequence 1, 1, 2, 1, 1, 2, 4, ... starting from 1.
+///
+fn luby(i: u64) -> u64 {
+    let mut i = i;
+    loop {
+        let mut k = 1;
+        while (1 << k) - 1 < i {
+            k += 1;
+        }
+        if i == (1 << k) - 1 {
+            return 1 << (k - 1);
+        }
+        i -= (1 << (k - 1)) - 1;
+    }
+}
+
+/// A binary max-heap of variables ordered by activity.
+///
+#[derive(Debug, Clone, Default)]
+struct VarHeap {
+    heap: Vec<Var>,
+    /// Position of every variable in the heap.
+    positions: Vec<Option<usize>>,
+}
+
+impl VarHeap {
+    fn insert(&mut self, var: Var, activity: &[f64]) {
+        if self.positions.len() <= var {
+            self.positions.resize(var + 1, None);
+        }
+        if self.positions[var].is_some() {
+            return;
+        }
+        self.positions[var] = Some(self.heap.len());
+        self.heap.push(var);
+        self.sift_up(self.heap.len() - 1, activity);
+    }
+
+    fn increase(&mut self, var: Var, activity: &[f64]) {
+        if let Some(position) = self.positions.get(var).copied().flatten() {
+            self.sift_up(position, activity);
+        }
+    }
+
+    fn pop(&mut self, activity: &[f64]) -> Option<Var> {
+        let top = *self.heap.first()?;
+        let last = self.heap.pop().unwrap();
+        self.positions[top] = None;
+        if !self.heap.is_empty() {
+            self.heap[0] = last;
+            self.positions[last] = Some(0);
+            self.sift_down(0, activity);
+        }
+        Some(top)
+    }
+
+    fn sift_up(&mut self, mut position: usize, activity: &[f64]) {
+        let var = self.heap[position];
+        while position > 0 {
+            let parent = (position - 1) / 2;
+            if activity[self.heap[parent]] >= activity[var] {
+                break;
+            }
+            self.heap[position] = self.heap[parent];
+            self.positions[self.heap[position]] = Some(position);
+            position = parent;
+        }
+        self.heap[position] = var;
+        self.positions[var] = Some(position);
+    }
+
+    fn sift_down(&mut self, mut position: usize, activity: &[f64]) {
+        let var = self.heap[position];
+        loop {
+            let left = 2 * position + 1;
+            if left >= self.heap.len() {
+                break;
+            }
+            let right = left + 1;
+            let child = if right < self.heap.len()
+                && activity[self.heap[right]] > activity[self.heap[left]]
+            {
+                right
+            } else {
+                left
+            };
+            if activity[self.heap[child]] <= activity[var] {
+                break;
+            }
+            self.heap[position] = self.heap[child];
+            self.positions[self.heap[position]] = Some(position);
+            position = child;
+        }
+        self.heap[position] = var;
+        self.positions[var] = Some(position);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+    use rand_pcg::Pcg32;
+
+    fn satisfies(clauses: &[Vec<Lit>], model: &[bool]) -> bool {
+        clauses
+            .iter()
+            .all(|c| c.iter().any(|l| model[l.var()] == l.is_positive()))
+    }
+
+    #[test]
+    fn luby_sequence() {
+        let terms: Vec<u64> = (1..=15).map(luby).collect();
+        assert_eq!(terms, vec![1, 1, 2, 1, 1, 2, 4, 1, 1, 2, 1, 1, 2, 4, 8]);
+    }
+
+    #[test]
+    fn pigeonhole_is_unsat() {
+        // 5 pigeons in 4 holes.
+        let mut solver = Solver::new();
+        let p: Vec<Vec<Var>> = (0..5)
+            .map(|_| (0..4).map(|_| solver.new_var()).collect())
+            .collect();
+        for pigeon in &p {
+            let clause: Vec<Lit> = pigeon.iter().map(|&v| Lit::new(v, true)).collect();
+            solver.add_clause(&clause);
+        }
+        for hole in 0..4 {
+            for (a, first) in p.iter().enumerate() {
+                for second in &p[a + 1..] {
+                    solver
+                        .add_clause(&[Lit::new(first[hole], false), Lit::new(second[hole], false)]);
+                }
+            }
+        }
+        assert_eq!(solver.solve(None), SatResult::Unsat);
+    }
+
+    #[test]
+    fn random_3sat_matches_brute_force() {
+        let mut rng = Pcg32::seed_from_u64(7);
+        for _ in 0..200 {
+            let vars = 10;
+            let mut solver = Solver::new();
+            (0..vars).for_each(|_| {
+                solver.new_var();
+            });
+            let clauses: Vec<Vec<Lit>> = (0..rng.gen_range(20..60))
+                .map(|_| {
+                    (0..3)
+                        .map(|_| Lit::new(rng.gen_range(0..vars), rng.gen_bool(0.5)))
+                        .collect()
+                })
+                .collect();
+            for clause in &clauses {
+                solver.add_clause(clause);
+            }
+            let expected = (0..1u32 << vars).any(|bits| {
+                let model: Vec<bool> = (0..vars).map(|v| bits & (1 << v) != 0).collect();
+                satisfies(&clauses, &model)
+            });
+            match solver.solve(None) {
+                SatResult::Sat(model) => {
+                    assert!(expected);
+                    assert!(satisfies(&clauses, &model));
+                }
+                SatResult::Unsat => assert!(!expected),
+                SatResult::Unknown => unreachable!(),
+            }
+        }
+    }
+
+    #[test]
+    fn enumerate_by_blocking() {
+        let mut solver = Solver::new();
+        let a = solver.new_var();
+        let b = solver.new_var();
+        solver.add_clause(&[Lit::new(a, true), Lit::new(b, true)]);
+        let mut models = Vec::new();
+        while let SatResult::Sat(model) = solver.solve(None) {
+            let blocking: Vec<Lit> = model
+                .iter()
+                .enumerate()
+                .map(|(v, &value)| Lit::new(v, !value))
+                .collect();
+            models.push(model);
+            solver.add_clause(&blocking);
+        }
+        assert_eq!(models.len(), 3);
+        assert!(!solver.add_clause(&[Lit::new(a, true)]));
+    }
+}
@@ -0,0 +1,828 @@
+use crate::ltl_engine::board::Board;
+use crate::ltl_engine::cells::Region;
+use crate::ltl_engine::config::Config;
+use crate::ltl_engine::neighbourhood::Neighbourhood;
+use crate::ltl_engine::pattern::{Pattern, Symmetry};
+use crate::ltl_engine::sat::{Lit, SatResult, Solver};
+use std::error::Error;
+use std::fmt;
+
+/// Options of an oscillator search.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchOptions {
+    /// Size of the box every phase of the oscillators fits in.
+    pub width: usize,
+    pub height: usize,
+    /// The exact period of the oscillators, 1 for still lifes.
+    pub period: usize,
+    /// The search stops after finding this many distinct oscillators.
+    pub max_solutions: usize,
+    /// Conflicts the solver may spend looking for every oscillator.
+    pub max_conflicts: Option<u64>,
+}
+
+impl Default for SearchOptions {
+    fn default() -> Self {
+        SearchOptions {
+            width: 8,
+            height: 8,
+            period: 1,
+            max_solutions: 1,
+            max_conflicts: None,
+        }
+    }
+}
+
+/// Errors raised by searches that can't be encoded.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub enum SearchError {
+    EmptyBox,
+    Period,
+    /// Cells without live neighbours are born, no finite pattern is stable.
+    Birth,
+}
+
+impl fmt::Display for SearchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SearchError::EmptyBox => write!(f, "Search box must not be empty"),
+            SearchError::Period => write!(f, "Period must be at least 1"),
+            SearchError::Birth => write!(
+                f,
+                "Rules with births at 0 live neighbours have no finite oscillators"
+            ),
+        }
+    }
+}
+
+impl Error for SearchError {}
+
+/// An oscillator found by a search.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct Oscillator {
+    pub period: usize,
+    /// Every phase, in the order they evolve, cropped to the region covered by all phases.
+    pub phases: Vec<Pattern>,
+    /// Code of the oscillator independent of position, phase and orientation, `xs` codes for
+    /// still lifes and `xp` codes for oscillators.
+    pub code: String,
+}
+
+impl Oscillator {
+    /// Returns the number of cells in a non-zero state of the first phase.
+    ///
+    pub fn population(&self) -> u64 {
+        self.phases[0].population()
+    }
+
+    /// Writes the first phase in RLE format.
+    ///
+    pub fn to_rle(&self, config: &Config) -> String {
+        self.phases[0].trim().to_rle(&config.to_string())
+    }
+
+    /// Creates a board holding the first phase.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The rule the oscillator was found for.
+    /// * `padding` - Empty space around the oscillator.
+    ///
+    pub fn to_board(&self, config: &Config, padding: usize) -> Board {
+        let phase = &self.phases[0];
+        let size = phase.width().max(phase.height()) + 2 * padding;
+        let mut board = Board::new(size as u64, config.clone());
+        phase.place(&mut board, padding, padding);
+        board
+    }
+}
+
+/// Oscillators found by a search.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchReport {
+    pub oscillators: Vec<Oscillator>,
+    /// True when the solver proved there are no other oscillators in the box.
+    pub exhausted: bool,
+}
+
+/// Encodes the evolution of a box of cells as clauses. Every cell of every generation has
+/// one variable per state, exactly one of them being true, the generation after the last one
+/// being the first one. Cells around the box are dead.
+///
+struct Encoder<'a> {
+    config: &'a Config,
+    solver: Solver,
+    truth: Lit,
+    width: usize,
+    height: usize,
+    generations: usize,
+    states: usize,
+    cells: Vec<Lit>,
+    offsets: Vec<(isize, isize)>,
+}
+
+impl<'a> Encoder<'a> {
+    fn new(config: &'a Config, width: usize, height: usize, generations: usize) -> Self {
+        let mut solver = Solver::new();
+        let truth = Lit::new(solver.new_var(), true);
+        solver.add_clause(&[truth]);
+        let states = config.states() as usize;
+        let count = generations * width * height * states;
+        let cells = (0..count)
+            .map(|_| Lit::new(solver.new_var(), true))
+            .collect();
+        Encoder {
+            config,
+            solver,
+            truth,
+            width,
+            height,
+            generations,
+            states,
+            cells,
+            offsets: offsets(config),
+        }
+    }
+
+    /// Returns the literal of a cell of a generation being in the given state.
+    ///
+    fn state(&self, t: usize, x: usize, y: usize, state: usize) -> Lit {
+        let t = t % self.generations;
+        self.cells[((t * self.width + x) * self.height + y) * self.states + state]
+    }
+
+    /// Returns the literal of a cell of a generation being live, false out of the box.
+    ///
+    fn live(&self, t: usize, x: isize, y: isize) -> Lit {
+        if x < 0 || y < 0 || x as usize >= self.width || y as usize >= self.height {
+            !self.truth
+        } else {
+            !self.state(t, x as usize, y as usize, 0)
+        }
+    }
+
+    fn and(&mut self, a: Lit, b: Lit) -> Lit {
+        if a == !self.truth || b == !self.truth {
+            return !self.truth;
+        }
+        if a == self.truth {
+            return b;
+        }
+        if b == self.truth {
+            return a;
+        }
+        let gate = Lit::new(self.solver.new_var(), true);
+        self.solver.add_clause(&[!gate, a]);
+        self.solver.add_clause(&[!gate, b]);
+        self.solver.add_clause(&[gate, !a, !b]);
+        gate
+    }
+
+    fn or(&mut self, a: Lit, b: Lit) -> Lit {
+        !self.and(!a, !b)
+    }
+
+    /// Counts the true inputs with a sequential counter.
+    ///
+    /// # Returns
+    ///
+    /// The literals of at least `k` inputs being true, for `k` from 0 to `max`.
+    ///
+    fn at_least(&mut self, inputs: &[Lit], max: usize) -> Vec<Lit> {
+        let mut counts = vec![!self.truth; max + 1];
+        counts[0] = self.truth;
+        for &input in inputs {
+            for k in (1..=max).rev() {
+                let carried = self.and(input, counts[k - 1]);
+                counts[k] = self.or(counts[k], carried);
+            }
+        }
+        counts
+    }
+
+    /// Returns the literal of the count being within a range.
+    ///
+    fn in_range(&mut self, counts: &[Lit], (lo, hi): (u16, u16)) -> Lit {
+        let at_least = |k: usize| counts.get(k).copied().unwrap_or(!self.truth);
+        let (lo, above) = (at_least(lo as usize), at_least(hi as usize + 1));
+        self.and(lo, !above)
+    }
+
+    /// Adds the clauses of the rule for the cell at the given position of a generation.
+    ///
+    fn transition(&mut self, t: usize, x: isize, y: isize) {
+        let inputs: Vec<Lit> = self
+            .offsets
+            .iter()
+            .map(|&(dx, dy)| self.live(t, x + dx, y + dy))
+            .filter(|&lit| lit != !self.truth)
+            .collect();
+        let (ss, bb) = (self.config.ss, self.config.bb);
+        let max = [ss.0, ss.1 + 1, bb.0, bb.1 + 1]
+            .into_iter()
+            .map(|k| k as usize)
+            .max()
+            .unwrap()
+            .min(inputs.len());
+        let counts = self.at_least(&inputs, max);
+        let birth = self.in_range(&counts, bb);
+        if x < 0 || y < 0 || x as usize >= self.width || y as usize >= self.height {
+            self.solver.add_clause(&[!birth]);
+            return;
+        }
+        let survival = self.in_range(&counts, ss);
+        let (x, y) = (x as usize, y as usize);
+        let dying = if self.states > 2 { 2 } else { 0 };
+        let (dead, live) = (self.state(t, x, y, 0), self.state(t, x, y, 1));
+        let next: Vec<Lit> = (0..self.states)
+            .map(|s| self.state(t + 1, x, y, s))
+            .collect();
+        self.solver.add_clause(&[!dead, !birth, next[1]]);
+        self.solver.add_clause(&[!dead, birth, next[0]]);
+        self.solver.add_clause(&[!live, !survival, next[1]]);
+        self.solver.add_clause(&[!live, survival, next[dying]]);
+        for s in 2..self.states {
+            let clause = [!self.state(t, x, y, s), next[(s + 1) % self.states]];
+            self.solver.add_clause(&clause);
+        }
+    }
+
+    /// Adds the clauses keeping every cell in exactly one state.
+    ///
+    fn encode_states(&mut self) {
+        for t in 0..self.generations {
+            for x in 0..self.width {
+                for y in 0..self.height {
+                    let states: Vec<Lit> =
+                        (0..self.states).map(|s| self.state(t, x, y, s)).collect();
+                    self.solver.add_clause(&states);
+                    for (i, &a) in states.iter().enumerate() {
+                        for &b in &states[i + 1..] {
+                            self.solver.add_clause(&[!a, !b]);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Adds the clauses of an oscillator whose period is the number of generations.
+    ///
+    fn encode_oscillator(&mut self) {
+        self.encode_states();
+        let r = self.config.rr as isize;
+        for t in 0..self.generations {
+            for x in -r..self.width as isize + r {
+                for y in -r..self.height as isize + r {
+                    self.transition(t, x, y);
+                }
+            }
+        }
+
+        // Some phase touches the left column and some phase the top row, which rules out
+        // translated copies of the same oscillator.
+        let left: Vec<Lit> = (0..self.generations)
+            .flat_map(|t| (0..self.height).map(move |y| (t, y)))
+            .map(|(t, y)| self.live(t, 0, y as isize))
+            .collect();
+        self.solver.add_clause(&left);
+        let top: Vec<Lit> = (0..self.generations)
+            .flat_map(|t| (0..self.width).map(move |x| (t, x)))
+            .map(|(t, x)| self.live(t, x as isize, 0))
+            .collect();
+        self.solver.add_clause(&top);
+
+        // The first phase differs from the phase `period / q` generations later for every
+        // prime factor `q` of the period, so the period is exact.
+        for q in prime_factors(self.generations) {
+            let shift = self.generations / q;
+            let mut differences = Vec::new();
+            for x in 0..self.width {
+                for y in 0..self.height {
+                    for s in 0..self.states {
+                        let (a, b) = (self.state(0, x, y, s), self.state(shift, x, y, s));
+                        differences.push(self.and(a, !b));
+                    }
+                }
+            }
+            self.solver.add_clause(&differences);
+        }
+    }
+
+    /// Adds the clauses of a first generation evolving into a target pattern placed
+    /// `margin` cells away from the edges of the box.
+    ///
+    /// # Arguments
+    ///
+    /// * `target` - The pattern of the second generation.
+    /// * `margin` - The offset of the target in the box.
+    /// * `exact` - Whether the cells around the target must be dead too.
+    ///
+    fn encode_predecessor(&mut self, target: &Pattern, margin: usize, exact: bool) {
+        self.encode_states();
+        let r = self.config.rr as isize;
+        for x in -r..self.width as isize + r {
+            for y in -r..self.height as isize + r {
+                let (tx, ty) = (x - margin as isize, y - margin as isize);
+                let in_target = tx >= 0
+                    && ty >= 0
+                    && (tx as usize) < target.width()
+                    && (ty as usize) < target.height();
+                if in_target {
+                    let state = target.get(tx as usize, ty as usize) as usize;
+                    let lit = self.state(1, x as usize, y as usize, state);
+                    self.solver.add_clause(&[lit]);
+                } else if !exact {
+                    continue;
+                } else if x >= 0
+                    && y >= 0
+                    && (x as usize) < self.width
+                    && (y as usize) < self.height
+                {
+                    let lit = self.state(1, x as usize, y as usize, 0);
+                    self.solver.add_clause(&[lit]);
+                }
+                self.transition(0, x, y);
+            }
+        }
+    }
+
+    /// Reads the phases of an oscillator from a model, as patterns the size of the box.
+    ///
+    fn phases(&self, model: &[bool]) -> Vec<Pattern> {
+        (0..self.generations)
+            .map(|t| {
+                let mut pattern = Pattern::new(self.width, self.height);
+                for x in 0..self.width {
+                    for y in 0..self.height {
+                        let state = (0..self.states)
+                            .find(|&s| model[self.state(t, x, y, s).var()])
+                            .unwrap();
+                        pattern.set(x, y, state as u8);
+                    }
+                }
+                pattern
+            })
+            .collect()
+    }
+
+    /// Forbids every phase of an oscillator, in every orientation fitting in the box, from
+    /// being found again as the first phase.
+    ///
+    fn block(&mut self, phases: &[Pattern]) {
+        for symmetry in Symmetry::ALL {
+            let transformed: Vec<Pattern> = phases.iter().map(|p| p.transform(symmetry)).collect();
+            let Some((x0, y0, x1, y1)) = live_bounds(&transformed) else {
+                continue;
+            };
+            if x1 - x0 >= self.width || y1 - y0 >= self.height {
+                continue;
+            }
+            for phase in &transformed {
+                let mut clause = Vec::with_capacity(self.width * self.height);
+                for x in 0..self.width {
+                    for y in 0..self.height {
+                        let (px, py) = (x + x0, y + y0);
+                        let state = if px < phase.width() && py < phase.height() {
+                            phase.get(px, py) as usize
+                        } else {
+                            0
+                        };
+                        clause.push(!self.state(0, x, y, state));
+                    }
+                }
+                self.solver.add_clause(&clause);
+            }
+        }
+    }
+}
+
+/// Returns the offsets of the cells counted by the neighbourhood of a rule.
+///
+fn offsets(config: &Config) -> Vec<(isize, isize)> {
+    let r = config.rr as isize;
+    let mut offsets = Vec::new();
+    for dx in -r..=r {
+        for dy in -r..=r {
+            if dx == 0 && dy == 0 && config.mm == 0 {
+                continue;
+            }
+            if config.nn == Neighbourhood::Neumann && dx.abs() + dy.abs() > r {
+                continue;
+            }
+            offsets.push((dx, dy));
+        }
+    }
+    offsets
+}
+
+fn prime_factors(mut n: usize) -> Vec<usize> {
+    let mut factors = Vec::new();
+    let mut p = 2;
+    while p * p <= n {
+        if n.is_multiple_of(p) {
+            factors.push(p);
+            while n.is_multiple_of(p) {
+                n /= p;
+            }
+        }
+        p += 1;
+    }
+    if n > 1 {
+        factors.push(n);
+    }
+    factors
+}
+
+/// Returns the bounds `(x0, y0, x1, y1)` of the non-zero cells of all patterns.
+///
+fn live_bounds(patterns: &[Pattern]) -> Option<(usize, usize, usize, usize)> {
+    let mut bounds: Option<(usize, usize, usize, usize)> = None;
+    for pattern in patterns {
+        for x in 0..pattern.width() {
+            for y in 0..pattern.height() {
+                if pattern.get(x, y) != 0 {
+                    bounds = Some(match bounds {
+                        None => (x, y, x, y),
+                        Some((x0, y0, x1, y1)) => (x0.min(x), y0.min(y), x1.max(x), y1.max(y)),
+                    });
+                }
+            }
+        }
+    }
+    bounds
+}
+
+/// Crops patterns of the same size to the region covered by all of them.
+///
+fn crop(patterns: &[Pattern]) -> Vec<Pattern> {
+    let (x0, y0, x1, y1) = live_bounds(patterns).unwrap_or((0, 0, 0, 0));
+    patterns
+        .iter()
+        .map(|pattern| {
+            let mut cropped = Pattern::new(x1 - x0 + 1, y1 - y0 + 1);
+            for x in 0..cropped.width() {
+                for y in 0..cropped.height() {
+                    cropped.set(x, y, pattern.get(x0 + x, y0 + y));
+                }
+            }
+            cropped
+        })
+        .collect()
+}
+
+/// Looks for still lifes or oscillators of a rule with a SAT solver.
+///
+/// The rule is encoded for every cell of the box and the cells around it within the radius
+/// of the neighbourhood, for every generation of the period, the last generation evolving
+/// into the first one.
+///
+/// # Arguments
+///
+/// * `config` - The rule.
+/// * `options` - The box, period and limits of the search.
+///
+/// # Returns
+///
+/// The distinct oscillators found, each once whatever its position, phase or orientation.
+///
+pub fn search_oscillators(
+    config: &Config,
+    options: &SearchOptions,
+) -> Result<SearchReport, SearchError> {
+    if options.width == 0 || options.height == 0 {
+        return Err(SearchError::EmptyBox);
+    }
+    if options.period == 0 {
+        return Err(SearchError::Period);
+    }
+    if config.bb.0 == 0 {
+        return Err(SearchError::Birth);
+    }
+    let mut encoder = Encoder::new(config, options.width, options.height, options.period);
+    encoder.encode_oscillator();
+    let mut oscillators: Vec<Oscillator> = Vec::new();
+    let mut exhausted = false;
+    while oscillators.len() < options.max_solutions {
+        let model = match encoder.solver.solve(options.max_conflicts) {
+            SatResult::Sat(model) => model,
+            SatResult::Unsat => {
+                exhausted = true;
+                break;
+            }
+            SatResult::Unknown => break,
+        };
+        let phases = encoder.phases(&model);
+        encoder.block(&phases);
+        let prefix = if options.period == 1 {
+            format!("xs{}", phases[0].population())
+        } else {
+            format!("xp{}", options.period)
+        };
+        let code = phases
+            .iter()
+            .map(|phase| phase.code_with_prefix(&prefix))
+            .min_by(|a, b| a.len().cmp(&b.len()).then_with(|| a.cmp(b)))
+            .unwrap();
+        if oscillators.iter().all(|o| o.code != code) {
+            oscillators.push(Oscillator {
+                period: options.period,
+                phases: crop(&phases),
+                code,
+            });
+        }
+    }
+    Ok(SearchReport {
+        oscillators,
+        exhausted,
+    })
+}
+
+/// Options of a predecessor search.
+///
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct PredecessorOptions {
+    /// Cells around the target the predecessor may extend to, the radius of the
+    /// neighbourhood when `None`.
+    pub margin: Option<usize>,
+    /// Whether every cell around the target must be dead after one generation, rather than
+    /// only the target itself matching.
+    pub exact: bool,
+    pub max_conflicts: Option<u64>,
+}
+
+/// Outcome of a predecessor search.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub enum Predecessor {
+    /// A pattern evolving into the target in one generation, the target being `margin`
+    /// cells away from its edges.
+    Found(Pattern),
+    /// No pattern within the margin evolves into the target. With a margin of at least the
+    /// radius of the neighbourhood and without `exact`, the target is a Garden of Eden.
+    None,
+    /// The conflict budget ran out.
+    Unknown,
+}
+
+/// Looks for a pattern evolving into a target in one generation with a SAT solver.
+///
+/// # Arguments
+///
+/// * `config` - The rule.
+/// * `target` - The pattern to reach, every cell of it has to match.
+/// * `options` - The area and limits of the search.
+///
+pub fn find_predecessor(
+    config: &Config,
+    target: &Pattern,
+    options: &PredecessorOptions,
+) -> Result<Predecessor, SearchError> {
+    if target.width() == 0 || target.height() == 0 {
+        return Err(SearchError::EmptyBox);
+    }
+    if options.exact && config.bb.0 == 0 {
+        return Err(SearchError::Birth);
+    }
+    let margin = options.margin.unwrap_or(config.rr as usize);
+    let (width, height) = (target.width() + 2 * margin, target.height() + 2 * margin);
+    let mut encoder = Encoder::new(config, width, height, 2);
+    encoder.encode_predecessor(target, margin, options.exact);
+    Ok(match encoder.solver.solve(options.max_conflicts) {
+        SatResult::Sat(model) => Predecessor::Found(encoder.phases(&model).swap_remove(0)),
+        SatResult::Unsat => Predecessor::None,
+        SatResult::Unknown => Predecessor::Unknown,
+    })
+}
+
+impl Board {
+    /// Looks for a pattern evolving into a region of the board in one generation.
+    ///
+    /// # Arguments
+    ///
+    /// * `region` - The target region, clamped to the board.
+    /// * `options` - The area and limits of the search.
+    ///
+    pub fn find_predecessor(
+        &self,
+        region: Region,
+        options: &PredecessorOptions,
+    ) -> Result<Predecessor, SearchError> {
+        find_predecessor(
+            self.config(),
+            &Pattern::from_region(&self.cells, region),
+            options,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn life() -> Config {
+        Config::new(1, 0, 0, (2, 3), (3, 3), Neighbourhood::Moore)
+    }
+
+    /// Checks an oscillator has its exact period when evolved on a board.
+    ///
+    fn assert_oscillates(oscillator: &Oscillator, config: &Config) {
+        let mut board = oscillator.to_board(config, 2 * config.rr as usize + 2);
+        let initial = board.cells.clone();
+        for generation in 1..=oscillator.period {
+            board.update();
+            assert_eq!(
+                board.cells == initial,
+                generation == oscillator.period,
+                "{} at generation {}",
+                oscillator.code,
+                generation
+            );
+        }
+    }
+
+    #[test]
+    fn life_still_lifes() {
+        let options = SearchOptions {
+            width: 4,
+            height: 4,
+            max_solutions: 20,
+            ..SearchOptions::default()
+        };
+        let report = search_oscillators(&life(), &options).unwrap();
+        assert!(report.exhausted);
+        let mut codes: Vec<&str> = report.oscillators.iter().map(|o| o.code.as_str()).collect();
+        codes.sort();
+        // Block, beehive, loaf, boat, ship, tub, pond and their combinations fitting in 4x4.
+        for code in [
+            "xs4_33", "xs6_696", "xs7_2596", "xs5_253", "xs6_356", "xs4_252", "xs8_6996",
+        ] {
+            assert!(codes.contains(&code), "{} missing from {:?}", code, codes);
+        }
+        for oscillator in &report.oscillators {
+            assert_oscillates(oscillator, &life());
+        }
+    }
+
+    #[test]
+    fn life_blinker() {
+        let options = SearchOptions {
+            width: 3,
+            height: 3,
+            period: 2,
+            max_solutions: 5,
+            ..SearchOptions::default()
+        };
+        let report = search_oscillators(&life(), &options).unwrap();
+        assert!(report.exhausted);
+        assert_eq!(report.oscillators.len(), 1);
+        let blinker = &report.oscillators[0];
+        assert_eq!(blinker.code, "xp2_7");
+        assert_eq!(blinker.phases.len(), 2);
+        assert_eq!(blinker.population(), 3);
+        let rle = blinker.to_rle(&life());
+        assert!(rle.starts_with("x = "));
+        assert!(rle.ends_with("\n3o!\n") || rle.ends_with("\no$o$o!\n"));
+        assert_oscillates(blinker, &life());
+    }
+
+    #[test]
+    fn larger_than_life_oscillators() {
+        let generations = Config::new(1, 3, 0, (2, 3), (3, 3), Neighbourhood::Moore);
+        let bosco = Config::new(2, 0, 1, (3, 6), (4, 5), Neighbourhood::Neumann);
+        for (config, period, solutions) in [(generations, 3, 1), (bosco, 1, 3)] {
+            let options = SearchOptions {
+                width: 5,
+                height: 5,
+                period,
+                max_solutions: solutions,
+                ..SearchOptions::default()
+            };
+            let report = search_oscillators(&config, &options).unwrap();
+            assert_eq!(report.oscillators.len(), solutions);
+            for oscillator in &report.oscillators {
+                assert_oscillates(oscillator, &config);
+            }
+        }
+    }
+
+    #[test]
+    fn invalid_searches() {
+        let options = SearchOptions::default();
+        let growing = Config::new(1, 0, 0, (2, 3), (0, 3), Neighbourhood::Moore);
+        assert_eq!(
+            search_oscillators(&growing, &options),
+            Err(SearchError::Birth)
+        );
+        let options = SearchOptions {
+            period: 0,
+            ..SearchOptions::default()
+        };
+        assert_eq!(
+            search_oscillators(&life(), &options),
+            Err(SearchError::Period)
+        );
+        assert_eq!(prime_factors(12), vec![2, 3]);
+        assert_eq!(prime_factors(7), vec![7]);
+    }
+
+    /// Checks a predecessor evolves into the target.
+    ///
+    fn assert_predecessor(predecessor: &Pattern, target: &Pattern, config: &Config) {
+        let padding = config.rr as usize + 1;
+        let margin = (predecessor.width() - target.width()) / 2;
+        let size = predecessor.width().max(predecessor.height()) + 2 * padding;
+        let mut board = Board::new(size as u64, config.clone());
+        predecessor.place(&mut board, padding, padding);
+        board.update();
+        let region = Region {
+            x: padding + margin,
+            y: padding + margin,
+            width: target.width(),
+            height: target.height(),
+        };
+        assert_eq!(Pattern::from_region(&board.cells, region), *target);
+    }
+
+    #[test]
+    fn life_predecessors() {
+        let mut block = Pattern::new(4, 4);
+        for (x, y) in [(1, 1), (1, 2), (2, 1), (2, 2)] {
+            block.set(x, y, 1);
+        }
+        for exact in [false, true] {
+            let options = PredecessorOptions {
+                exact,
+                ..PredecessorOptions::default()
+            };
+            let Predecessor::Found(predecessor) =
+                find_predecessor(&life(), &block, &options).unwrap()
+            else {
+                panic!("block has predecessors");
+            };
+            assert_eq!(predecessor.width(), 6);
+            assert_predecessor(&predecessor, &block, &life());
+            if exact {
+                let mut board = Board::new(12, life());
+                predecessor.place(&mut board, 3, 3);
+                board.update();
+                assert_eq!(board.stats().population, 4);
+            }
+        }
+
+        let mut board = Board::new(8, life());
+        board.set_cell(3, 3, 1);
+        let region = Region {
+            x: 2,
+            y: 2,
+            width: 3,
+            height: 3,
+        };
+        // Even squeezed into the region, three cells give birth to a lone cell.
+        let options = PredecessorOptions {
+            margin: Some(0),
+            ..PredecessorOptions::default()
+        };
+        let Ok(Predecessor::Found(predecessor)) = board.find_predecessor(region, &options) else {
+            panic!("a lone cell has predecessors");
+        };
+        assert_eq!(predecessor.population(), 3);
+        assert_predecessor(
+            &predecessor,
+            &Pattern::from_region(&board.cells, region),
+            &life(),
+        );
+    }
+
+    #[test]
+    fn garden_of_eden() {
+        // Live cells always survive, so none of them ever starts dying.
+        let config = Config::new(1, 3, 0, (0, 8), (3, 3), Neighbourhood::Moore);
+        let mut target = Pattern::new(1, 1);
+        target.set(0, 0, 2);
+        for exact in [false, true] {
+            let options = PredecessorOptions {
+                exact,
+                ..PredecessorOptions::default()
+            };
+            assert_eq!(
+                find_predecessor(&config, &target, &options),
+                Ok(Predecessor::None)
+            );
+        }
+        let generations = Config::new(1, 3, 0, (2, 3), (3, 3), Neighbourhood::Moore);
+        let mut target = Pattern::new(3, 1);
+        target.set(0, 0, 2);
+        target.set(1, 0, 1);
+        let Ok(Predecessor::Found(predecessor)) =
+            find_predecessor(&generations, &target, &PredecessorOptions::default())
+        else {
+            panic!("a dying cell next to a live cell has predecessors");
+        };
+        assert_predecessor(&predecessor, &target, &generations);
+    }
+}
@@ -0,0 +1,414 @@
+use crate::ltl_engine::board::Board;
+use crate::ltl_engine::cells::Region;
+use crate::ltl_engine::census::{CensusEntry, CensusOptions, ObjectKind};
+use crate::ltl_engine::config::Config;
+use crate::ltl_engine::pattern::Pattern;
+use serde::Serialize;
+use std::cmp::{max, Ordering};
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{self, Write};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
+use std::sync::Mutex;
+use std::thread;
+
+const JSON: &str = "json";
+const TEXT: &str = "text";
+
+/// Supported formats of soup search reports.
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReportFormat {
+    Json,
+    Text,
+}
+
+impl FromStr for ReportFormat {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            JSON => Ok(ReportFormat::Json),
+            TEXT | "txt" => Ok(ReportFormat::Text),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Options of a soup search.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct SoupOptions {
+    /// Number of soups to run.
+    pub soups: usize,
+    /// Seed of the first soup, the following soups use consecutive seeds.
+    pub first_seed: u64,
+    /// Size of the board the soups evolve on.
+    pub board_size: u64,
+    /// Size of the random square in the middle of the board.
+    pub soup_size: u64,
+    /// The maximal number of generations a soup runs for to stabilise.
+    pub max_generations: usize,
+    /// The longest period of a stable soup.
+    pub max_period: usize,
+    /// Number of soups run at the same time, the number of cores when `None`.
+    pub threads: Option<usize>,
+    /// Number of example seeds kept for every object.
+    pub examples: usize,
+    /// Options of the census of stable soups.
+    pub census: CensusOptions,
+}
+
+impl Default for SoupOptions {
+    fn default() -> Self {
+        SoupOptions {
+            soups: 100,
+            first_seed: 0,
+            board_size: 64,
+            soup_size: 16,
+            max_generations: 1000,
+            max_period: 32,
+            threads: None,
+            examples: 3,
+            census: CensusOptions::default(),
+        }
+    }
+}
+
+/// Objects of one kind found by a soup search.
+///
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SoupEntry {
+    #[serde(flatten)]
+    pub kind: ObjectKind,
+    pub hash: u64,
+    pub code: String,
+    /// Number of objects found in all soups.
+    pub count: u64,
+    pub population: u64,
+    /// Seeds of the first soups the object was found in.
+    pub seeds: Vec<u64>,
+}
+
+/// The aggregated census of a soup search.
+///
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SoupReport {
+    pub rule: String,
+    pub soups: usize,
+    pub first_seed: u64,
+    pub board_size: u64,
+    pub soup_size: u64,
+    pub max_generations: usize,
+    /// Seeds of the soups that didn't stabilise within `max_generations`.
+    pub unstabilised: Vec<u64>,
+    /// Objects found, the most common first.
+    pub objects: Vec<SoupEntry>,
+}
+
+impl SoupReport {
+    /// Writes the report in the given format.
+    ///
+    pub fn write<W: Write>(&self, mut writer: W, format: ReportFormat) -> io::Result<()> {
+        match format {
+            ReportFormat::Json => {
+                serde_json::to_writer_pretty(&mut writer, self).map_err(io::Error::from)?;
+                writeln!(writer)
+            }
+            ReportFormat::Text => write!(writer, "{}", self),
+        }
+    }
+}
+
+impl fmt::Display for SoupReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Soup search of {}", self.rule)?;
+        writeln!(
+            f,
+            "{} soups of {}x{} cells on {}x{} boards, seeds {}-{}",
+            self.soups,
+            self.soup_size,
+            self.soup_size,
+            self.board_size,
+            self.board_size,
+            self.first_seed,
+            (self.first_seed + self.soups as u64).saturating_sub(1)
+        )?;
+        writeln!(
+            f,
+            "{} soups didn't stabilise within {} generations",
+            self.unstabilised.len(),
+            self.max_generations
+        )?;
+        writeln!(f)?;
+        writeln!(
+            f,
+            "{:>8}  {:<24}  {:>10}  {:<16}  {:<16}  seeds",
+            "count", "kind", "population", "code", "hash"
+        )?;
+        for entry in &self.objects {
+            let seeds: Vec<String> = entry.seeds.iter().map(|s| s.to_string()).collect();
+            writeln!(
+                f,
+                "{:>8}  {:<24}  {:>10}  {:<16}  {:016x}  {}",
+                entry.count,
+                entry.kind.to_string(),
+                entry.population,
+                entry.code,
+                entry.hash,
+                seeds.join(", ")
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Census of a single soup.
+///
+struct SoupResult {
+    seed: u64,
+    stabilised: bool,
+    summary: Vec<CensusEntry>,
+}
+
+/// Creates a board with a random soup in its middle.
+///
+/// # Arguments
+///
+/// * `config` - The rule of the soup.
+/// * `seed` - The seed of the soup.
+/// * `options` - Soup search options.
+///
+pub fn seed_soup(config: &Config, seed: u64, options: &SoupOptions) -> Board {
+    let soup_size = options.soup_size.min(options.board_size);
+    let mut soup = Board::new(soup_size, config.clone());
+    soup.randomize(Some(seed));
+    let pattern = Pattern::from_region(&soup.cells, Region::full(soup_size as usize));
+
+    let mut board = Board::new(options.board_size, config.clone());
+    // Soups run one per worker thread, so each board updates on its own thread.
+    board.set_threads(Some(1));
+    let offset = ((options.board_size - soup_size) / 2) as usize;
+    pattern.place(&mut board, offset, offset);
+    board
+}
+
+/// Runs a single soup until it stabilises.
+///
+/// # Arguments
+///
+/// * `config` - The rule of the soup.
+/// * `seed` - The seed of the soup.
+/// * `options` - Soup search options.
+///
+/// # Returns
+///
+/// The board after the run and whether it became periodic.
+///
+pub fn run_soup(config: &Config, seed: u64, options: &SoupOptions) -> (Board, bool) {
+    let mut board = seed_soup(config, seed, options);
+    let stabilised = board
+        .run_until_periodic(options.max_generations, options.max_period)
+        .is_some();
+    (board, stabilised)
+}
+
+/// Runs many random soups in parallel and aggregates the census of the objects they leave.
+///
+/// # Arguments
+///
+/// * `config` - The rule of the soups.
+/// * `options` - Soup search options.
+///
+/// # Returns
+///
+/// The report of the search.
+///
+pub fn soup_search(config: &Config, options: &SoupOptions) -> SoupReport {
+    let results = parallel_map(options.soups, options.threads, |i| {
+        let seed = options.first_seed + i as u64;
+        let (board, stabilised) = run_soup(config, seed, options);
+        SoupResult {
+            seed,
+            stabilised,
+            summary: board.census(&options.census).summary(),
+        }
+    });
+
+    let mut objects: Vec<SoupEntry> = Vec::new();
+    let mut index: HashMap<(ObjectKind, u64), usize> = HashMap::new();
+    let mut unstabilised = Vec::new();
+    for result in results {
+        if !result.stabilised {
+            unstabilised.push(result.seed);
+        }
+        for found in result.summary {
+            let i = *index.entry((found.kind, found.hash)).or_insert_with(|| {
+                objects.push(SoupEntry {
+                    kind: found.kind,
+                    hash: found.hash,
+                    code: found.code.clone(),
+                    count: 0,
+                    population: found.population,
+                    seeds: Vec::new(),
+                });
+                objects.len() - 1
+            });
+            let entry = &mut objects[i];
+            entry.count += found.count;
+            if entry.seeds.len() < options.examples {
+                entry.seeds.push(result.seed);
+            }
+        }
+    }
+    objects.sort_by(|a, b| match b.count.cmp(&a.count) {
+        Ordering::Equal => a.population.cmp(&b.population),
+        ordering => ordering,
+    });
+
+    SoupReport {
+        rule: config.to_string(),
+        soups: options.soups,
+        first_seed: options.first_seed,
+        board_size: options.board_size,
+        soup_size: options.soup_size.min(options.board_size),
+        max_generations: options.max_generations,
+        unstabilised,
+        objects,
+    }
+}
+
+/// Computes `f(0)..f(count)` on a pool of threads.
+///
+/// # Arguments
+///
+/// * `count` - The number of computed values.
+/// * `threads` - The number of threads, the number of cores when `None`.
+/// * `f` - The computation of a value from its index.
+///
+/// # Returns
+///
+/// The values ordered by their index.
+///
+pub(crate) fn parallel_map<T: Send, F: Fn(usize) -> T + Sync>(
+    count: usize,
+    threads: Option<usize>,
+    f: F,
+) -> Vec<T> {
+    let threads = threads.unwrap_or_else(|| thread::available_parallelism().map_or(1, |n| n.get()));
+    let next = AtomicUsize::new(0);
+    let results = Mutex::new(Vec::with_capacity(count));
+    thread::scope(|scope| {
+        for _ in 0..max(threads, 1).min(max(count, 1)) {
+            scope.spawn(|| loop {
+                let i = next.fetch_add(1, AtomicOrdering::Relaxed);
+                if i >= count {
+                    break;
+                }
+                let value = f(i);
+                results.lock().unwrap().push((i, value));
+            });
+        }
+    });
+    let mut results = results.into_inner().unwrap();
+    results.sort_by_key(|&(i, _)| i);
+    results.into_iter().map(|(_, value)| value).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ltl_engine::neighbourhood::Neighbourhood;
+
+    fn options() -> SoupOptions {
+        SoupOptions {
+            soups: 6,
+            first_seed: 10,
+            board_size: 32,
+            soup_size: 8,
+            max_generations: 300,
+            max_period: 8,
+            threads: Some(3),
+            examples: 2,
+            ..SoupOptions::default()
+        }
+    }
+
+    #[test]
+    fn soup_is_centred() {
+        let config = Config::new(1, 0, 0, (2, 3), (3, 3), Neighbourhood::Moore);
+        let options = SoupOptions {
+            max_generations: 0,
+            ..options()
+        };
+        let (board, _) = run_soup(&config, 1, &options);
+        let bounding_box = board.stats().bounding_box.unwrap();
+        assert!(bounding_box.x >= 12 && bounding_box.x + bounding_box.width <= 20);
+        assert!(bounding_box.y >= 12 && bounding_box.y + bounding_box.height <= 20);
+    }
+
+    #[test]
+    fn search_is_deterministic() {
+        let config = Config::new(1, 0, 0, (2, 3), (3, 3), Neighbourhood::Moore);
+        let report = soup_search(&config, &options());
+        let single_threaded = soup_search(
+            &config,
+            &SoupOptions {
+                threads: Some(1),
+                ..options()
+            },
+        );
+        assert_eq!(report, single_threaded);
+        assert_eq!(report.rule, "R1,C0,M0,S2..3,B3..3,NM");
+        let found: u64 = report.objects.iter().map(|o| o.count).sum();
+        assert!(found > 0);
+        assert!(report.objects.iter().all(|o| o.seeds.len() <= 2));
+        assert!(report
+            .objects
+            .windows(2)
+            .all(|pair| pair[0].count >= pair[1].count));
+    }
+
+    #[test]
+    fn report_formats() {
+        let report = SoupReport {
+            rule: "R1,C0,M0,S2..3,B3..3,NM".to_string(),
+            soups: 2,
+            first_seed: 0,
+            board_size: 32,
+            soup_size: 8,
+            max_generations: 100,
+            unstabilised: vec![1],
+            objects: vec![SoupEntry {
+                kind: ObjectKind::Oscillator { period: 2 },
+                hash: 255,
+                code: "xp2_7".to_string(),
+                count: 3,
+                population: 3,
+                seeds: vec![0, 1],
+            }],
+        };
+        let mut text = Vec::new();
+        report.write(&mut text, ReportFormat::Text).unwrap();
+        let text = String::from_utf8(text).unwrap();
+        assert!(text.contains("1 soups didn't stabilise"));
+        assert!(text.contains("p2 oscillator"));
+        assert!(text.contains("xp2_7             00000000000000ff  0, 1"));
+
+        let mut json = Vec::new();
+        report.write(&mut json, ReportFormat::Json).unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&json).unwrap();
+        assert_eq!(json["objects"][0]["kind"], "oscillator");
+        assert_eq!(json["objects"][0]["period"], 2);
+        assert_eq!(json["objects"][0]["code"], "xp2_7");
+        assert_eq!(json["unstabilised"][0], 1);
+    }
+
+    #[test]
+    fn format_from_str() {
+        assert_eq!(ReportFormat::from_str("JSON"), Ok(ReportFormat::Json));
+        assert_eq!(ReportFormat::from_str("txt"), Ok(ReportFormat::Text));
+        assert_eq!(ReportFormat::from_str("xml"), Err(()));
+    }
+}
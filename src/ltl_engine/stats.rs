@@ -0,0 +1,474 @@
+use crate::ltl_engine::cells::{Cell, Cells, Region};
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::str::FromStr;
+
+const CSV: &str = "csv";
+const JSON: &str = "json";
+
+/// Statistics of one generation of a board.
+///
+/// Every non-zero state counts towards the population, the same way cells are counted in
+/// neighbourhoods. Births, deaths and survivors describe the transitions of state 1 during
+/// the update that produced the generation.
+///
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Stats {
+    pub generation: u64,
+    /// Number of cells in every state.
+    pub counts: Vec<u64>,
+    /// Number of cells in a non-zero state.
+    pub population: u64,
+    /// Fraction of the board covered by the population.
+    pub density: f64,
+    /// Cells that went from state 0 to state 1.
+    pub births: u64,
+    /// Cells that left state 1.
+    pub deaths: u64,
+    /// Cells that stayed in state 1.
+    pub survivors: u64,
+    /// The smallest region holding the whole population, `None` when the board is empty.
+    pub bounding_box: Option<Region>,
+}
+
+impl Stats {
+    /// Counts the cells of a board.
+    ///
+    /// # Arguments
+    ///
+    /// * `cells` - The cells of the board.
+    /// * `states` - The number of states supported by the board.
+    /// * `generation` - The generation of the cells.
+    ///
+    /// # Returns
+    ///
+    /// Statistics of the cells, without any transitions.
+    ///
+    pub fn of(cells: &Cells, states: u8, generation: u64) -> Self {
+        let mut tally = Tally::new(states);
+        for (x, column) in cells.iter().enumerate() {
+            for (y, &state) in column.iter().enumerate() {
+                tally.count(x, y, state);
+            }
+        }
+        tally.into_stats(generation, cells.len())
+    }
+
+    /// Updates the statistics after a single cell changed its state. Transitions are left as
+    /// they are.
+    ///
+    /// # Arguments
+    ///
+    /// * `cells` - The cells of the board, already holding the new state.
+    /// * `x` - The x coordinate of the cell.
+    /// * `y` - The y coordinate of the cell.
+    /// * `before` - The previous state of the cell.
+    ///
+    pub(crate) fn replace(&mut self, cells: &Cells, x: usize, y: usize, before: Cell) {
+        let after = cells[x][y];
+        if before == after {
+            return;
+        }
+        if let Some(count) = self.counts.get_mut(before as usize) {
+            *count -= 1;
+        }
+        if self.counts.len() <= after as usize {
+            self.counts.resize(after as usize + 1, 0);
+        }
+        self.counts[after as usize] += 1;
+        self.population = self.counts.iter().skip(1).sum();
+        self.density = density(self.population, cells.len());
+
+        if after != 0 {
+            let cell = Region {
+                x,
+                y,
+                width: 1,
+                height: 1,
+            };
+            self.bounding_box = Some(self.bounding_box.map_or(cell, |b| b.union(&cell)));
+        } else if before != 0 && self.bounding_box.is_some_and(|b| b.on_edge(x, y)) {
+            self.bounding_box = bounding_box(cells);
+        }
+    }
+}
+
+/// Accumulates statistics of a part of the board.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Tally {
+    counts: Vec<u64>,
+    births: u64,
+    deaths: u64,
+    survivors: u64,
+    /// Inclusive bounds `(x_min, y_min, x_max, y_max)` of the non-zero cells.
+    bounds: Option<(usize, usize, usize, usize)>,
+}
+
+impl Tally {
+    pub(crate) fn new(states: u8) -> Self {
+        Tally {
+            counts: vec![0; states as usize],
+            births: 0,
+            deaths: 0,
+            survivors: 0,
+            bounds: None,
+        }
+    }
+
+    /// Counts a cell in the given state.
+    ///
+    #[inline]
+    pub(crate) fn count(&mut self, x: usize, y: usize, state: Cell) {
+        if self.counts.len() <= state as usize {
+            self.counts.resize(state as usize + 1, 0);
+        }
+        self.counts[state as usize] += 1;
+        if state != 0 {
+            self.bounds = Some(match self.bounds {
+                None => (x, y, x, y),
+                Some((x0, y0, x1, y1)) => (x0.min(x), y0.min(y), x1.max(x), y1.max(y)),
+            });
+        }
+    }
+
+    /// Counts a cell that went from state `before` to state `after`.
+    ///
+    #[inline]
+    pub(crate) fn transition(&mut self, x: usize, y: usize, before: Cell, after: Cell) {
+        match (before, after) {
+            (1, 1) => self.survivors += 1,
+            (1, _) => self.deaths += 1,
+            (0, 1) => self.births += 1,
+            _ => {}
+        }
+        self.count(x, y, after);
+    }
+
+    /// Adds the counts of another part of the board.
+    ///
+    pub(crate) fn merge(&mut self, other: &Tally) {
+        if self.counts.len() < other.counts.len() {
+            self.counts.resize(other.counts.len(), 0);
+        }
+        for (count, other) in self.counts.iter_mut().zip(&other.counts) {
+            *count += other;
+        }
+        self.births += other.births;
+        self.deaths += other.deaths;
+        self.survivors += other.survivors;
+        self.bounds = match (self.bounds, other.bounds) {
+            (Some((x0, y0, x1, y1)), Some((u0, v0, u1, v1))) => {
+                Some((x0.min(u0), y0.min(v0), x1.max(u1), y1.max(v1)))
+            }
+            (bounds, None) | (None, bounds) => bounds,
+        };
+    }
+
+    pub(crate) fn into_stats(self, generation: u64, size: usize) -> Stats {
+        let population = self.counts.iter().skip(1).sum();
+        Stats {
+            generation,
+            population,
+            density: density(population, size),
+            counts: self.counts,
+            births: self.births,
+            deaths: self.deaths,
+            survivors: self.survivors,
+            bounding_box: self.bounds.map(|(x0, y0, x1, y1)| Region {
+                x: x0,
+                y: y0,
+                width: x1 - x0 + 1,
+                height: y1 - y0 + 1,
+            }),
+        }
+    }
+}
+
+fn density(population: u64, size: usize) -> f64 {
+    if size == 0 {
+        0.0
+    } else {
+        population as f64 / (size * size) as f64
+    }
+}
+
+/// Returns the smallest region holding every non-zero cell.
+///
+pub fn bounding_box(cells: &Cells) -> Option<Region> {
+    let mut tally = Tally::new(0);
+    for (x, column) in cells.iter().enumerate() {
+        if let Some(y0) = column.iter().position(|&s| s != 0) {
+            let y1 = column.iter().rposition(|&s| s != 0).unwrap();
+            tally.count(x, y0, 1);
+            tally.count(x, y1, 1);
+        }
+    }
+    tally.into_stats(0, cells.len()).bounding_box
+}
+
+/// Supported formats of exported statistics.
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StatsFormat {
+    Csv,
+    Json,
+}
+
+impl FromStr for StatsFormat {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            CSV => Ok(StatsFormat::Csv),
+            JSON => Ok(StatsFormat::Json),
+            _ => Err(()),
+        }
+    }
+}
+
+impl StatsFormat {
+    /// Guesses the format from the extension of the given path.
+    ///
+    pub fn from_path(path: &Path) -> Option<Self> {
+        path.extension()?.to_str()?.parse().ok()
+    }
+}
+
+/// Statistics of the latest generations of a board, oldest first.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatsHistory {
+    capacity: usize,
+    entries: VecDeque<Stats>,
+}
+
+impl StatsHistory {
+    /// Create an empty history.
+    ///
+    /// # Arguments
+    ///
+    /// * `capacity` - The number of generations kept, older generations are dropped first.
+    ///
+    pub fn new(capacity: usize) -> Self {
+        StatsHistory {
+            capacity,
+            entries: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    /// Appends the statistics of a generation, dropping the oldest one when full.
+    ///
+    pub fn push(&mut self, stats: Stats) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.entries.len() == self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(stats);
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Iterates over the kept generations, oldest first.
+    ///
+    pub fn iter(&self) -> impl Iterator<Item = &Stats> {
+        self.entries.iter()
+    }
+
+    /// Writes the history as CSV, one generation per row. The counts of the states are
+    /// written to the `state_<n>` columns.
+    ///
+    pub fn write_csv<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        let states = self.iter().map(|s| s.counts.len()).max().unwrap_or(0);
+        write!(
+            writer,
+            "generation,population,density,births,deaths,survivors,bbox_x,bbox_y,bbox_width,bbox_height"
+        )?;
+        for state in 0..states {
+            write!(writer, ",state_{}", state)?;
+        }
+        writeln!(writer)?;
+        for stats in self.iter() {
+            write!(
+                writer,
+                "{},{},{},{},{},{}",
+                stats.generation,
+                stats.population,
+                stats.density,
+                stats.births,
+                stats.deaths,
+                stats.survivors
+            )?;
+            match stats.bounding_box {
+                Some(b) => write!(writer, ",{},{},{},{}", b.x, b.y, b.width, b.height)?,
+                None => write!(writer, ",,,,")?,
+            }
+            for state in 0..states {
+                write!(writer, ",{}", stats.counts.get(state).unwrap_or(&0))?;
+            }
+            writeln!(writer)?;
+        }
+        Ok(())
+    }
+
+    /// Writes the history as a JSON array of generations.
+    ///
+    pub fn write_json<W: Write>(&self, writer: W) -> io::Result<()> {
+        serde_json::to_writer(writer, &self.entries).map_err(io::Error::from)
+    }
+
+    /// Writes the history in the given format.
+    ///
+    pub fn write<W: Write>(&self, writer: W, format: StatsFormat) -> io::Result<()> {
+        match format {
+            StatsFormat::Csv => self.write_csv(writer),
+            StatsFormat::Json => self.write_json(writer),
+        }
+    }
+
+    /// Saves the history to a file, choosing the format from the file extension.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the file, ending with `.csv` or `.json`.
+    ///
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let path = path.as_ref();
+        let format = StatsFormat::from_path(path).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Only .csv and .json files are supported",
+            )
+        })?;
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write(&mut writer, format)?;
+        writer.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cells() -> Cells {
+        Cells::from(vec![vec![0, 0, 0], vec![0, 1, 2], vec![0, 0, 1]])
+    }
+
+    #[test]
+    fn stats_of_cells() {
+        let stats = Stats::of(&cells(), 3, 7);
+        assert_eq!(stats.generation, 7);
+        assert_eq!(stats.counts, vec![6, 2, 1]);
+        assert_eq!(stats.population, 3);
+        assert_eq!(stats.density, 3.0 / 9.0);
+        assert_eq!(
+            stats.bounding_box,
+            Some(Region {
+                x: 1,
+                y: 1,
+                width: 2,
+                height: 2
+            })
+        );
+    }
+
+    #[test]
+    fn stats_of_empty_cells() {
+        let stats = Stats::of(&Cells::new(2), 2, 0);
+        assert_eq!(stats.counts, vec![4, 0]);
+        assert_eq!(stats.population, 0);
+        assert_eq!(stats.bounding_box, None);
+    }
+
+    #[test]
+    fn tally_transitions() {
+        let mut left = Tally::new(3);
+        left.transition(0, 0, 0, 1);
+        left.transition(0, 1, 1, 1);
+        let mut right = Tally::new(3);
+        right.transition(2, 2, 1, 2);
+        right.transition(2, 1, 0, 0);
+        left.merge(&right);
+
+        let stats = left.into_stats(1, 3);
+        assert_eq!(stats.counts, vec![1, 2, 1]);
+        assert_eq!((stats.births, stats.deaths, stats.survivors), (1, 1, 1));
+        assert_eq!(stats.bounding_box, Some(Region::full(3)));
+    }
+
+    #[test]
+    fn replace_shrinks_bounding_box() {
+        let mut cells = cells();
+        let mut stats = Stats::of(&cells, 3, 0);
+        cells[2][2] = 0;
+        stats.replace(&cells, 2, 2, 1);
+        assert_eq!(stats, Stats::of(&cells, 3, 0));
+
+        cells[0][0] = 2;
+        stats.replace(&cells, 0, 0, 0);
+        assert_eq!(stats, Stats::of(&cells, 3, 0));
+    }
+
+    #[test]
+    fn history_is_bounded() {
+        let mut history = StatsHistory::new(2);
+        for generation in 0..3 {
+            history.push(Stats::of(&cells(), 3, generation));
+        }
+        let generations: Vec<u64> = history.iter().map(|s| s.generation).collect();
+        assert_eq!(generations, vec![1, 2]);
+    }
+
+    #[test]
+    fn history_csv() {
+        let mut history = StatsHistory::new(4);
+        history.push(Stats::of(&cells(), 3, 0));
+        history.push(Stats::of(&Cells::new(3), 3, 1));
+        let mut buffer = Vec::new();
+        history.write_csv(&mut buffer).unwrap();
+        assert_eq!(
+            String::from_utf8(buffer).unwrap(),
+            "generation,population,density,births,deaths,survivors,bbox_x,bbox_y,bbox_width,bbox_height,state_0,state_1,state_2\n\
+             0,3,0.3333333333333333,0,0,0,1,1,2,2,6,2,1\n\
+             1,0,0,0,0,0,,,,,9,0,0\n"
+        );
+    }
+
+    #[test]
+    fn history_json() {
+        let mut history = StatsHistory::new(1);
+        history.push(Stats::of(&Cells::new(1), 2, 0));
+        let mut buffer = Vec::new();
+        history.write_json(&mut buffer).unwrap();
+        assert_eq!(
+            String::from_utf8(buffer).unwrap(),
+            r#"[{"generation":0,"counts":[1,0],"population":0,"density":0.0,"births":0,"deaths":0,"survivors":0,"bounding_box":null}]"#
+        );
+    }
+
+    #[test]
+    fn format_from_str() {
+        assert_eq!(StatsFormat::from_str("CSV"), Ok(StatsFormat::Csv));
+        assert_eq!(StatsFormat::from_str("json"), Ok(StatsFormat::Json));
+        assert_eq!(StatsFormat::from_str("xml"), Err(()));
+    }
+}
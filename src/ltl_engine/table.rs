@@ -0,0 +1,401 @@
+use crate::ltl_engine::cells::Cell;
+use crate::ltl_engine::config::Config;
+use crate::ltl_engine::neighbourhood::Neighbourhood;
+use crate::ltl_engine::Rule;
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+/// Digits of the next states in the text form of a table, the `n`th standing for state `n`.
+///
+const DIGITS: &[u8; 36] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+
+/// Errors raised by building or parsing a table rule.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub enum TableError {
+    /// Tables need between 2 and 36 states.
+    States(usize),
+    /// The neighbourhood has more cells than `Neighbourhood::area` counts.
+    Radius(u8),
+    /// The number of rows doesn't match the number of states.
+    Rows {
+        expected: usize,
+        actual: usize,
+    },
+    /// A row doesn't have one next state per neighbourhood count.
+    Counts {
+        state: Cell,
+        expected: usize,
+        actual: usize,
+    },
+    /// A transition leads to a state the table doesn't have, or starts from one.
+    State(usize),
+    /// A transition is given for a count above the area of the neighbourhood.
+    Count(usize),
+    /// The text form isn't `R{rr},C{states},M{mm},{NM|NN},T{rows}`.
+    Syntax,
+    Character(char),
+}
+
+impl fmt::Display for TableError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TableError::States(states) => {
+                write!(f, "Tables need 2 to 36 states, {} given", states)
+            }
+            TableError::Radius(rr) => write!(f, "Radius {} is too large for a table", rr),
+            TableError::Rows { expected, actual } => {
+                write!(
+                    f,
+                    "Expected {} rows, one per state, got {}",
+                    expected, actual
+                )
+            }
+            TableError::Counts {
+                state,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "Expected {} next states for state {}, one per count, got {}",
+                expected, state, actual
+            ),
+            TableError::State(state) => write!(f, "State {} is outside the table", state),
+            TableError::Count(count) => {
+                write!(f, "Count {} exceeds the area of the neighbourhood", count)
+            }
+            TableError::Syntax => write!(
+                f,
+                "Tables are written R{{rr}},C{{states}},M{{mm}},{{NM|NN}},T{{rows}}"
+            ),
+            TableError::Character(c) => write!(f, "Invalid character '{}' in table", c),
+        }
+    }
+}
+
+impl Error for TableError {}
+
+/// Outer totalistic rule looking the next state of a cell up in a table, by its state and
+/// the number of live cells in its neighbourhood. Neighbourhoods are those of Larger than
+/// Life.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableRule {
+    rr: u8,
+    mm: u8,
+    nn: Neighbourhood,
+    states: Cell,
+    /// Next states, `area + 1` per state indexed by the count.
+    table: Vec<Cell>,
+}
+
+impl TableRule {
+    /// Create a rule from its table.
+    ///
+    /// # Arguments
+    ///
+    /// * `rr` - The radius of the neighborhood.
+    /// * `mm` - Is the centre cell included in neighbourhood.
+    /// * `nn` - The neighborhood type.
+    /// * `rows` - The next states of every state, indexed by the number of live cells in the
+    ///   neighbourhood, `Neighbourhood::area + 1` of them per state.
+    ///
+    pub fn new(
+        rr: u8,
+        mm: u8,
+        nn: Neighbourhood,
+        rows: Vec<Vec<Cell>>,
+    ) -> Result<Self, TableError> {
+        let states = rows.len();
+        let counts = TableRule::counts(rr, mm, &nn, states)?;
+        for (state, row) in rows.iter().enumerate() {
+            if row.len() != counts {
+                return Err(TableError::Counts {
+                    state: state as Cell,
+                    expected: counts,
+                    actual: row.len(),
+                });
+            }
+            if let Some(&next) = row.iter().find(|&&next| next as usize >= states) {
+                return Err(TableError::State(next as usize));
+            }
+        }
+        Ok(TableRule {
+            rr,
+            mm,
+            nn,
+            states: states as Cell,
+            table: rows.concat(),
+        })
+    }
+
+    /// Create a rule from its transitions, transitions left out leading to state 0.
+    ///
+    /// # Arguments
+    ///
+    /// * `rr` - The radius of the neighborhood.
+    /// * `mm` - Is the centre cell included in neighbourhood.
+    /// * `nn` - The neighborhood type.
+    /// * `states` - The number of cell states.
+    /// * `transitions` - Next states by state and number of live cells in the neighbourhood.
+    ///
+    pub fn from_transitions<I>(
+        rr: u8,
+        mm: u8,
+        nn: Neighbourhood,
+        states: usize,
+        transitions: I,
+    ) -> Result<Self, TableError>
+    where
+        I: IntoIterator<Item = ((usize, usize), usize)>,
+    {
+        let counts = TableRule::counts(rr, mm, &nn, states)?;
+        let mut rows = vec![vec![0; counts]; states];
+        for ((state, count), next) in transitions {
+            if state >= states || next >= states {
+                return Err(TableError::State(state.max(next)));
+            }
+            if count >= counts {
+                return Err(TableError::Count(count));
+            }
+            rows[state][count] = next as Cell;
+        }
+        TableRule::new(rr, mm, nn, rows)
+    }
+
+    /// Returns the table of a Larger than Life rule, an error when its neighbourhood is too
+    /// large for a table.
+    ///
+    pub fn from_config(config: &Config) -> Result<Self, TableError> {
+        let counts = config.nn.area(config.rr, config.mm) as usize + 1;
+        let rows = (0..config.states())
+            .map(|state| {
+                (0..counts)
+                    .map(|count| config.next_state(state, &vec![1; count]))
+                    .collect()
+            })
+            .collect();
+        TableRule::new(config.rr, config.mm, config.nn.clone(), rows)
+    }
+
+    /// Returns the number of counts of a row, validating the size of the table.
+    ///
+    fn counts(rr: u8, mm: u8, nn: &Neighbourhood, states: usize) -> Result<usize, TableError> {
+        if !(2..=DIGITS.len()).contains(&states) {
+            return Err(TableError::States(states));
+        }
+        // `Neighbourhood::area` caps at 255 and overflows on large radii, the full area is
+        // checked first.
+        let r = rr as usize;
+        let full = match nn {
+            Neighbourhood::Moore => (2 * r + 1).pow(2),
+            Neighbourhood::Neumann => r.pow(2) + (r + 1).pow(2),
+        };
+        if full - (mm == 0) as usize > Cell::MAX as usize {
+            return Err(TableError::Radius(rr));
+        }
+        let r = rr as isize;
+        let cells = (-r..=r)
+            .flat_map(|dx| (-r..=r).map(move |dy| (dx, dy)))
+            .filter(|&(dx, dy)| nn.contains(rr, mm, dx, dy))
+            .count();
+        let area = nn.area(rr, mm) as usize;
+        if cells != area {
+            return Err(TableError::Radius(rr));
+        }
+        Ok(area + 1)
+    }
+
+    /// Returns the next state of a cell with `count` live cells in its neighbourhood.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the state or the count is outside the table.
+    ///
+    pub fn lookup(&self, state: Cell, count: usize) -> Cell {
+        let counts = self.table.len() / self.states as usize;
+        if count >= counts {
+            panic!("Count exceeds the area of the neighbourhood");
+        }
+        self.table[state as usize * counts + count]
+    }
+}
+
+impl Rule for TableRule {
+    fn states(&self) -> Cell {
+        self.states
+    }
+
+    fn radius(&self) -> usize {
+        self.rr as usize
+    }
+
+    fn contains(&self, dx: isize, dy: isize) -> bool {
+        self.nn.contains(self.rr, self.mm, dx, dy)
+    }
+
+    fn next_state(&self, state: Cell, neighbours: &[Cell]) -> Cell {
+        self.lookup(state, neighbours.iter().filter(|&&s| s > 0).count())
+    }
+}
+
+impl fmt::Display for TableRule {
+    /// Formats the rule as `R{rr},C{states},M{mm},{nn},T{rows}`, rows being separated by `/`
+    /// and holding one digit per count, e.g. `R1,C2,M0,NM,T000100000/001100000` for Life.
+    ///
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rows: Vec<String> = self
+            .table
+            .chunks(self.table.len() / self.states as usize)
+            .map(|row| row.iter().map(|&s| DIGITS[s as usize] as char).collect())
+            .collect();
+        write!(
+            f,
+            "R{},C{},M{},{},T{}",
+            self.rr,
+            self.states,
+            self.mm,
+            self.nn,
+            rows.join("/")
+        )
+    }
+}
+
+impl FromStr for TableRule {
+    type Err = TableError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let fields: Vec<&str> = s.trim().split(',').collect();
+        let [rr, states, mm, nn, table] = fields[..] else {
+            return Err(TableError::Syntax);
+        };
+        let number = |field: &str, prefix: char| {
+            field
+                .strip_prefix(prefix)
+                .and_then(|n| n.parse::<u8>().ok())
+                .ok_or(TableError::Syntax)
+        };
+        let (rr, states, mm) = (number(rr, 'R')?, number(states, 'C')?, number(mm, 'M')?);
+        let nn = Neighbourhood::from_str(nn).map_err(|_| TableError::Syntax)?;
+        let rows = table
+            .strip_prefix('T')
+            .ok_or(TableError::Syntax)?
+            .split('/')
+            .map(|row| {
+                row.chars()
+                    .map(|c| match DIGITS.iter().position(|&d| d as char == c) {
+                        Some(state) => Ok(state as Cell),
+                        None => Err(TableError::Character(c)),
+                    })
+                    .collect()
+            })
+            .collect::<Result<Vec<Vec<Cell>>, TableError>>()?;
+        if rows.len() != states as usize {
+            return Err(TableError::Rows {
+                expected: states as usize,
+                actual: rows.len(),
+            });
+        }
+        TableRule::new(rr, mm, nn, rows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ltl_engine::board::Board;
+
+    const LIFE: &str = "R1,C2,M0,NM,T000100000/001100000";
+
+    #[test]
+    fn life_table() {
+        let config = Config::new(1, 0, 0, (2, 3), (3, 3), Neighbourhood::Moore);
+        let table = TableRule::from_config(&config).unwrap();
+        assert_eq!(table.to_string(), LIFE);
+        assert_eq!(LIFE.parse::<TableRule>(), Ok(table.clone()));
+        assert_eq!(table.lookup(0, 3), 1);
+        assert_eq!(table.lookup(1, 4), 0);
+
+        let transitions = [((0, 3), 1), ((1, 2), 1), ((1, 3), 1)];
+        assert_eq!(
+            TableRule::from_transitions(1, 0, Neighbourhood::Moore, 2, transitions),
+            Ok(table.clone())
+        );
+
+        let mut ltl = Board::new(16, config);
+        ltl.randomize(Some(7));
+        let mut board = Board::new(16, table);
+        board.set_cells(ltl.cells.as_slice()).unwrap();
+        for _ in 0..8 {
+            ltl.update();
+            board.update();
+        }
+        assert_eq!(board.cells, ltl.cells);
+    }
+
+    #[test]
+    fn generations_and_neumann_tables() {
+        let config = Config::new(2, 4, 1, (3, 6), (4, 5), Neighbourhood::Neumann);
+        let table = TableRule::from_config(&config).unwrap();
+        assert_eq!(table.to_string().split('/').count(), 4);
+        assert_eq!(table.to_string().parse::<TableRule>(), Ok(table.clone()));
+        for state in 0..4 {
+            for count in 0..=13 {
+                assert_eq!(
+                    table.lookup(state, count),
+                    config.next_state(state, &vec![1; count])
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn invalid_tables() {
+        let moore = || Neighbourhood::Moore;
+        assert_eq!(
+            TableRule::new(1, 0, moore(), vec![vec![0; 9]]),
+            Err(TableError::States(1))
+        );
+        assert_eq!(
+            TableRule::new(1, 0, moore(), vec![vec![0; 9], vec![0; 8]]),
+            Err(TableError::Counts {
+                state: 1,
+                expected: 9,
+                actual: 8
+            })
+        );
+        assert_eq!(
+            TableRule::new(1, 0, moore(), vec![vec![0; 9], vec![2; 9]]),
+            Err(TableError::State(2))
+        );
+        assert_eq!(
+            TableRule::new(8, 1, moore(), vec![vec![0; 290]; 2]),
+            Err(TableError::Radius(8))
+        );
+        assert_eq!(
+            "R200,C2,M0,NM,T0/0".parse::<TableRule>(),
+            Err(TableError::Radius(200))
+        );
+        assert_eq!(
+            TableRule::new(11, 0, Neighbourhood::Neumann, vec![vec![0; 265]; 2]),
+            Err(TableError::Radius(11))
+        );
+        assert_eq!(
+            TableRule::from_transitions(1, 0, moore(), 2, [((0, 9), 1)]),
+            Err(TableError::Count(9))
+        );
+        assert_eq!("R1,C2,M0,NM".parse::<TableRule>(), Err(TableError::Syntax));
+        assert_eq!(
+            "R1,C2,M0,NM,T000100000".parse::<TableRule>(),
+            Err(TableError::Rows {
+                expected: 2,
+                actual: 1
+            })
+        );
+        assert_eq!(
+            "R1,C2,M0,NM,T000100000/00110000!".parse::<TableRule>(),
+            Err(TableError::Character('!'))
+        );
+    }
+}
@@ -0,0 +1,337 @@
+use crate::ltl_engine::board::Board;
+use crate::ltl_engine::cells::Cell;
+use crate::ltl_engine::hash::{add, mul, pow, sub};
+use crate::ltl_engine::pattern::{Pattern, Symmetry};
+
+/// Base of the rolling hash of the cells of a column.
+const BASE: u64 = 0x0d6f_91a3_c84e_7b15;
+
+/// A rectangular pattern to look for on a board, whose cells either have to match a state
+/// or match anything. Cells are stored column by column, like the cells of a board.
+///
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Template {
+    width: usize,
+    height: usize,
+    cells: Vec<Option<Cell>>,
+}
+
+impl Template {
+    /// Create a template of cells matching anything.
+    ///
+    pub fn new(width: usize, height: usize) -> Self {
+        Template {
+            width,
+            height,
+            cells: vec![None; width * height],
+        }
+    }
+
+    /// Create a template from rows of cells, `None` matching anything. Short rows are padded
+    /// with cells matching anything.
+    ///
+    pub fn from_rows(rows: &[Vec<Option<Cell>>]) -> Self {
+        let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+        let mut template = Template::new(width, rows.len());
+        for (y, row) in rows.iter().enumerate() {
+            for (x, &state) in row.iter().enumerate() {
+                template.set(x, y, state);
+            }
+        }
+        template
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<Cell> {
+        self.cells[x * self.height + y]
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, state: Option<Cell>) {
+        self.cells[x * self.height + y] = state;
+    }
+
+    /// Returns the template transformed by a symmetry of the square.
+    ///
+    pub fn transform(&self, symmetry: Symmetry) -> Template {
+        let mut template = if symmetry.swaps_axes() {
+            Template::new(self.height, self.width)
+        } else {
+            Template::new(self.width, self.height)
+        };
+        for x in 0..self.width {
+            for y in 0..self.height {
+                let (tx, ty) = symmetry.apply(x, y, self.width, self.height);
+                template.set(tx, ty, self.get(x, y));
+            }
+        }
+        template
+    }
+
+    /// Returns the longest vertical run of cells to match as its column, first row and
+    /// length, preferring runs holding live cells since dead ones match most of a board.
+    ///
+    fn key(&self) -> Option<(usize, usize, usize)> {
+        let mut runs = Vec::new();
+        for x in 0..self.width {
+            let mut y = 0;
+            while y < self.height {
+                if self.get(x, y).is_none() {
+                    y += 1;
+                    continue;
+                }
+                let start = y;
+                let mut live = false;
+                while let Some(state) = (y < self.height).then(|| self.get(x, y)).flatten() {
+                    live |= state != 0;
+                    y += 1;
+                }
+                runs.push((live, y - start, x, start));
+            }
+        }
+        runs.into_iter()
+            .max_by_key(|&(live, length, _, _)| (live, length))
+            .map(|(_, length, x, start)| (x, start, length))
+    }
+}
+
+impl From<&Pattern> for Template {
+    /// Create a template matching every cell of a pattern.
+    ///
+    fn from(pattern: &Pattern) -> Self {
+        let mut template = Template::new(pattern.width(), pattern.height());
+        for x in 0..pattern.width() {
+            for y in 0..pattern.height() {
+                template.set(x, y, Some(pattern.get(x, y)));
+            }
+        }
+        template
+    }
+}
+
+/// An occurrence of a template on a board.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Match {
+    /// Left column of the transformed template.
+    pub x: usize,
+    /// Top row of the transformed template.
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+    /// The symmetry turning the template into the occurrence.
+    pub symmetry: Symmetry,
+}
+
+/// Returns the hash of a cell, offset so dead cells change the hash too.
+///
+#[inline]
+fn cell_hash(state: Cell) -> u64 {
+    state as u64 + 1
+}
+
+impl Board {
+    /// Returns every occurrence of a template on the board, under any of the given
+    /// symmetries. Orientations of the template equal to an earlier one are skipped, so a
+    /// symmetric template matches once per position. Matches are sorted by position.
+    ///
+    /// Every orientation is looked for with a rolling hash of the columns of the board,
+    /// positions whose column hash matches the longest run of the template being checked
+    /// cell by cell, which keeps a search cheap enough to run every generation.
+    ///
+    /// # Arguments
+    ///
+    /// * `template` - The cells to look for.
+    /// * `symmetries` - The orientations of the template to look for.
+    ///
+    pub fn find_pattern(&self, template: &Template, symmetries: &[Symmetry]) -> Vec<Match> {
+        let mut orientations: Vec<(Symmetry, Template)> = Vec::new();
+        for &symmetry in symmetries {
+            let oriented = template.transform(symmetry);
+            if orientations.iter().all(|(_, other)| *other != oriented) {
+                orientations.push((symmetry, oriented));
+            }
+        }
+        let mut matches = Vec::new();
+        for (symmetry, oriented) in &orientations {
+            self.find_oriented(oriented, *symmetry, &mut matches);
+        }
+        matches.sort_by_key(|found| (found.y, found.x));
+        matches
+    }
+
+    /// Pushes the occurrences of one orientation of a template.
+    ///
+    fn find_oriented(&self, template: &Template, symmetry: Symmetry, matches: &mut Vec<Match>) {
+        let size = self.cells.len();
+        let (width, height) = (template.width(), template.height());
+        if width > size || height > size {
+            return;
+        }
+        let found = |x, y| Match {
+            x,
+            y,
+            width,
+            height,
+            symmetry,
+        };
+        let Some((kx, ky, length)) = template.key() else {
+            for x in 0..=size - width {
+                matches.extend((0..=size - height).map(|y| found(x, y)));
+            }
+            return;
+        };
+        let cared: Vec<(usize, usize, Cell)> = (0..width)
+            .flat_map(|x| (0..height).map(move |y| (x, y)))
+            .filter_map(|(x, y)| template.get(x, y).map(|state| (x, y, state)))
+            .collect();
+        let key = (ky..ky + length).fold(0, |hash, y| {
+            add(mul(hash, BASE), cell_hash(template.get(kx, y).unwrap()))
+        });
+        let top = pow(BASE, length as u64 - 1);
+        for x in 0..=size - width {
+            let column = &self.cells[x + kx];
+            let mut hash = 0;
+            for (y, &state) in column.iter().enumerate() {
+                if y >= length {
+                    hash = sub(hash, mul(top, cell_hash(column[y - length])));
+                }
+                hash = add(mul(hash, BASE), cell_hash(state));
+                if y + 1 < length || hash != key {
+                    continue;
+                }
+                let Some(oy) = (y + 1 - length).checked_sub(ky) else {
+                    continue;
+                };
+                if oy + height > size {
+                    break;
+                }
+                let matched = cared
+                    .iter()
+                    .all(|&(dx, dy, state)| self.cells[x + dx][oy + dy] == state);
+                if matched {
+                    matches.push(found(x, oy));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ltl_engine::config::Config;
+    use crate::ltl_engine::neighbourhood::Neighbourhood;
+
+    fn life() -> Config {
+        Config::new(1, 0, 0, (2, 3), (3, 3), Neighbourhood::Moore)
+    }
+
+    fn glider() -> Pattern {
+        let mut glider = Pattern::new(3, 3);
+        for (x, y) in [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)] {
+            glider.set(x, y, 1);
+        }
+        glider
+    }
+
+    #[test]
+    fn track_glider() {
+        let mut board = Board::new(24, life());
+        glider().place(&mut board, 2, 2);
+        let template = Template::from(&glider());
+        for generation in 0..4 {
+            let found = board.find_pattern(&template, &[Symmetry::Identity]);
+            if generation == 0 {
+                let expected = Match {
+                    x: 2,
+                    y: 2,
+                    width: 3,
+                    height: 3,
+                    symmetry: Symmetry::Identity,
+                };
+                assert_eq!(found, vec![expected]);
+            } else {
+                assert!(found.is_empty());
+            }
+            board.update();
+        }
+        // The glider moved by one cell diagonally.
+        let found = board.find_pattern(&template, &[Symmetry::Identity]);
+        assert_eq!((found[0].x, found[0].y), (3, 3));
+    }
+
+    #[test]
+    fn symmetries_and_wildcards() {
+        let mut board = Board::new(16, life());
+        glider().place(&mut board, 1, 1);
+        glider()
+            .transform(Symmetry::Rotate90)
+            .place(&mut board, 8, 2);
+        glider().transform(Symmetry::FlipX).place(&mut board, 3, 10);
+        let template = Template::from(&glider());
+        let found = board.find_pattern(&template, &Symmetry::ALL);
+        let found: Vec<_> = found
+            .iter()
+            .map(|found| (found.x, found.y, found.symmetry))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (1, 1, Symmetry::Identity),
+                (8, 2, Symmetry::Rotate90),
+                (3, 10, Symmetry::FlipX),
+            ]
+        );
+
+        // A template of the top row of any glider facing down matches the glider and
+        // nothing else, the rotated one having the live cell elsewhere.
+        let mut top = Template::new(3, 3);
+        for (x, state) in [(0, 0), (1, 1), (2, 0)] {
+            top.set(x, 0, Some(state));
+        }
+        let found = board.find_pattern(&top, &[Symmetry::Identity]);
+        assert!(found.iter().any(|found| (found.x, found.y) == (1, 1)));
+        assert!(found.iter().all(|found| (found.x, found.y) != (8, 2)));
+
+        // A symmetric template matches once per position.
+        let mut block = Pattern::new(2, 2);
+        for (x, y) in [(0, 0), (0, 1), (1, 0), (1, 1)] {
+            block.set(x, y, 1);
+        }
+        let mut board = Board::new(8, life());
+        block.place(&mut board, 6, 6);
+        let found = board.find_pattern(&Template::from(&block), &Symmetry::ALL);
+        assert_eq!(found.len(), 1);
+        assert_eq!((found[0].x, found[0].y), (6, 6));
+    }
+
+    #[test]
+    fn multistate_and_edge_cases() {
+        let config = Config::new(1, 3, 0, (2, 3), (3, 3), Neighbourhood::Moore);
+        let mut board = Board::new(6, config);
+        board.set_cell(2, 3, 2);
+        board.set_cell(3, 3, 1);
+        let template = Template::from_rows(&[vec![Some(2), Some(1)]]);
+        let found = board.find_pattern(&template, &[Symmetry::Identity]);
+        assert_eq!((found.len(), found[0].x, found[0].y), (1, 2, 3));
+        let found = board.find_pattern(&template, &[Symmetry::FlipX]);
+        assert!(found.is_empty());
+
+        assert!(board
+            .find_pattern(&Template::new(7, 1), &[Symmetry::Identity])
+            .is_empty());
+        assert_eq!(
+            board
+                .find_pattern(&Template::new(5, 6), &[Symmetry::Identity])
+                .len(),
+            2
+        );
+    }
+}
@@ -0,0 +1,339 @@
+use crate::ltl_engine::board::Board;
+use crate::ltl_engine::image::{Palette, Rgba};
+use crate::ltl_engine::cells::Region;
+use crate::ltl_engine::Rule;
+use std::cmp::max;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::str::FromStr;
+
+const Y4M: &str = "y4m";
+const RGB: &str = "rgb";
+
+/// Supported video formats.
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum VideoFormat {
+    /// YUV4MPEG2 stream with 4:4:4 chroma.
+    Y4m,
+    /// Headerless stream of packed 24-bit RGB frames.
+    RawRgb,
+}
+
+impl FromStr for VideoFormat {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            Y4M => Ok(VideoFormat::Y4m),
+            RGB => Ok(VideoFormat::RawRgb),
+            _ => Err(()),
+        }
+    }
+}
+
+impl VideoFormat {
+    /// Guesses the video format from the extension of the given path.
+    ///
+    pub fn from_path(path: &Path) -> Option<Self> {
+        path.extension()?.to_str()?.parse().ok()
+    }
+}
+
+/// Options of a video stream.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct VideoOptions {
+    /// Frames per second written to the stream header.
+    pub fps: u32,
+    /// The side of the square of pixels drawn for every cell.
+    pub scale: usize,
+    /// Region of the board to record, the whole board when `None`.
+    pub region: Option<Region>,
+}
+
+impl Default for VideoOptions {
+    fn default() -> Self {
+        VideoOptions {
+            fps: 30,
+            scale: 1,
+            region: None,
+        }
+    }
+}
+
+/// Writes generations of a board as video frames, one frame at a time.
+///
+pub struct VideoWriter<W: Write> {
+    writer: W,
+    format: VideoFormat,
+    region: Region,
+    scale: usize,
+    /// Output triple (RGB or YCbCr) of every state.
+    colors: Vec<[u8; 3]>,
+    row: Vec<u8>,
+    frames: usize,
+}
+
+impl<W: Write> VideoWriter<W> {
+    /// Creates a new video writer and writes the stream header.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - The destination of the stream.
+    /// * `format` - The video format.
+    /// * `palette` - The colours of the cell states.
+    /// * `options` - Video options.
+    /// * `size` - The size of the recorded board.
+    ///
+    pub fn new(
+        mut writer: W,
+        format: VideoFormat,
+        palette: &Palette,
+        options: &VideoOptions,
+        size: usize,
+    ) -> io::Result<Self> {
+        let region = options.region.unwrap_or(Region::full(size)).clamp(size);
+        if region.width == 0 || region.height == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Recorded region is empty",
+            ));
+        }
+        let scale = max(options.scale, 1);
+        let colors = palette
+            .colors()
+            .iter()
+            .map(|&c| match format {
+                VideoFormat::Y4m => to_ycbcr(c),
+                VideoFormat::RawRgb => [c[0], c[1], c[2]],
+            })
+            .collect();
+        if format == VideoFormat::Y4m {
+            writeln!(
+                writer,
+                "YUV4MPEG2 W{} H{} F{}:1 Ip A1:1 C444",
+                region.width * scale,
+                region.height * scale,
+                max(options.fps, 1)
+            )?;
+        }
+        Ok(VideoWriter {
+            writer,
+            format,
+            region,
+            scale,
+            colors,
+            row: Vec::with_capacity(region.width * scale * 3),
+            frames: 0,
+        })
+    }
+
+    /// Writes the current state of the board as the next frame.
+    ///
+    pub fn write_frame<R: Rule>(&mut self, board: &Board<R>) -> io::Result<()> {
+        let frame = board.capture(&self.region);
+        match self.format {
+            VideoFormat::Y4m => {
+                self.writer.write_all(b"FRAME\n")?;
+                for plane in 0..3 {
+                    self.write_rows(&frame, |color, row| row.push(color[plane]))?;
+                }
+            }
+            VideoFormat::RawRgb => {
+                self.write_rows(&frame, |color, row| row.extend_from_slice(color))?;
+            }
+        }
+        self.frames += 1;
+        Ok(())
+    }
+
+    /// Writes every row of the frame, pushing the bytes of a pixel with `push`.
+    ///
+    fn write_rows<F: Fn(&[u8; 3], &mut Vec<u8>)>(
+        &mut self,
+        frame: &[u8],
+        push: F,
+    ) -> io::Result<()> {
+        let last = self.colors.len() - 1;
+        for cells in frame.chunks_exact(self.region.width) {
+            self.row.clear();
+            for &state in cells {
+                let color = &self.colors[(state as usize).min(last)];
+                for _ in 0..self.scale {
+                    push(color, &mut self.row);
+                }
+            }
+            for _ in 0..self.scale {
+                self.writer.write_all(&self.row)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the number of frames written so far.
+    ///
+    pub fn frames(&self) -> usize {
+        self.frames
+    }
+
+    /// Flushes the stream and returns the underlying writer.
+    ///
+    pub fn into_inner(mut self) -> io::Result<W> {
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+/// Converts a colour to BT.601 limited range YCbCr.
+///
+fn to_ycbcr(color: Rgba) -> [u8; 3] {
+    let [r, g, b] = [color[0] as i32, color[1] as i32, color[2] as i32];
+    let y = ((66 * r + 129 * g + 25 * b + 128) >> 8) + 16;
+    let cb = ((-38 * r - 74 * g + 112 * b + 128) >> 8) + 128;
+    let cr = ((112 * r - 94 * g - 18 * b + 128) >> 8) + 128;
+    [y as u8, cb as u8, cr as u8]
+}
+
+impl<R: Rule> Board<R> {
+    /// Run the simulation, streaming every generation as a video frame.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - The destination of the stream.
+    /// * `format` - The video format.
+    /// * `palette` - The colours of the cell states.
+    /// * `options` - Video options.
+    /// * `generations` - Number of generations to simulate.
+    ///
+    /// # Returns
+    ///
+    /// The number of frames written, including the initial generation.
+    ///
+    pub fn stream_video<W: Write>(
+        &mut self,
+        writer: W,
+        format: VideoFormat,
+        palette: &Palette,
+        options: &VideoOptions,
+        generations: usize,
+    ) -> io::Result<usize> {
+        let mut video = VideoWriter::new(writer, format, palette, options, self.cells.len())?;
+        video.write_frame(self)?;
+        for _ in 0..generations {
+            self.update();
+            video.write_frame(self)?;
+        }
+        let frames = video.frames();
+        video.into_inner()?;
+        Ok(frames)
+    }
+
+    /// Run the simulation and stream it to a file, choosing the format from the file
+    /// extension.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the file, ending with `.y4m` or `.rgb`.
+    /// * `palette` - The colours of the cell states.
+    /// * `options` - Video options.
+    /// * `generations` - Number of generations to simulate.
+    ///
+    /// # Returns
+    ///
+    /// The number of frames written, including the initial generation.
+    ///
+    pub fn save_video<P: AsRef<Path>>(
+        &mut self,
+        path: P,
+        palette: &Palette,
+        options: &VideoOptions,
+        generations: usize,
+    ) -> io::Result<usize> {
+        let path = path.as_ref();
+        let format = VideoFormat::from_path(path).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Only .y4m and .rgb files are supported",
+            )
+        })?;
+        let writer = BufWriter::new(File::create(path)?);
+        self.stream_video(writer, format, palette, options, generations)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ltl_engine::config::Config;
+    use crate::ltl_engine::image::{BLACK, WHITE};
+    use crate::ltl_engine::neighbourhood::Neighbourhood;
+
+    fn blinker_board() -> Board {
+        let config = Config::new(1, 0, 0, (2, 3), (3, 3), Neighbourhood::Moore);
+        let mut board = Board::new(5, config);
+        for x in 1..4 {
+            board.set_cell(x, 2, 1);
+        }
+        board
+    }
+
+    fn palette() -> Palette {
+        Palette::new(vec![WHITE, BLACK])
+    }
+
+    #[test]
+    fn format_from_str() {
+        assert_eq!(VideoFormat::from_str("Y4M"), Ok(VideoFormat::Y4m));
+        assert_eq!(VideoFormat::from_str("rgb"), Ok(VideoFormat::RawRgb));
+        assert_eq!(VideoFormat::from_str("avi"), Err(()));
+    }
+
+    #[test]
+    fn ycbcr_black_and_white() {
+        assert_eq!(to_ycbcr(BLACK), [16, 128, 128]);
+        assert_eq!(to_ycbcr(WHITE), [235, 128, 128]);
+    }
+
+    #[test]
+    fn stream_y4m() {
+        let mut board = blinker_board();
+        let mut buffer = Vec::new();
+        let options = VideoOptions {
+            fps: 25,
+            scale: 2,
+            ..VideoOptions::default()
+        };
+        let frames = board
+            .stream_video(&mut buffer, VideoFormat::Y4m, &palette(), &options, 3)
+            .unwrap();
+
+        let header = b"YUV4MPEG2 W10 H10 F25:1 Ip A1:1 C444\n";
+        assert_eq!(frames, 4);
+        assert!(buffer.starts_with(header));
+        assert_eq!(buffer.len(), header.len() + 4 * (6 + 3 * 10 * 10));
+    }
+
+    #[test]
+    fn stream_raw_rgb_region() {
+        let mut board = blinker_board();
+        let mut buffer = Vec::new();
+        let options = VideoOptions {
+            region: Some(Region {
+                x: 1,
+                y: 2,
+                width: 3,
+                height: 1,
+            }),
+            ..VideoOptions::default()
+        };
+        board
+            .stream_video(&mut buffer, VideoFormat::RawRgb, &palette(), &options, 1)
+            .unwrap();
+
+        let mut right = [0; 9].to_vec();
+        right.extend_from_slice(&[255, 255, 255, 0, 0, 0, 255, 255, 255]);
+        assert_eq!(buffer, right);
+    }
+}
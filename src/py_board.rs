@@ -1,8 +1,34 @@
+use pyo3::buffer::PyBuffer;
+use pyo3::exceptions;
+use pyo3::ffi;
 use pyo3::prelude::*;
+use pyo3::types::{IntoPyDict, PyBytes};
+use pyo3::AsPyPointer;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::os::raw::{c_char, c_int, c_void};
+use std::ptr;
+use std::str::FromStr;
 
+use crate::ltl_engine::activity::{Activity, ActivityLayer};
 use crate::ltl_engine::board::Board;
+use crate::ltl_engine::census::{CensusOptions, ObjectKind};
+use crate::ltl_engine::damage::{twin_run, Perturbation};
+use crate::ltl_engine::image::{
+    Fit, Image, ImageError, ImageFormat, Palette, Quantize, MAX_COLORS,
+};
+use crate::ltl_engine::cells::{Cell, Region};
+use crate::ltl_engine::pattern::{Pattern, Symmetry};
+use crate::ltl_engine::recorder::{AnimationFormat, RecordOptions};
+use crate::ltl_engine::search::{Predecessor, PredecessorOptions};
+use crate::ltl_engine::stats::{Stats, StatsFormat, StatsHistory};
+use crate::ltl_engine::template::Template;
+use crate::ltl_engine::video::{VideoFormat, VideoOptions};
 use crate::py_config::PyConfig;
 
+/// Buffer format of a cell, an unsigned byte.
+const CELL_FORMAT: &[u8] = b"B\0";
+
 #[pyclass]
 #[derive(Debug, PartialEq)]
 pub struct PyBoard {
@@ -18,13 +44,145 @@ impl PyBoard {
         }
     }
 
+    #[staticmethod]
+    #[pyo3(signature = (source, size, config, fit="scale", threshold=128, palette=None))]
+    fn from_image(
+        source: &PyAny,
+        size: u64,
+        config: &PyConfig,
+        fit: &str,
+        threshold: u8,
+        palette: Option<Vec<Vec<u8>>>,
+    ) -> PyResult<Self> {
+        let image = match source.extract::<&str>() {
+            Ok(path) => Image::load(path)?,
+            Err(_) => Image::decode(source.extract::<&[u8]>()?)?,
+        };
+        let fit = Fit::from_str(fit).map_err(|_| {
+            PyErr::new::<exceptions::PyValueError, _>("scale or crop options are available")
+        })?;
+        let quantize = match palette {
+            None => Quantize::Threshold(threshold),
+            Some(colors) => Quantize::Nearest(to_palette(colors)?),
+        };
+        Ok(PyBoard {
+            board: Board::from_image(&image, size, config.config.clone(), fit, &quantize)?,
+        })
+    }
+
     #[getter]
     fn board(&self) -> PyObject {
         Python::with_gil(|py| {
-            self.board.cells.to_object(py)
+            self.board.cells.to_vec().to_object(py)
         })
     }
 
+    /// Exposes the cells as a read-only `size` by `size` buffer of unsigned bytes, so
+    /// `numpy.asarray(board)` views the board without copying. Writes go through
+    /// `set_cells`, which validates states.
+    unsafe fn __getbuffer__(
+        slf: &PyCell<Self>,
+        view: *mut ffi::Py_buffer,
+        flags: c_int,
+    ) -> PyResult<()> {
+        if view.is_null() {
+            return Err(exceptions::PyBufferError::new_err("View is null"));
+        }
+        if flags & ffi::PyBUF_WRITABLE == ffi::PyBUF_WRITABLE {
+            return Err(exceptions::PyBufferError::new_err(
+                "Board views are read-only, use set_cells",
+            ));
+        }
+        let board = slf.try_borrow()?;
+        let cells = &board.board.cells;
+        let size = cells.len() as isize;
+        // Shape and strides are released in `__releasebuffer__`.
+        let layout = Box::into_raw(Box::new([size, size, size, 1]));
+
+        ffi::Py_INCREF(slf.as_ptr());
+        (*view).obj = slf.as_ptr();
+        (*view).buf = cells.as_slice().as_ptr() as *mut c_void;
+        (*view).len = size * size;
+        (*view).readonly = 1;
+        (*view).itemsize = 1;
+        (*view).format = if flags & ffi::PyBUF_FORMAT == ffi::PyBUF_FORMAT {
+            CELL_FORMAT.as_ptr() as *mut c_char
+        } else {
+            ptr::null_mut()
+        };
+        if flags & ffi::PyBUF_ND == ffi::PyBUF_ND {
+            (*view).ndim = 2;
+            (*view).shape = (*layout).as_mut_ptr();
+        } else {
+            (*view).ndim = 1;
+            (*view).shape = ptr::null_mut();
+        }
+        (*view).strides = if flags & ffi::PyBUF_STRIDES == ffi::PyBUF_STRIDES {
+            (*layout).as_mut_ptr().add(2)
+        } else {
+            ptr::null_mut()
+        };
+        (*view).suboffsets = ptr::null_mut();
+        (*view).internal = layout as *mut c_void;
+        Ok(())
+    }
+
+    unsafe fn __releasebuffer__(&self, view: *mut ffi::Py_buffer) {
+        drop(Box::from_raw((*view).internal as *mut [isize; 4]));
+    }
+
+    #[getter]
+    fn __array_interface__(&self, py: Python) -> PyObject {
+        let size = self.board.cells.len();
+        let data = self.board.cells.as_slice().as_ptr() as usize;
+        let key_vals = &[
+            ("shape", (size, size).to_object(py)),
+            ("typestr", "|u1".to_object(py)),
+            ("data", (data, true).to_object(py)),
+            ("version", 3.to_object(py)),
+        ];
+        key_vals.into_py_dict(py).into()
+    }
+
+    fn set_cells(&mut self, py: Python, cells: &PyAny) -> PyResult<()> {
+        let size = self.board.cells.len();
+        let shape_error = || {
+            PyErr::new::<exceptions::PyValueError, _>(format!(
+                "Expected cells of shape ({}, {})",
+                size, size
+            ))
+        };
+        let values = match PyBuffer::<u8>::get(cells) {
+            Ok(buffer) => {
+                if buffer.shape() != [size, size] {
+                    return Err(shape_error());
+                }
+                buffer.to_vec(py)?
+            }
+            Err(_) => {
+                let columns: Vec<Vec<i64>> = cells.extract()?;
+                if columns.len() != size || columns.iter().any(|c| c.len() != size) {
+                    return Err(shape_error());
+                }
+                columns
+                    .into_iter()
+                    .flatten()
+                    .map(|value| {
+                        u8::try_from(value).map_err(|_| {
+                            PyErr::new::<exceptions::PyValueError, _>(format!(
+                                "Config doesnt support state {}",
+                                value
+                            ))
+                        })
+                    })
+                    .collect::<PyResult<Vec<u8>>>()?
+            }
+        };
+        self.board
+            .set_cells(&values)
+            .map_err(|e| PyErr::new::<exceptions::PyValueError, _>(e.to_string()))
+    }
+
     fn reset(&mut self) -> () {
         self.board.reset();
     }
@@ -54,4 +212,729 @@ impl PyBoard {
     fn update(&mut self) -> () {
         self.board.update()
     }
+
+    #[getter]
+    fn generation(&self) -> u64 {
+        self.board.generation()
+    }
+
+    #[getter]
+    fn stats(&self, py: Python) -> PyObject {
+        to_stats_dict(py, self.board.stats())
+    }
+
+    fn refresh_stats(&mut self) {
+        self.board.refresh_stats()
+    }
+
+    fn record_stats(&mut self, capacity: usize) {
+        self.board.record_stats(capacity)
+    }
+
+    #[pyo3(signature = (format=None))]
+    fn stats_history(&self, py: Python, format: Option<&str>) -> PyResult<PyObject> {
+        let history = self.history()?;
+        match format {
+            None => Ok(history
+                .iter()
+                .map(|stats| to_stats_dict(py, stats))
+                .collect::<Vec<PyObject>>()
+                .to_object(py)),
+            Some(format) => {
+                let format = StatsFormat::from_str(format).map_err(|_| {
+                    PyErr::new::<exceptions::PyValueError, _>("csv or json formats are available")
+                })?;
+                let mut buffer = Vec::new();
+                history.write(&mut buffer, format)?;
+                Ok(String::from_utf8_lossy(&buffer).to_object(py))
+            }
+        }
+    }
+
+    fn save_stats(&self, path: &str) -> PyResult<()> {
+        Ok(self.history()?.save(path)?)
+    }
+
+    fn explain_cell(&self, py: Python, x: usize, y: usize) -> PyResult<PyObject> {
+        let size = self.board.cells.len();
+        if x >= size || y >= size {
+            return Err(PyErr::new::<exceptions::PyValueError, _>(format!(
+                "Cell ({}, {}) is outside the board of size {}",
+                x, y, size
+            )));
+        }
+        let explanation = self.board.explain_cell(x, y);
+        let key_vals = &[
+            ("state", explanation.state.to_object(py)),
+            ("neighbours", explanation.neighbours.to_object(py)),
+            ("count", explanation.count.to_object(py)),
+            ("condition", explanation.condition.name().to_object(py)),
+            ("range", explanation.range.to_object(py)),
+            ("satisfied", explanation.satisfied.to_object(py)),
+            ("next_state", explanation.next_state.to_object(py)),
+        ];
+        Ok(key_vals.into_py_dict(py).into())
+    }
+
+    fn neighbour_counts(&self, py: Python) -> PyObject {
+        let counts = py.allow_threads(|| self.board.neighbour_counts());
+        to_columns(py, &counts, self.board.cells.len())
+    }
+
+    #[pyo3(signature = (enabled=true))]
+    fn record_activity(&mut self, enabled: bool) {
+        self.board.record_activity(enabled)
+    }
+
+    fn reset_activity(&mut self) {
+        self.board.reset_activity()
+    }
+
+    #[pyo3(signature = (layer="changes"))]
+    fn activity(&self, py: Python, layer: &str) -> PyResult<PyObject> {
+        let activity = self.recorded_activity()?;
+        let size = activity.size();
+        Ok(match to_activity_layer(layer)? {
+            ActivityLayer::LastActive => to_columns(py, activity.last_active(), size),
+            layer => to_columns(py, &activity.values(layer), size),
+        })
+    }
+
+    fn activity_summary(&self, py: Python) -> PyResult<PyObject> {
+        let activity = self.recorded_activity()?;
+        let reach = activity.reach().map(|b| (b.x, b.y, b.width, b.height));
+        let key_vals = &[
+            ("since", activity.since().to_object(py)),
+            ("generations", activity.generations().to_object(py)),
+            ("reach", reach.to_object(py)),
+        ];
+        Ok(key_vals.into_py_dict(py).into())
+    }
+
+    #[pyo3(signature = (layer="changes", scale=1, format=None))]
+    fn activity_heatmap(
+        &self,
+        py: Python,
+        layer: &str,
+        scale: usize,
+        format: Option<&str>,
+    ) -> PyResult<PyObject> {
+        let image = self
+            .recorded_activity()?
+            .heatmap(to_activity_layer(layer)?, scale);
+        let bytes = match format {
+            None => image.pixels,
+            Some(format) => {
+                let format = ImageFormat::from_str(format).map_err(|_| {
+                    PyErr::new::<exceptions::PyValueError, _>("png or ppm formats are available")
+                })?;
+                image.encode(format)?
+            }
+        };
+        Ok(PyBytes::new(py, &bytes).into())
+    }
+
+    #[pyo3(signature = (enabled=true))]
+    fn track_ages(&mut self, enabled: bool) {
+        self.board.track_ages(enabled)
+    }
+
+    fn reset_ages(&mut self) {
+        self.board.reset_ages()
+    }
+
+    fn ages(&self, py: Python) -> PyResult<PyObject> {
+        let ages = self.board.ages().ok_or_else(|| {
+            PyErr::new::<exceptions::PyValueError, _>("Ages aren't counted, call track_ages first")
+        })?;
+        Ok(to_columns(py, ages, self.board.cells.len()))
+    }
+
+    fn get_age(&self, x: usize, y: usize) -> PyResult<Option<u16>> {
+        let size = self.board.cells.len();
+        if x >= size || y >= size {
+            return Err(PyErr::new::<exceptions::PyValueError, _>(format!(
+                "Cell ({}, {}) is outside the board of size {}",
+                x, y, size
+            )));
+        }
+        Ok(self.board.get_age(x, y))
+    }
+
+    #[getter]
+    fn hash(&self) -> u64 {
+        self.board.hash()
+    }
+
+    fn set_hash_history(&mut self, capacity: usize) {
+        self.board.set_hash_history(capacity)
+    }
+
+    #[pyo3(signature = (max_period=64))]
+    fn detect_cycle(&self, max_period: usize) -> Option<(u64, u64)> {
+        self.board
+            .detect_cycle(max_period)
+            .map(|cycle| (cycle.start, cycle.period))
+    }
+
+    #[pyo3(signature = (max_period=64))]
+    fn detect_translation(&self, py: Python, max_period: usize) -> Option<PyObject> {
+        self.board.detect_translation(max_period).map(|t| {
+            let key_vals = &[
+                ("start", t.start.to_object(py)),
+                ("period", t.period.to_object(py)),
+                ("dx", t.dx.to_object(py)),
+                ("dy", t.dy.to_object(py)),
+                ("speed", t.speed().to_object(py)),
+            ];
+            key_vals.into_py_dict(py).into()
+        })
+    }
+
+    #[pyo3(signature = (
+        radius=None,
+        max_period=32,
+        max_generations=64,
+        padding=None,
+        summary=false
+    ))]
+    fn census(
+        &self,
+        py: Python,
+        radius: Option<usize>,
+        max_period: usize,
+        max_generations: usize,
+        padding: Option<usize>,
+        summary: bool,
+    ) -> PyObject {
+        let options = CensusOptions {
+            radius,
+            max_period,
+            max_generations,
+            padding,
+        };
+        let census = self.board.census(&options);
+        if summary {
+            return census
+                .summary()
+                .iter()
+                .map(|entry| {
+                    let mut key_vals = kind_key_vals(py, &entry.kind);
+                    key_vals.extend([
+                        ("hash", entry.hash.to_object(py)),
+                        ("code", entry.code.to_object(py)),
+                        ("count", entry.count.to_object(py)),
+                        ("population", entry.population.to_object(py)),
+                    ]);
+                    key_vals.into_py_dict(py).into()
+                })
+                .collect::<Vec<PyObject>>()
+                .to_object(py);
+        }
+        census
+            .objects
+            .iter()
+            .map(|object| {
+                let b = object.bounding_box;
+                let mut key_vals = kind_key_vals(py, &object.kind);
+                key_vals.extend([
+                    ("bounding_box", (b.x, b.y, b.width, b.height).to_object(py)),
+                    ("population", object.population.to_object(py)),
+                    ("counts", object.counts.to_object(py)),
+                    ("hash", object.hash.to_object(py)),
+                    ("code", object.code.to_object(py)),
+                ]);
+                key_vals.into_py_dict(py).into()
+            })
+            .collect::<Vec<PyObject>>()
+            .to_object(py)
+    }
+
+    #[pyo3(signature = (region=None))]
+    fn pattern_code(&self, region: Option<(usize, usize, usize, usize)>) -> String {
+        let region = region.map_or(Region::full(self.board.cells.len()), to_region);
+        Pattern::from_region(&self.board.cells, region).code()
+    }
+
+    fn place_code(&mut self, code: &str, x: usize, y: usize) -> PyResult<()> {
+        let pattern = Pattern::from_code(code)
+            .map_err(|e| PyErr::new::<exceptions::PyValueError, _>(e.to_string()))?;
+        let states = self.board.config().states();
+        if pattern.as_slice().iter().any(|&state| state >= states) {
+            return Err(PyErr::new::<exceptions::PyValueError, _>(format!(
+                "Pattern has states the config doesn't support, {} states available",
+                states
+            )));
+        }
+        pattern.place(&mut self.board, x, y);
+        Ok(())
+    }
+
+    #[pyo3(signature = (region=None, margin=None, exact=false, max_conflicts=None))]
+    fn find_predecessor(
+        &self,
+        py: Python,
+        region: Option<(usize, usize, usize, usize)>,
+        margin: Option<usize>,
+        exact: bool,
+        max_conflicts: Option<u64>,
+    ) -> PyResult<PyObject> {
+        let region = region.map_or(Region::full(self.board.cells.len()), to_region);
+        let options = PredecessorOptions {
+            margin,
+            exact,
+            max_conflicts,
+        };
+        let predecessor = py
+            .allow_threads(|| self.board.find_predecessor(region, &options))
+            .map_err(|e| PyErr::new::<exceptions::PyValueError, _>(e.to_string()))?;
+        let config = self.board.config();
+        let (status, pattern) = match predecessor {
+            Predecessor::Found(pattern) => ("found", Some(pattern)),
+            Predecessor::None => ("none", None),
+            Predecessor::Unknown => ("unknown", None),
+        };
+        let garden_of_eden = status == "none"
+            && !exact
+            && margin.is_none_or(|margin| margin >= config.rr as usize);
+        let (rle, board) = match pattern {
+            Some(pattern) => {
+                let padding = config.rr as usize;
+                let size = pattern.width().max(pattern.height()) + 2 * padding;
+                let mut board = Board::new(size as u64, config.clone());
+                pattern.place(&mut board, padding, padding);
+                let board = Py::new(py, PyBoard { board })?;
+                (Some(pattern.to_rle(&config.to_string())), Some(board))
+            }
+            None => (None, None),
+        };
+        let key_vals = &[
+            ("status", status.to_object(py)),
+            ("garden_of_eden", garden_of_eden.to_object(py)),
+            ("rle", rle.to_object(py)),
+            ("board", board.to_object(py)),
+        ];
+        Ok(key_vals.into_py_dict(py).into())
+    }
+
+    #[pyo3(signature = (pattern, symmetries=None))]
+    fn find_pattern(
+        &self,
+        py: Python,
+        pattern: &PyAny,
+        symmetries: Option<Vec<String>>,
+    ) -> PyResult<PyObject> {
+        let template = match pattern.extract::<String>() {
+            Ok(code) => Template::from(
+                &Pattern::from_code(&code)
+                    .map_err(|e| PyErr::new::<exceptions::PyValueError, _>(e.to_string()))?,
+            ),
+            Err(_) => Template::from_rows(&pattern.extract::<Vec<Vec<Option<Cell>>>>()?),
+        };
+        let symmetries = match symmetries {
+            Some(names) => names
+                .iter()
+                .map(|name| {
+                    Symmetry::from_str(name).map_err(|_| {
+                        PyErr::new::<exceptions::PyValueError, _>(format!(
+                            "Unknown symmetry '{}'",
+                            name
+                        ))
+                    })
+                })
+                .collect::<PyResult<Vec<Symmetry>>>()?,
+            None => vec![Symmetry::Identity],
+        };
+        let matches = py.allow_threads(|| self.board.find_pattern(&template, &symmetries));
+        Ok(matches
+            .iter()
+            .map(|found| {
+                let key_vals = &[
+                    ("x", found.x.to_object(py)),
+                    ("y", found.y.to_object(py)),
+                    ("width", found.width.to_object(py)),
+                    ("height", found.height.to_object(py)),
+                    ("symmetry", found.symmetry.name().to_object(py)),
+                ];
+                key_vals.into_py_dict(py).into()
+            })
+            .collect::<Vec<PyObject>>()
+            .to_object(py))
+    }
+
+    #[pyo3(signature = (max_generations, max_period=64))]
+    fn run_until_periodic(
+        &mut self,
+        max_generations: usize,
+        max_period: usize,
+    ) -> Option<(u64, u64)> {
+        self.board
+            .run_until_periodic(max_generations, max_period)
+            .map(|cycle| (cycle.start, cycle.period))
+    }
+
+    fn compare(&self, py: Python, other: PyRef<PyBoard>) -> PyResult<PyObject> {
+        if self.board.cells.len() != other.board.cells.len() {
+            return Err(PyErr::new::<exceptions::PyValueError, _>(
+                "Compared boards differ in size",
+            ));
+        }
+        let diff = self.board.compare(&other.board);
+        let bounding_box = diff.bounding_box.map(|b| (b.x, b.y, b.width, b.height));
+        let key_vals = &[
+            ("distance", diff.distance.to_object(py)),
+            ("fraction", diff.fraction.to_object(py)),
+            ("live_distance", diff.live_distance.to_object(py)),
+            ("bounding_box", bounding_box.to_object(py)),
+        ];
+        Ok(key_vals.into_py_dict(py).into())
+    }
+
+    #[pyo3(signature = (generations, cells=None, count=1, radius=0, seed=0))]
+    fn twin_run(
+        &self,
+        py: Python,
+        generations: usize,
+        cells: Option<Vec<(usize, usize)>>,
+        count: usize,
+        radius: usize,
+        seed: u64,
+    ) -> PyObject {
+        let perturbation = match cells {
+            Some(cells) => Perturbation::Cells(cells),
+            None => Perturbation::Random {
+                count,
+                radius,
+                seed,
+            },
+        };
+        let curve = py.allow_threads(|| twin_run(&self.board, &perturbation, generations));
+        let points: Vec<PyObject> = curve
+            .points
+            .iter()
+            .map(|point| {
+                let key_vals = &[
+                    ("generation", point.generation.to_object(py)),
+                    ("distance", point.distance.to_object(py)),
+                    ("fraction", point.fraction.to_object(py)),
+                    ("radius", point.radius.to_object(py)),
+                ];
+                key_vals.into_py_dict(py).into()
+            })
+            .collect();
+        let key_vals = &[
+            ("perturbed", curve.perturbed.to_object(py)),
+            ("origin", curve.origin.to_object(py)),
+            ("points", points.to_object(py)),
+            ("healed_at", curve.healed_at.to_object(py)),
+            ("velocity", curve.velocity.to_object(py)),
+        ];
+        key_vals.into_py_dict(py).into()
+    }
+
+    #[pyo3(signature = (scale=1, palette=None, format=None))]
+    fn render_image(
+        &self,
+        py: Python,
+        scale: usize,
+        palette: Option<Vec<Vec<u8>>>,
+        format: Option<&str>,
+    ) -> PyResult<PyObject> {
+        let image = self.render(scale, palette)?;
+        let bytes = match format {
+            None => image.pixels,
+            Some(format) => {
+                let format = ImageFormat::from_str(format).map_err(|_| {
+                    PyErr::new::<exceptions::PyValueError, _>("png or ppm formats are available")
+                })?;
+                image.encode(format)?
+            }
+        };
+        Ok(PyBytes::new(py, &bytes).into())
+    }
+
+    #[pyo3(signature = (path, scale=1, palette=None))]
+    fn save_image(&self, path: &str, scale: usize, palette: Option<Vec<Vec<u8>>>) -> PyResult<()> {
+        Ok(self.render(scale, palette)?.save(path)?)
+    }
+
+    #[pyo3(signature = (
+        generations,
+        format="gif",
+        frame_skip=1,
+        scale=1,
+        delay_ms=100,
+        region=None,
+        max_bytes=None,
+        palette=None,
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn record(
+        &mut self,
+        py: Python,
+        generations: usize,
+        format: &str,
+        frame_skip: usize,
+        scale: usize,
+        delay_ms: u16,
+        region: Option<(usize, usize, usize, usize)>,
+        max_bytes: Option<usize>,
+        palette: Option<Vec<Vec<u8>>>,
+    ) -> PyResult<PyObject> {
+        let format = AnimationFormat::from_str(format).map_err(|_| {
+            PyErr::new::<exceptions::PyValueError, _>("gif or apng formats are available")
+        })?;
+        let options = to_record_options(generations, frame_skip, scale, delay_ms, region, max_bytes);
+        let palette = self.palette(palette)?;
+        let mut buffer = Vec::new();
+        self.board.record(&mut buffer, format, &palette, &options)?;
+        Ok(PyBytes::new(py, &buffer).into())
+    }
+
+    #[pyo3(signature = (
+        path,
+        generations,
+        frame_skip=1,
+        scale=1,
+        delay_ms=100,
+        region=None,
+        max_bytes=None,
+        palette=None,
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn save_recording(
+        &mut self,
+        path: &str,
+        generations: usize,
+        frame_skip: usize,
+        scale: usize,
+        delay_ms: u16,
+        region: Option<(usize, usize, usize, usize)>,
+        max_bytes: Option<usize>,
+        palette: Option<Vec<Vec<u8>>>,
+    ) -> PyResult<usize> {
+        let options = to_record_options(generations, frame_skip, scale, delay_ms, region, max_bytes);
+        let palette = self.palette(palette)?;
+        Ok(self.board.save_recording(path, &palette, &options)?)
+    }
+
+    #[pyo3(signature = (
+        target,
+        generations,
+        format=None,
+        fps=30,
+        scale=1,
+        region=None,
+        palette=None,
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn stream_video(
+        &mut self,
+        target: &PyAny,
+        generations: usize,
+        format: Option<&str>,
+        fps: u32,
+        scale: usize,
+        region: Option<(usize, usize, usize, usize)>,
+        palette: Option<Vec<Vec<u8>>>,
+    ) -> PyResult<usize> {
+        let options = VideoOptions {
+            fps,
+            scale,
+            region: region.map(to_region),
+        };
+        let palette = self.palette(palette)?;
+        let format = match format {
+            Some(format) => Some(VideoFormat::from_str(format).map_err(|_| {
+                PyErr::new::<exceptions::PyValueError, _>("y4m or rgb formats are available")
+            })?),
+            None => None,
+        };
+        if let Ok(path) = target.extract::<&str>() {
+            return match format {
+                None => Ok(self.board.save_video(path, &palette, &options, generations)?),
+                Some(format) => {
+                    let writer = BufWriter::new(File::create(path)?);
+                    Ok(self
+                        .board
+                        .stream_video(writer, format, &palette, &options, generations)?)
+                }
+            };
+        }
+        let writer = BufWriter::new(PyWriter {
+            file: target.into(),
+        });
+        let format = format.unwrap_or(VideoFormat::Y4m);
+        Ok(self
+            .board
+            .stream_video(writer, format, &palette, &options, generations)?)
+    }
+}
+
+impl PyBoard {
+    fn render(&self, scale: usize, palette: Option<Vec<Vec<u8>>>) -> PyResult<Image> {
+        Ok(self.board.render_image(&self.palette(palette)?, scale))
+    }
+
+    fn palette(&self, palette: Option<Vec<Vec<u8>>>) -> PyResult<Palette> {
+        match palette {
+            None => Ok(Palette::for_config(self.board.config())),
+            Some(colors) => to_palette(colors),
+        }
+    }
+
+    fn history(&self) -> PyResult<&StatsHistory> {
+        self.board.stats_history().ok_or_else(|| {
+            PyErr::new::<exceptions::PyValueError, _>(
+                "Statistics aren't recorded, call record_stats first",
+            )
+        })
+    }
+
+    fn recorded_activity(&self) -> PyResult<&Activity> {
+        self.board.activity().ok_or_else(|| {
+            PyErr::new::<exceptions::PyValueError, _>(
+                "Activity isn't recorded, call record_activity first",
+            )
+        })
+    }
+}
+
+/// Splits values stored column by column into a list of columns, `columns[x][y]` being the
+/// value of the cell at `(x, y)`.
+///
+fn to_columns<T: ToPyObject>(py: Python, values: &[T], size: usize) -> PyObject {
+    values
+        .chunks(size.max(1))
+        .map(|column| column.to_object(py))
+        .collect::<Vec<PyObject>>()
+        .to_object(py)
+}
+
+fn to_activity_layer(layer: &str) -> PyResult<ActivityLayer> {
+    ActivityLayer::from_str(layer).map_err(|_| {
+        PyErr::new::<exceptions::PyValueError, _>(
+            "changes, alive, last_active or reached layers are available",
+        )
+    })
+}
+
+/// Describes the kind of a census object with its `kind`, `period`, `dx` and `dy` keys.
+///
+fn kind_key_vals(py: Python, kind: &ObjectKind) -> Vec<(&'static str, PyObject)> {
+    let (dx, dy) = kind.displacement();
+    vec![
+        ("kind", kind.name().to_object(py)),
+        ("period", kind.period().to_object(py)),
+        ("dx", dx.to_object(py)),
+        ("dy", dy.to_object(py)),
+    ]
+}
+
+pub(crate) fn to_stats_dict(py: Python, stats: &Stats) -> PyObject {
+    let bounding_box = stats
+        .bounding_box
+        .map(|b| (b.x, b.y, b.width, b.height));
+    let key_vals = &[
+        ("generation", stats.generation.to_object(py)),
+        ("counts", stats.counts.to_object(py)),
+        ("population", stats.population.to_object(py)),
+        ("density", stats.density.to_object(py)),
+        ("births", stats.births.to_object(py)),
+        ("deaths", stats.deaths.to_object(py)),
+        ("survivors", stats.survivors.to_object(py)),
+        ("bounding_box", bounding_box.to_object(py)),
+    ];
+    key_vals.into_py_dict(py).into()
+}
+
+fn to_record_options(
+    generations: usize,
+    frame_skip: usize,
+    scale: usize,
+    delay_ms: u16,
+    region: Option<(usize, usize, usize, usize)>,
+    max_bytes: Option<usize>,
+) -> RecordOptions {
+    RecordOptions {
+        generations,
+        frame_skip,
+        scale,
+        delay_ms,
+        region: region.map(to_region),
+        max_bytes,
+    }
+}
+
+fn to_region((x, y, width, height): (usize, usize, usize, usize)) -> Region {
+    Region {
+        x,
+        y,
+        width,
+        height,
+    }
+}
+
+impl From<ImageError> for PyErr {
+    fn from(e: ImageError) -> Self {
+        match e {
+            ImageError::Io(e) => e.into(),
+            e => PyErr::new::<exceptions::PyValueError, _>(e.to_string()),
+        }
+    }
+}
+
+/// Adapts a Python file-like object with a `write` method to `std::io::Write`.
+///
+struct PyWriter {
+    file: PyObject,
+}
+
+impl Write for PyWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        Python::with_gil(|py| {
+            self.file
+                .call_method1(py, "write", (PyBytes::new(py, buf),))
+                .map_err(io::Error::other)?;
+            Ok(buf.len())
+        })
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Python::with_gil(|py| {
+            if self.file.as_ref(py).hasattr("flush").unwrap_or(false) {
+                self.file
+                    .call_method0(py, "flush")
+                    .map_err(io::Error::other)?;
+            }
+            Ok(())
+        })
+    }
+}
+
+/// Converts a list of `(r, g, b)` or `(r, g, b, a)` colours to a palette.
+///
+pub fn to_palette(colors: Vec<Vec<u8>>) -> PyResult<Palette> {
+    if colors.is_empty() {
+        return Err(PyErr::new::<exceptions::PyValueError, _>(
+            "Palette requires at least one colour",
+        ));
+    }
+    if colors.len() > MAX_COLORS {
+        return Err(PyErr::new::<exceptions::PyValueError, _>(format!(
+            "Palette supports at most {} colours",
+            MAX_COLORS
+        )));
+    }
+    colors
+        .into_iter()
+        .map(|c| match c[..] {
+            [r, g, b] => Ok([r, g, b, 255]),
+            [r, g, b, a] => Ok([r, g, b, a]),
+            _ => Err(PyErr::new::<exceptions::PyValueError, _>(
+                "Colours must be (r, g, b) or (r, g, b, a) tuples",
+            )),
+        })
+        .collect::<PyResult<Vec<_>>>()
+        .map(Palette::new)
 }
@@ -1,7 +1,16 @@
+use pyo3::exceptions;
 use pyo3::prelude::*;
 use pyo3::types::{IntoPyDict, PyType};
 
+use crate::ltl_engine::census::CensusOptions;
 use crate::ltl_engine::config::Config;
+use crate::ltl_engine::explore::{self, classify_rule, ExploreOptions, Sweep};
+use crate::ltl_engine::neighbourhood::Neighbourhood;
+use crate::ltl_engine::phase::{phase_diagram, phase_ranges, PhaseMetric};
+use crate::ltl_engine::search::{search_oscillators, SearchOptions};
+use crate::ltl_engine::soup::{soup_search, ReportFormat, SoupOptions};
+use crate::py_board::PyBoard;
+use crate::py_json;
 use crate::py_neighbourhood::PyNeighbourhood;
 
 #[pyclass]
@@ -71,10 +80,346 @@ impl PyConfig {
         })
     }
 
+    #[classmethod]
+    fn preset(_cls: &PyType, name: &str) -> PyResult<Self> {
+        match Config::preset(name) {
+            Some(config) => Ok(PyConfig { config }),
+            None => Err(PyErr::new::<exceptions::PyValueError, _>(format!(
+                "Unknown preset {}",
+                name
+            ))),
+        }
+    }
+
+    #[classmethod]
+    fn presets(_cls: &PyType) -> PyObject {
+        Python::with_gil(|py| {
+            Config::presets()
+                .iter()
+                .map(|preset| {
+                    let config = PyConfig {
+                        config: preset.config.clone(),
+                    };
+                    let key_vals = &[
+                        ("name", preset.name.to_object(py)),
+                        ("config", config.into_py(py)),
+                        ("source", preset.source.to_object(py)),
+                        ("description", preset.description.to_object(py)),
+                        ("board_size", preset.board_size.to_object(py)),
+                        ("density", preset.density.to_object(py)),
+                    ];
+                    key_vals.into_py_dict(py).into()
+                })
+                .collect::<Vec<PyObject>>()
+                .to_object(py)
+        })
+    }
+
     #[classmethod]
     fn randomize(_cls: &PyType, seed: Option<u64>) -> Self {
         PyConfig {
             config: Config::randomize(seed),
         }
     }
+
+    #[allow(clippy::too_many_arguments)]
+    #[pyo3(signature = (
+        soups=100,
+        first_seed=0,
+        board_size=64,
+        soup_size=16,
+        max_generations=1000,
+        max_period=32,
+        threads=None,
+        examples=3,
+        format="json"
+    ))]
+    fn soup_search(
+        &self,
+        py: Python,
+        soups: usize,
+        first_seed: u64,
+        board_size: u64,
+        soup_size: u64,
+        max_generations: usize,
+        max_period: usize,
+        threads: Option<usize>,
+        examples: usize,
+        format: &str,
+    ) -> PyResult<String> {
+        let format = format.parse::<ReportFormat>().map_err(|_| {
+            PyErr::new::<exceptions::PyValueError, _>("json or text formats are available")
+        })?;
+        let options = SoupOptions {
+            soups,
+            first_seed,
+            board_size,
+            soup_size,
+            max_generations,
+            max_period,
+            threads,
+            examples,
+            census: CensusOptions {
+                max_period,
+                ..CensusOptions::default()
+            },
+        };
+        let report = py.allow_threads(|| soup_search(&self.config, &options));
+        let mut buffer = Vec::new();
+        report.write(&mut buffer, format)?;
+        Ok(String::from_utf8_lossy(&buffer).into_owned())
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    #[pyo3(signature = (
+        soups=4,
+        first_seed=0,
+        board_size=64,
+        soup_size=16,
+        max_generations=500,
+        max_period=32,
+        census=true
+    ))]
+    fn classify_behaviour(
+        &self,
+        py: Python,
+        soups: usize,
+        first_seed: u64,
+        board_size: u64,
+        soup_size: u64,
+        max_generations: usize,
+        max_period: usize,
+        census: bool,
+    ) -> PyResult<PyObject> {
+        let options = to_explore_options(
+            soups,
+            first_seed,
+            board_size,
+            soup_size,
+            max_generations,
+            max_period,
+            None,
+            census,
+        );
+        let report = py.allow_threads(|| classify_rule(&self.config, &options));
+        py_json::serialize(py, &report)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    #[pyo3(signature = (
+        steps=8,
+        ss=None,
+        bb=None,
+        seeds=4,
+        first_seed=0,
+        board_size=64,
+        soup_size=64,
+        max_generations=500,
+        max_period=32,
+        threads=None,
+        csv=None,
+        heatmap=None,
+        metric="density",
+        scale=8
+    ))]
+    fn phase_diagram(
+        &self,
+        py: Python,
+        steps: u16,
+        ss: Option<Vec<(u16, u16)>>,
+        bb: Option<Vec<(u16, u16)>>,
+        seeds: usize,
+        first_seed: u64,
+        board_size: u64,
+        soup_size: u64,
+        max_generations: usize,
+        max_period: usize,
+        threads: Option<usize>,
+        csv: Option<&str>,
+        heatmap: Option<&str>,
+        metric: &str,
+        scale: usize,
+    ) -> PyResult<PyObject> {
+        let metric = metric.parse::<PhaseMetric>().map_err(|_| {
+            PyErr::new::<exceptions::PyValueError, _>(
+                "density, activity or extinction metrics are available",
+            )
+        })?;
+        let ss = ss.unwrap_or_else(|| phase_ranges(&self.config, steps));
+        let bb = bb.unwrap_or_else(|| phase_ranges(&self.config, steps));
+        let options = to_explore_options(
+            seeds,
+            first_seed,
+            board_size,
+            soup_size,
+            max_generations,
+            max_period,
+            threads,
+            false,
+        );
+        let diagram =
+            py.allow_threads(|| phase_diagram(&self.config, &ss, &bb, &options.soups));
+        if let Some(path) = csv {
+            diagram.save_csv(path)?;
+        }
+        if let Some(path) = heatmap {
+            diagram.heatmap(metric, scale).save(path)?;
+        }
+        py_json::serialize(py, &diagram)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    #[pyo3(signature = (
+        width=8,
+        height=8,
+        period=1,
+        max_solutions=1,
+        max_conflicts=None,
+        padding=None
+    ))]
+    fn search_oscillators(
+        &self,
+        py: Python,
+        width: usize,
+        height: usize,
+        period: usize,
+        max_solutions: usize,
+        max_conflicts: Option<u64>,
+        padding: Option<usize>,
+    ) -> PyResult<PyObject> {
+        let options = SearchOptions {
+            width,
+            height,
+            period,
+            max_solutions,
+            max_conflicts,
+        };
+        let report = py
+            .allow_threads(|| search_oscillators(&self.config, &options))
+            .map_err(|e| PyErr::new::<exceptions::PyValueError, _>(e.to_string()))?;
+        let padding = padding.unwrap_or(2 * self.config.rr as usize);
+        let oscillators = report
+            .oscillators
+            .iter()
+            .map(|oscillator| {
+                let board = PyBoard {
+                    board: oscillator.to_board(&self.config, padding),
+                };
+                let key_vals = &[
+                    ("period", oscillator.period.to_object(py)),
+                    ("code", oscillator.code.to_object(py)),
+                    ("population", oscillator.population().to_object(py)),
+                    ("rle", oscillator.to_rle(&self.config).to_object(py)),
+                    ("board", Py::new(py, board)?.to_object(py)),
+                ];
+                Ok(key_vals.into_py_dict(py).into())
+            })
+            .collect::<PyResult<Vec<PyObject>>>()?;
+        let key_vals = &[
+            ("oscillators", oscillators.to_object(py)),
+            ("exhausted", report.exhausted.to_object(py)),
+        ];
+        Ok(key_vals.into_py_dict(py).into())
+    }
+
+    #[classmethod]
+    #[allow(clippy::too_many_arguments)]
+    #[pyo3(signature = (
+        count=20,
+        seed=0,
+        rr=None,
+        cc=0,
+        mm=None,
+        nn=None,
+        steps=4,
+        soups=4,
+        board_size=64,
+        soup_size=16,
+        max_generations=500,
+        max_period=32,
+        census=true,
+        threads=None,
+        format="json"
+    ))]
+    fn sweep(
+        _cls: &PyType,
+        py: Python,
+        count: usize,
+        seed: u64,
+        rr: Option<Vec<u8>>,
+        cc: u8,
+        mm: Option<Vec<u8>>,
+        nn: Option<Vec<PyNeighbourhood>>,
+        steps: u16,
+        soups: usize,
+        board_size: u64,
+        soup_size: u64,
+        max_generations: usize,
+        max_period: usize,
+        census: bool,
+        threads: Option<usize>,
+        format: &str,
+    ) -> PyResult<String> {
+        let format = format.parse::<ReportFormat>().map_err(|_| {
+            PyErr::new::<exceptions::PyValueError, _>("json or text formats are available")
+        })?;
+        let sweep = match rr {
+            None => Sweep::Random { count, seed },
+            Some(rr) => Sweep::Grid {
+                rr,
+                cc,
+                mm: mm.unwrap_or_else(|| vec![0, 1]),
+                nn: nn.map_or_else(
+                    || vec![Neighbourhood::Moore, Neighbourhood::Neumann],
+                    |nn| nn.iter().map(|n| n.to_neighbourhood()).collect(),
+                ),
+                steps,
+            },
+        };
+        let options = to_explore_options(
+            soups,
+            0,
+            board_size,
+            soup_size,
+            max_generations,
+            max_period,
+            threads,
+            census,
+        );
+        let catalogue = py.allow_threads(|| explore::sweep(&sweep, &options));
+        let mut buffer = Vec::new();
+        catalogue.write(&mut buffer, format)?;
+        Ok(String::from_utf8_lossy(&buffer).into_owned())
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn to_explore_options(
+    soups: usize,
+    first_seed: u64,
+    board_size: u64,
+    soup_size: u64,
+    max_generations: usize,
+    max_period: usize,
+    threads: Option<usize>,
+    census: bool,
+) -> ExploreOptions {
+    ExploreOptions {
+        soups: SoupOptions {
+            soups,
+            first_seed,
+            board_size,
+            soup_size,
+            max_generations,
+            max_period,
+            threads,
+            census: CensusOptions {
+                max_period,
+                ..CensusOptions::default()
+            },
+            ..SoupOptions::default()
+        },
+        census,
+        ..ExploreOptions::default()
+    }
 }
@@ -0,0 +1,35 @@
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+use serde::Serialize;
+use serde_json::Value;
+
+/// Converts a JSON value to the equivalent Python object.
+///
+pub fn to_py(py: Python, value: &Value) -> PyObject {
+    match value {
+        Value::Null => py.None(),
+        Value::Bool(b) => b.to_object(py),
+        Value::Number(n) => match (n.as_u64(), n.as_i64()) {
+            (Some(u), _) => u.to_object(py),
+            (_, Some(i)) => i.to_object(py),
+            _ => n.as_f64().to_object(py),
+        },
+        Value::String(s) => s.to_object(py),
+        Value::Array(values) => PyList::new(py, values.iter().map(|v| to_py(py, v))).to_object(py),
+        Value::Object(map) => {
+            let dict = PyDict::new(py);
+            for (key, value) in map {
+                dict.set_item(key, to_py(py, value)).unwrap();
+            }
+            dict.to_object(py)
+        }
+    }
+}
+
+/// Converts a serializable value to Python objects through its JSON representation.
+///
+pub fn serialize<T: Serialize>(py: Python, value: &T) -> PyResult<PyObject> {
+    let value = serde_json::to_value(value)
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?;
+    Ok(to_py(py, &value))
+}
@@ -0,0 +1,321 @@
+use pyo3::exceptions;
+use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyType};
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use crate::ltl_engine::board::Board;
+use crate::ltl_engine::cells::Cell;
+use crate::ltl_engine::golly::{GollyError, GollyRule};
+use crate::ltl_engine::image::{ImageFormat, Palette};
+use crate::ltl_engine::isotropic::IsotropicRule;
+use crate::ltl_engine::table::TableRule;
+use crate::ltl_engine::Rule;
+use crate::py_board::{to_palette, to_stats_dict};
+use crate::py_config::PyConfig;
+use crate::py_neighbourhood::PyNeighbourhood;
+
+/// Rules other than Larger than Life that boards can run from Python, which has no generics.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub enum AnyRule {
+    Table(TableRule),
+    Isotropic(IsotropicRule),
+    Golly(GollyRule),
+}
+
+impl AnyRule {
+    /// Returns the palette boards of the rule render with by default.
+    ///
+    fn palette(&self) -> Palette {
+        match self {
+            AnyRule::Golly(rule) => rule.palette(),
+            rule => Palette::for_config(rule),
+        }
+    }
+}
+
+impl Rule for AnyRule {
+    fn states(&self) -> Cell {
+        match self {
+            AnyRule::Table(rule) => rule.states(),
+            AnyRule::Isotropic(rule) => rule.states(),
+            AnyRule::Golly(rule) => rule.states(),
+        }
+    }
+
+    fn radius(&self) -> usize {
+        match self {
+            AnyRule::Table(rule) => rule.radius(),
+            AnyRule::Isotropic(rule) => rule.radius(),
+            AnyRule::Golly(rule) => rule.radius(),
+        }
+    }
+
+    fn contains(&self, dx: isize, dy: isize) -> bool {
+        match self {
+            AnyRule::Table(rule) => rule.contains(dx, dy),
+            AnyRule::Isotropic(rule) => rule.contains(dx, dy),
+            AnyRule::Golly(rule) => rule.contains(dx, dy),
+        }
+    }
+
+    fn next_state(&self, state: Cell, neighbours: &[Cell]) -> Cell {
+        match self {
+            AnyRule::Table(rule) => rule.next_state(state, neighbours),
+            AnyRule::Isotropic(rule) => rule.next_state(state, neighbours),
+            AnyRule::Golly(rule) => rule.next_state(state, neighbours),
+        }
+    }
+
+    fn positional(&self) -> bool {
+        match self {
+            AnyRule::Table(rule) => rule.positional(),
+            AnyRule::Isotropic(rule) => rule.positional(),
+            AnyRule::Golly(rule) => rule.positional(),
+        }
+    }
+}
+
+impl std::fmt::Display for AnyRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AnyRule::Table(rule) => write!(f, "{}", rule),
+            AnyRule::Isotropic(rule) => write!(f, "{}", rule),
+            AnyRule::Golly(rule) => write!(f, "{}", rule),
+        }
+    }
+}
+
+#[pyclass]
+#[derive(Debug, Clone, PartialEq)]
+pub struct PyRule {
+    pub rule: AnyRule,
+}
+
+#[pymethods]
+impl PyRule {
+    #[classmethod]
+    fn parse(_cls: &PyType, text: &str) -> PyResult<Self> {
+        let rule = if text.contains('@') {
+            GollyRule::from_str(text)
+                .map(AnyRule::Golly)
+                .map_err(|e| e.to_string())
+        } else if text.trim_start().starts_with(['R', 'r']) {
+            TableRule::from_str(text)
+                .map(AnyRule::Table)
+                .map_err(|e| e.to_string())
+        } else {
+            IsotropicRule::from_str(text)
+                .map(AnyRule::Isotropic)
+                .map_err(|e| e.to_string())
+        };
+        Ok(PyRule {
+            rule: rule.map_err(PyErr::new::<exceptions::PyValueError, _>)?,
+        })
+    }
+
+    #[classmethod]
+    fn load(_cls: &PyType, path: &str) -> PyResult<Self> {
+        Ok(PyRule {
+            rule: AnyRule::Golly(GollyRule::load(path).map_err(to_py_err)?),
+        })
+    }
+
+    #[classmethod]
+    fn table(
+        _cls: &PyType,
+        rr: u8,
+        mm: u8,
+        nn: PyNeighbourhood,
+        states: usize,
+        transitions: HashMap<(usize, usize), usize>,
+    ) -> PyResult<Self> {
+        let rule = TableRule::from_transitions(rr, mm, nn.to_neighbourhood(), states, transitions)
+            .map_err(|e| PyErr::new::<exceptions::PyValueError, _>(e.to_string()))?;
+        Ok(PyRule {
+            rule: AnyRule::Table(rule),
+        })
+    }
+
+    #[classmethod]
+    #[pyo3(signature = (config, isotropic=false))]
+    fn from_config(_cls: &PyType, config: &PyConfig, isotropic: bool) -> PyResult<Self> {
+        let rule = if isotropic {
+            IsotropicRule::from_config(&config.config)
+                .map(AnyRule::Isotropic)
+                .map_err(|e| e.to_string())
+        } else {
+            TableRule::from_config(&config.config)
+                .map(AnyRule::Table)
+                .map_err(|e| e.to_string())
+        };
+        Ok(PyRule {
+            rule: rule.map_err(PyErr::new::<exceptions::PyValueError, _>)?,
+        })
+    }
+
+    #[getter]
+    fn states(&self) -> Cell {
+        self.rule.states()
+    }
+
+    #[getter]
+    fn palette(&self) -> Vec<Vec<u8>> {
+        self.rule
+            .palette()
+            .colors()
+            .iter()
+            .map(|c| c.to_vec())
+            .collect()
+    }
+
+    fn next_state(&self, state: Cell, neighbours: Vec<Cell>) -> PyResult<Cell> {
+        if state >= self.rule.states() {
+            return Err(PyErr::new::<exceptions::PyValueError, _>(format!(
+                "Rule doesnt support state {}",
+                state
+            )));
+        }
+        let r = self.rule.radius() as isize;
+        let expected = (-r..=r)
+            .flat_map(|dx| (-r..=r).map(move |dy| (dx, dy)))
+            .filter(|&(dx, dy)| self.rule.contains(dx, dy))
+            .count();
+        if neighbours.len() != expected {
+            return Err(PyErr::new::<exceptions::PyValueError, _>(format!(
+                "Expected {} neighbours, got {}",
+                expected,
+                neighbours.len()
+            )));
+        }
+        if let Some(neighbour) = neighbours.iter().find(|&&n| n >= self.rule.states()) {
+            return Err(PyErr::new::<exceptions::PyValueError, _>(format!(
+                "Rule doesnt support state {}",
+                neighbour
+            )));
+        }
+        Ok(self.rule.next_state(state, &neighbours))
+    }
+
+    fn __str__(&self) -> String {
+        self.rule.to_string()
+    }
+}
+
+/// A board running a `PyRule`, with the storage, statistics and rendering of `PyBoard`.
+///
+#[pyclass]
+#[derive(Debug, PartialEq)]
+pub struct PyRuleBoard {
+    pub board: Board<AnyRule>,
+}
+
+#[pymethods]
+impl PyRuleBoard {
+    #[new]
+    fn new(size: u64, rule: &PyRule) -> Self {
+        PyRuleBoard {
+            board: Board::new(size, rule.rule.clone()),
+        }
+    }
+
+    #[getter]
+    fn rule(&self) -> PyRule {
+        PyRule {
+            rule: self.board.config().clone(),
+        }
+    }
+
+    #[getter]
+    fn board(&self, py: Python) -> PyObject {
+        self.board.cells.to_vec().to_object(py)
+    }
+
+    fn set_cells(&mut self, cells: Vec<Cell>) -> PyResult<()> {
+        self.board
+            .set_cells(&cells)
+            .map_err(|e| PyErr::new::<exceptions::PyValueError, _>(e.to_string()))
+    }
+
+    fn reset(&mut self) {
+        self.board.reset();
+    }
+
+    fn get_cell(&self, x: usize, y: usize) -> Cell {
+        self.board.get_cell(x, y)
+    }
+
+    fn set_cell(&mut self, x: usize, y: usize, value: Cell) -> PyResult<()> {
+        if value >= self.board.config().states() {
+            return Err(PyErr::new::<exceptions::PyValueError, _>(format!(
+                "Rule doesnt support state {}",
+                value
+            )));
+        }
+        self.board.set_cell(x, y, value);
+        Ok(())
+    }
+
+    fn randomize(&mut self, seed: Option<u64>) {
+        self.board.randomize(seed)
+    }
+
+    fn update(&mut self) {
+        self.board.update()
+    }
+
+    #[getter]
+    fn generation(&self) -> u64 {
+        self.board.generation()
+    }
+
+    #[getter]
+    fn stats(&self, py: Python) -> PyObject {
+        to_stats_dict(py, self.board.stats())
+    }
+
+    #[getter]
+    fn hash(&self) -> u64 {
+        self.board.hash()
+    }
+
+    #[pyo3(signature = (max_period=64))]
+    fn detect_cycle(&self, max_period: usize) -> Option<(u64, u64)> {
+        self.board
+            .detect_cycle(max_period)
+            .map(|cycle| (cycle.start, cycle.period))
+    }
+
+    #[pyo3(signature = (scale=1, palette=None, format=None))]
+    fn render_image(
+        &self,
+        py: Python,
+        scale: usize,
+        palette: Option<Vec<Vec<u8>>>,
+        format: Option<&str>,
+    ) -> PyResult<PyObject> {
+        let palette = match palette {
+            None => self.board.config().palette(),
+            Some(colors) => to_palette(colors)?,
+        };
+        let image = self.board.render_image(&palette, scale);
+        let bytes = match format {
+            None => image.pixels,
+            Some(format) => {
+                let format = ImageFormat::from_str(format).map_err(|_| {
+                    PyErr::new::<exceptions::PyValueError, _>("png or ppm formats are available")
+                })?;
+                image.encode(format)?
+            }
+        };
+        Ok(PyBytes::new(py, &bytes).into())
+    }
+}
+
+fn to_py_err(e: GollyError) -> PyErr {
+    match e {
+        GollyError::Io(e) => e.into(),
+        e => PyErr::new::<exceptions::PyValueError, _>(e.to_string()),
+    }
+}